@@ -1,12 +1,20 @@
+use anyhow::Result;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
 
-use crate::git::Commit;
+use crate::contributor::ContributorResolver;
+use crate::git::{Commit, CommitOrder, GitRepo};
+use crate::locale::Locale;
 
-static CONVENTIONAL_COMMIT_PREFIX: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"(?i)^([a-z]+)(?:\(([a-z-]+)\))?(!)?(?:\s*):(?:\s*).+").unwrap());
+static CONVENTIONAL_COMMIT_PREFIX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^([a-z]+)(?:\(([a-z0-9_.-]+)\))?(!)?(?:\s*):(?:\s*)\S.*").unwrap()
+});
+
+static EMPTY_CONVENTIONAL_COMMIT: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^[a-z]+(?:\([a-z0-9_.-]+\))?!?\s*:\s*$").unwrap());
 
 static BREAKING_FOOTER: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?im)^BREAKING[- ]CHANGES?:").unwrap());
@@ -14,6 +22,21 @@ static BREAKING_FOOTER: Lazy<Regex> =
 static BREAKING_FOOTER_DESC: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?im)^BREAKING[- ]CHANGES?:[ \t]*(?s:(.+))").unwrap());
 
+#[derive(Error, Debug)]
+pub enum AnalyzerError {
+    #[error("{} commit(s) could not be categorized:\n{}", .0.len(), .0.join("\n"))]
+    Uncategorized(Vec<String>),
+
+    #[error("{} breaking change(s) have no description:\n{}", .0.len(), .0.join("\n"))]
+    UndescribedBreakingChanges(Vec<String>),
+
+    #[error("unknown commit category: {0}")]
+    UnknownCategory(String),
+
+    #[error("{} ignore pattern(s) are not valid regular expressions:\n{}", .0.len(), .0.join("\n"))]
+    InvalidIgnorePatterns(Vec<String>),
+}
+
 struct ConventionalCommit {
     commit_type: String,
     scope: Option<String>,
@@ -25,29 +48,288 @@ struct CommitMeta {
     type_: String,
     breaking: bool,
     breaking_description: Option<String>,
+    deprecation_description: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, PartialOrd, Ord)]
 pub enum CommitCategory {
+    #[serde(rename = "breaking")]
     Breaking,
+    #[serde(rename = "build")]
+    Build,
+    #[serde(rename = "chore")]
     Chore,
+    #[serde(rename = "ci")]
     CI,
+    #[serde(rename = "dependencies")]
     Dependencies,
+    #[serde(rename = "deprecations")]
+    Deprecation,
+    #[serde(rename = "docs")]
     Documentation,
+    #[serde(rename = "new-features")]
     Feature,
+    #[serde(rename = "bug-fixes")]
     Fix,
+    #[serde(rename = "hot-fixes")]
+    Hotfix,
+    #[serde(rename = "other")]
     Other,
+    #[serde(rename = "performance")]
     Performance,
+    #[serde(rename = "refactor")]
     Refactor,
+    #[serde(rename = "reverts")]
+    Revert,
+    #[serde(rename = "style")]
+    Style,
+    #[serde(rename = "test")]
     Test,
 }
 
+impl std::fmt::Display for CommitCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for CommitCategory {
+    type Err = AnalyzerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CommitCategory::ALL
+            .into_iter()
+            .find(|category| category.as_str() == s)
+            .ok_or_else(|| AnalyzerError::UnknownCategory(s.to_string()))
+    }
+}
+
+impl CommitCategory {
+    pub const ALL: [CommitCategory; 16] = [
+        CommitCategory::Breaking,
+        CommitCategory::Build,
+        CommitCategory::Chore,
+        CommitCategory::CI,
+        CommitCategory::Dependencies,
+        CommitCategory::Deprecation,
+        CommitCategory::Documentation,
+        CommitCategory::Feature,
+        CommitCategory::Fix,
+        CommitCategory::Hotfix,
+        CommitCategory::Other,
+        CommitCategory::Performance,
+        CommitCategory::Refactor,
+        CommitCategory::Revert,
+        CommitCategory::Style,
+        CommitCategory::Test,
+    ];
+
+    /// The canonical slug of the category, matching its serialized form.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CommitCategory::Breaking => "breaking",
+            CommitCategory::Build => "build",
+            CommitCategory::Chore => "chore",
+            CommitCategory::CI => "ci",
+            CommitCategory::Dependencies => "dependencies",
+            CommitCategory::Deprecation => "deprecations",
+            CommitCategory::Documentation => "docs",
+            CommitCategory::Feature => "new-features",
+            CommitCategory::Fix => "bug-fixes",
+            CommitCategory::Hotfix => "hot-fixes",
+            CommitCategory::Other => "other",
+            CommitCategory::Performance => "performance",
+            CommitCategory::Refactor => "refactor",
+            CommitCategory::Revert => "reverts",
+            CommitCategory::Style => "style",
+            CommitCategory::Test => "test",
+        }
+    }
+
+    /// The anchor the default template links to from the stats line, if any.
+    pub fn anchor(&self) -> Option<&'static str> {
+        match self {
+            CommitCategory::Breaking => Some("breaking-changes"),
+            CommitCategory::Feature => Some("new-features"),
+            CommitCategory::Fix => Some("bug-fixes"),
+            CommitCategory::Hotfix => Some("hot-fixes"),
+            CommitCategory::Deprecation => Some("deprecations"),
+            _ => None,
+        }
+    }
+
+    /// Whether the default template renders a section for this category.
+    pub fn is_rendered_by_default(&self) -> bool {
+        matches!(
+            self,
+            CommitCategory::Breaking
+                | CommitCategory::Feature
+                | CommitCategory::Fix
+                | CommitCategory::Hotfix
+                | CommitCategory::Performance
+                | CommitCategory::Revert
+                | CommitCategory::Dependencies
+                | CommitCategory::Deprecation
+        )
+    }
+}
+
+/// The semantic version bump a release warrants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Bump {
+    Patch,
+    Minor,
+    Major,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct CategorizedCommits {
     pub by_category: HashMap<CommitCategory, Vec<Commit>>,
     pub contributors: Vec<ContributorSummary>,
 }
 
+impl CategorizedCommits {
+    /// Combines the categorized commits of several repositories into one, naming the
+    /// repository each commit came from. Commits are interleaved by date in the given
+    /// order, and contributors to more than one repository are counted once, with their
+    /// commits summed.
+    pub fn merge(
+        repositories: Vec<(String, CategorizedCommits)>,
+        order: CommitOrder,
+        options: &AnalyzerOptions,
+    ) -> CategorizedCommits {
+        let mut by_category: HashMap<CommitCategory, Vec<Commit>> = HashMap::new();
+        let mut contributor_map: HashMap<String, ContributorSummary> = HashMap::new();
+
+        for (name, categorized) in repositories {
+            for (category, commits) in categorized.by_category {
+                by_category
+                    .entry(category)
+                    .or_default()
+                    .extend(commits.into_iter().map(|mut commit| {
+                        commit.repository = Some(name.clone());
+                        commit
+                    }));
+            }
+
+            for contributor in categorized.contributors {
+                match contributor_map.get_mut(&contributor.username) {
+                    Some(summary) => {
+                        summary.count += contributor.count;
+                        summary.first_commit_timestamp = summary
+                            .first_commit_timestamp
+                            .min(contributor.first_commit_timestamp);
+                        summary.last_commit_timestamp = summary
+                            .last_commit_timestamp
+                            .max(contributor.last_commit_timestamp);
+                    }
+                    None => {
+                        contributor_map.insert(contributor.username.clone(), contributor);
+                    }
+                }
+            }
+        }
+
+        for commits in by_category.values_mut() {
+            sort_commits(commits, order);
+        }
+
+        let mut contributors: Vec<_> = contributor_map.into_values().collect();
+        sort_contributors(&mut contributors, options.sort_contributors_by_name);
+
+        CategorizedCommits {
+            by_category,
+            contributors,
+        }
+    }
+
+    /// Fails if any commit could not be assigned a conventional category, listing
+    /// each offending commit by its short hash and subject.
+    pub fn ensure_categorized(&self) -> Result<()> {
+        match self.by_category.get(&CommitCategory::Other) {
+            Some(other) if !other.is_empty() => Err(AnalyzerError::Uncategorized(
+                other
+                    .iter()
+                    .map(|c| format!("  - {} {}", &c.hash[..7.min(c.hash.len())], c.first_line))
+                    .collect(),
+            )
+            .into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Usernames of every resolved contributor that is not a bot, most active first.
+    pub fn contributor_handles(&self) -> Vec<&str> {
+        self.contributors
+            .iter()
+            .filter(|c| !c.is_bot)
+            .map(|c| c.username.as_str())
+            .collect()
+    }
+
+    /// Fails if any breaking change lacks an explanation, either as a `BREAKING CHANGE`
+    /// footer or as a body accompanying a `!` commit.
+    pub fn ensure_breaking_described(&self) -> Result<()> {
+        // Breaking changes can be filed under any category, such as a dependency update
+        // or an inline feature, and a deprecation is listed twice, so each is checked once
+        let mut seen = HashSet::new();
+        let mut undescribed: Vec<&Commit> = self
+            .by_category
+            .values()
+            .flatten()
+            .filter(|c| c.breaking && seen.insert(c.hash.as_str()))
+            .filter(|c| {
+                c.breaking_description.is_none()
+                    && c.body.as_deref().is_none_or(|b| b.trim().is_empty())
+            })
+            .collect();
+        undescribed.sort_by(|a, b| {
+            b.timestamp
+                .cmp(&a.timestamp)
+                .then_with(|| a.hash.cmp(&b.hash))
+        });
+        let undescribed: Vec<String> = undescribed
+            .into_iter()
+            .map(|c| format!("  - {} {}", &c.hash[..7.min(c.hash.len())], c.first_line))
+            .collect();
+
+        if undescribed.is_empty() {
+            Ok(())
+        } else {
+            Err(AnalyzerError::UndescribedBreakingChanges(undescribed).into())
+        }
+    }
+
+    /// Recommends a major bump for a breaking change, a minor bump for a new feature and
+    /// a patch bump otherwise. A commit reverted within the same release no longer counts,
+    /// so a breaking change that was backed out does not warrant a major bump.
+    pub fn recommend_bump(&self) -> Bump {
+        let commits: Vec<&Commit> = self.by_category.values().flatten().collect();
+        let kept: Vec<&Commit> = commits
+            .iter()
+            .copied()
+            .filter(|c| !is_neutralized(c, &commits))
+            .collect();
+
+        if kept.iter().any(|c| c.breaking) {
+            Bump::Major
+        } else if kept.iter().any(|c| c.type_ == "feat") {
+            Bump::Minor
+        } else {
+            Bump::Patch
+        }
+    }
+}
+
+/// Whether a commit is reverted by another that still stands. Reverting a revert
+/// restores the original commit.
+fn is_neutralized(commit: &Commit, commits: &[&Commit]) -> bool {
+    commits
+        .iter()
+        .any(|revert| revert.reverts(commit) && !is_neutralized(revert, commits))
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct ContributorSummary {
     pub username: String,
@@ -57,21 +339,160 @@ pub struct ContributorSummary {
     pub is_ai: bool,
     pub first_commit_timestamp: i64,
     pub last_commit_timestamp: i64,
+    pub name: Option<String>,
+}
+
+/// Which category wins when a commit matches several, such as `feat(deps)!:`, which is
+/// a breaking change, a feature and a dependency update all at once.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum CategoryPrecedence {
+    /// Breaking changes, then dependency updates, then the commit type
+    #[default]
+    BreakingFirst,
+    /// Dependency updates, then breaking changes, then the commit type
+    DependenciesFirst,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AnalyzerOptions {
+    pub precedence: CategoryPrecedence,
+    /// List contributors alphabetically rather than by commit count
+    pub sort_contributors_by_name: bool,
+    /// Keep breaking changes within the section of their commit type
+    pub breaking_inline: bool,
+    /// Regular expressions matched against each commit subject, dropping any commit
+    /// that matches one
+    pub ignore_patterns: Vec<Regex>,
+}
+
+impl AnalyzerOptions {
+    /// Compiles the ignore patterns, failing with every pattern that is not a valid
+    /// regular expression and the reason it could not be compiled.
+    pub fn with_ignore_patterns(mut self, patterns: &[String]) -> Result<Self> {
+        let mut invalid = Vec::new();
+        for pattern in patterns {
+            match Regex::new(pattern) {
+                Ok(re) => self.ignore_patterns.push(re),
+                Err(err) => invalid.push(format!("  - {}: {}", pattern, err)),
+            }
+        }
+
+        if invalid.is_empty() {
+            Ok(self)
+        } else {
+            Err(AnalyzerError::InvalidIgnorePatterns(invalid).into())
+        }
+    }
 }
 
 pub struct CommitAnalyzer;
 
 impl CommitAnalyzer {
+    /// Walks the history of a repository between two references, resolves contributors
+    /// when a resolver is provided, and categorizes the resulting commits.
+    pub fn analyze_from_repo(
+        repo: &GitRepo,
+        from: Option<String>,
+        to: Option<String>,
+        resolver: Option<&mut ContributorResolver>,
+    ) -> Result<CategorizedCommits> {
+        Self::analyze_from_repo_with_options(repo, from, to, resolver, &AnalyzerOptions::default())
+    }
+
+    pub fn analyze_from_repo_with_options(
+        repo: &GitRepo,
+        from: Option<String>,
+        to: Option<String>,
+        resolver: Option<&mut ContributorResolver>,
+        options: &AnalyzerOptions,
+    ) -> Result<CategorizedCommits> {
+        let mut history = repo.history(from, to)?;
+
+        if let Some(resolver) = resolver {
+            resolver.resolve_contributors(&mut history);
+        }
+
+        Ok(Self::analyze_with_options(&history, options))
+    }
+
+    /// A plain-text summary of the release, such as "1 breaking change, 2 new features",
+    /// mirroring the stats line of the default template.
+    pub fn summarize(categorized: &CategorizedCommits) -> String {
+        Self::summarize_with_locale(categorized, Locale::default())
+    }
+
+    pub fn summarize_with_locale(categorized: &CategorizedCommits, locale: Locale) -> String {
+        let i18n = locale.translations();
+
+        [
+            (
+                CommitCategory::Breaking,
+                "breaking_change_stat",
+                "breaking_changes_stat",
+            ),
+            (CommitCategory::Hotfix, "hotfix_stat", "hotfixes_stat"),
+            (
+                CommitCategory::Feature,
+                "new_feature_stat",
+                "new_features_stat",
+            ),
+            (CommitCategory::Fix, "bug_fix_stat", "bug_fixes_stat"),
+        ]
+        .iter()
+        .filter_map(|(category, singular, plural)| {
+            let count = categorized.by_category.get(category).map_or(0, Vec::len);
+            match count {
+                0 => None,
+                1 => Some(format!("{} {}", count, i18n[singular])),
+                _ => Some(format!("{} {}", count, i18n[plural])),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+    }
+
     pub fn analyze(commits: &[Commit]) -> CategorizedCommits {
+        Self::analyze_with_options(commits, &AnalyzerOptions::default())
+    }
+
+    pub fn analyze_with_options(
+        commits: &[Commit],
+        options: &AnalyzerOptions,
+    ) -> CategorizedCommits {
+        let kept: Vec<&Commit> = commits
+            .iter()
+            .filter(|commit| {
+                let ignored = options
+                    .ignore_patterns
+                    .iter()
+                    .any(|pattern| pattern.is_match(&commit.first_line));
+                if ignored {
+                    log::info!(
+                        "ignoring commit {} matching an ignore pattern",
+                        &commit.hash[..7.min(commit.hash.len())]
+                    );
+                }
+                !ignored
+            })
+            .collect();
+
         let mut by_category: HashMap<CommitCategory, Vec<Commit>> = HashMap::new();
 
-        for commit in commits {
-            let (category, meta) = Self::categorize(commit);
+        for &commit in &kept {
+            let (category, meta) = Self::categorize(commit, options);
             let mut c = commit.clone();
             c.scope = meta.scope;
             c.type_ = meta.type_;
             c.breaking = meta.breaking;
             c.breaking_description = meta.breaking_description;
+            c.deprecation_description = meta.deprecation_description;
+            // A deprecation is listed in its own section as well as under its type
+            if c.deprecation_description.is_some() && category != CommitCategory::Deprecation {
+                by_category
+                    .entry(CommitCategory::Deprecation)
+                    .or_default()
+                    .push(c.clone());
+            }
             by_category.entry(category).or_default().push(c);
         }
 
@@ -79,13 +500,26 @@ impl CommitAnalyzer {
         for (category, commits) in &by_category {
             log::info!(
                 "  * {}: {} commit{}",
-                format!("{:?}", category).to_lowercase(),
+                category,
                 commits.len(),
                 if commits.len() == 1 { "" } else { "s" }
             );
         }
 
-        let contributors = Self::aggregate_contributors(commits);
+        let rendered = by_category
+            .iter()
+            .filter(|(category, _)| category.is_rendered_by_default())
+            .flat_map(|(_, commits)| commits.iter().map(|c| &c.hash))
+            .collect::<HashSet<_>>()
+            .len();
+        log::info!(
+            "total commits: {}, rendered: {}, other/ignored: {}",
+            kept.len(),
+            rendered,
+            kept.len() - rendered
+        );
+
+        let contributors = Self::aggregate_contributors(&kept, options.sort_contributors_by_name);
 
         CategorizedCommits {
             by_category,
@@ -93,7 +527,8 @@ impl CommitAnalyzer {
         }
     }
 
-    fn categorize(commit: &Commit) -> (CommitCategory, CommitMeta) {
+    fn categorize(commit: &Commit, options: &AnalyzerOptions) -> (CommitCategory, CommitMeta) {
+        let precedence = options.precedence;
         let parsed = Self::parse_conventional_commit(&commit.first_line);
         let scope = parsed
             .as_ref()
@@ -117,34 +552,106 @@ impl CommitAnalyzer {
             type_,
             breaking,
             breaking_description,
+            deprecation_description: Self::find_deprecated_trailer(commit).map(str::to_string),
         };
 
-        if breaking {
+        // An explicit Changelog trailer always wins over anything inferred
+        if let Some(category) = Self::find_changelog_trailer(commit) {
+            return (category, meta);
+        }
+
+        let dependencies = parsed
+            .as_ref()
+            .is_some_and(|p| p.scope.as_deref() == Some("deps"));
+
+        if dependencies && precedence == CategoryPrecedence::DependenciesFirst {
+            return (CommitCategory::Dependencies, meta);
+        }
+
+        if breaking && !options.breaking_inline {
             return (CommitCategory::Breaking, meta);
         }
 
+        if commit.is_revert() {
+            return (CommitCategory::Revert, meta);
+        }
+
+        if dependencies {
+            return (CommitCategory::Dependencies, meta);
+        }
+
         if let Some(ref parsed) = parsed {
-            if parsed.scope.as_deref() == Some("deps") {
-                return (CommitCategory::Dependencies, meta);
+            let category =
+                Self::category_for_type(&parsed.commit_type).unwrap_or(CommitCategory::Other);
+            // A breaking change is never hidden, so when its type has no section of its
+            // own it is still listed under Breaking Changes
+            if breaking && !category.is_rendered_by_default() {
+                return (CommitCategory::Breaking, meta);
             }
-
-            let category = match parsed.commit_type.as_str() {
-                "feat" => CommitCategory::Feature,
-                "fix" => CommitCategory::Fix,
-                "docs" => CommitCategory::Documentation,
-                "ci" => CommitCategory::CI,
-                "test" => CommitCategory::Test,
-                "perf" => CommitCategory::Performance,
-                "chore" => CommitCategory::Chore,
-                "refactor" => CommitCategory::Refactor,
-                _ => CommitCategory::Other,
-            };
             (category, meta)
+        } else if breaking {
+            (CommitCategory::Breaking, meta)
         } else {
+            // A prefix without a description is not a conventional commit, so it is
+            // kept under Other, where its subject can still be read
+            if EMPTY_CONVENTIONAL_COMMIT.is_match(&commit.first_line) {
+                log::warn!(
+                    "commit {} has a conventional prefix but no description: {}",
+                    &commit.hash[..7.min(commit.hash.len())],
+                    commit.first_line
+                );
+            }
             (CommitCategory::Other, meta)
         }
     }
 
+    fn category_for_type(commit_type: &str) -> Option<CommitCategory> {
+        let category = match commit_type {
+            "feat" => CommitCategory::Feature,
+            "fix" => CommitCategory::Fix,
+            "hotfix" => CommitCategory::Hotfix,
+            "docs" => CommitCategory::Documentation,
+            "build" => CommitCategory::Build,
+            "style" => CommitCategory::Style,
+            "ci" => CommitCategory::CI,
+            "test" => CommitCategory::Test,
+            "perf" => CommitCategory::Performance,
+            "chore" => CommitCategory::Chore,
+            "refactor" => CommitCategory::Refactor,
+            "revert" => CommitCategory::Revert,
+            _ => return None,
+        };
+        Some(category)
+    }
+
+    /// Resolves a `Changelog: <category>` trailer, accepting a category slug
+    /// (`bug-fixes`), a conventional type (`fix`) or its long form (`feature`)
+    fn find_changelog_trailer(commit: &Commit) -> Option<CommitCategory> {
+        let value = commit.trailers.iter().find_map(|trailer| match trailer {
+            crate::git::GitTrailer::Other { key, value }
+                if key.eq_ignore_ascii_case("changelog") =>
+            {
+                Some(value.trim().to_lowercase())
+            }
+            _ => None,
+        })?;
+
+        let category = match value.as_str() {
+            "feature" | "added" => Some(CommitCategory::Feature),
+            "fixed" => Some(CommitCategory::Fix),
+            "performance" => Some(CommitCategory::Performance),
+            other => Self::category_for_type(other).or_else(|| other.parse().ok()),
+        };
+        if category.is_none() {
+            log::warn!(
+                "commit {} has an unknown Changelog trailer: {}",
+                &commit.hash[..7.min(commit.hash.len())],
+                value
+            );
+        }
+        category
+    }
+
     fn find_breaking_trailer(commit: &Commit) -> Option<&str> {
         commit.trailers.iter().find_map(|trailer| {
             if let crate::git::GitTrailer::Other { key, value } = trailer {
@@ -157,6 +664,17 @@ impl CommitAnalyzer {
         })
     }
 
+    fn find_deprecated_trailer(commit: &Commit) -> Option<&str> {
+        commit.trailers.iter().find_map(|trailer| match trailer {
+            crate::git::GitTrailer::Other { key, value }
+                if key.eq_ignore_ascii_case("deprecated") =>
+            {
+                Some(value.as_str())
+            }
+            _ => None,
+        })
+    }
+
     fn extract_breaking_description(commit: &Commit) -> Option<String> {
         if let Some(value) = Self::find_breaking_trailer(commit) {
             return Some(value.to_string());
@@ -194,7 +712,7 @@ impl CommitAnalyzer {
         }
     }
 
-    fn aggregate_contributors(commits: &[Commit]) -> Vec<ContributorSummary> {
+    fn aggregate_contributors(commits: &[&Commit], by_name: bool) -> Vec<ContributorSummary> {
         let mut contributor_map: HashMap<String, ContributorSummary> = HashMap::new();
 
         for commit in commits {
@@ -216,17 +734,36 @@ impl CommitAnalyzer {
                         is_ai: contributor.is_ai,
                         first_commit_timestamp: commit.timestamp,
                         last_commit_timestamp: commit.timestamp,
+                        name: contributor.name.clone(),
                     });
             }
         }
 
         let mut contributors: Vec<_> = contributor_map.into_values().collect();
+        sort_contributors(&mut contributors, by_name);
+
+        contributors
+    }
+}
+
+/// Orders contributors alphabetically, or by commit count with ties broken by
+/// username, so the order is stable across runs.
+fn sort_contributors(contributors: &mut [ContributorSummary], by_name: bool) {
+    if by_name {
+        contributors.sort_by(|a, b| a.username.cmp(&b.username));
+    } else {
         contributors.sort_by(|a, b| {
             b.count
                 .cmp(&a.count)
                 .then_with(|| a.username.cmp(&b.username))
         });
+    }
+}
 
-        contributors
+/// Orders commits by date, keeping commits of the same date in their existing order.
+fn sort_commits(commits: &mut [Commit], order: CommitOrder) {
+    match order {
+        CommitOrder::NewestFirst => commits.sort_by_key(|c| std::cmp::Reverse(c.timestamp)),
+        CommitOrder::OldestFirst => commits.sort_by_key(|c| c.timestamp),
     }
 }
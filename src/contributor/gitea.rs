@@ -0,0 +1,229 @@
+use super::{Contributor, PlatformResolver};
+use crate::platform::Platform;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::time::Duration;
+
+pub struct GiteaResolver {
+    agent: ureq::Agent,
+    cache: HashMap<String, Option<Contributor>>,
+    gitea_token: Option<String>,
+    repo_owner: String,
+    repo_name: String,
+    api_url: String,
+}
+
+impl GiteaResolver {
+    pub fn new(platform: &Platform) -> Result<Self> {
+        match platform {
+            Platform::Gitea {
+                owner,
+                repo,
+                api_url,
+                token,
+                ..
+            } => Ok(Self {
+                agent: super::build_agent(super::DEFAULT_TIMEOUT),
+                cache: HashMap::new(),
+                gitea_token: token.clone(),
+                repo_owner: owner.clone(),
+                repo_name: repo.clone(),
+                api_url: api_url.clone(),
+            }),
+            _ => anyhow::bail!("GiteaResolver requires a Gitea platform"),
+        }
+    }
+
+    /// Abandons each API request after the given timeout, falling back as though it
+    /// had failed.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.agent = super::build_agent(timeout);
+        self
+    }
+
+    fn query_commit_api(&self, commit_hash: &str) -> Option<(String, String, Option<String>)> {
+        let url = format!(
+            "{}/repos/{}/{}/git/commits/{}",
+            self.api_url, self.repo_owner, self.repo_name, commit_hash
+        );
+
+        let mut request = self
+            .agent
+            .get(&url)
+            .header(
+                "User-Agent",
+                &format!("release-note/{}", env!("CARGO_PKG_VERSION")),
+            )
+            .header("Accept", "application/json");
+
+        if let Some(token) = &self.gitea_token {
+            request = request.header("Authorization", &format!("token {}", token));
+        }
+
+        match request.call() {
+            Ok(resp) => {
+                if let Ok(json) = resp.into_body().read_json::<serde_json::Value>()
+                    && let Some(login) = json.pointer("/author/login").and_then(|v| v.as_str())
+                    && !login.is_empty()
+                {
+                    let avatar_url = json
+                        .pointer("/author/avatar_url")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default();
+                    let name = json
+                        .pointer("/author/full_name")
+                        .and_then(|v| v.as_str())
+                        .filter(|name| !name.trim().is_empty())
+                        .map(str::to_string);
+                    return Some((login.to_string(), avatar_url.to_string(), name));
+                }
+                None
+            }
+            Err(ureq::Error::StatusCode(404)) => {
+                log::debug!(
+                    "commit {} not found in project on Gitea",
+                    &commit_hash[..7.min(commit_hash.len())]
+                );
+                None
+            }
+            Err(e) => {
+                log::warn!("failed to query Gitea commit API: {}", e);
+                None
+            }
+        }
+    }
+}
+
+impl PlatformResolver for GiteaResolver {
+    fn resolve(&mut self, commit_hash: Option<&str>, email: &str) -> Option<Contributor> {
+        if let Some(cached) = self.cache.get(email) {
+            return cached.clone();
+        }
+
+        let contributor = if let Some(username) = Self::resolve_ai_contributor(email) {
+            Some(Contributor {
+                username,
+                avatar_url: Self::generate_gravatar_url(email),
+                is_bot: false,
+                is_ai: true,
+                name: None,
+            })
+        } else {
+            commit_hash.and_then(|h| self.query_commit_api(h)).map(
+                |(username, avatar_url, name)| {
+                    let avatar_url = if avatar_url.is_empty() {
+                        Self::generate_gravatar_url(email)
+                    } else {
+                        avatar_url
+                    };
+
+                    log::info!("resolved contributor {} for email: {}", username, email);
+                    Contributor {
+                        username,
+                        avatar_url,
+                        is_bot: false,
+                        is_ai: false,
+                        name,
+                    }
+                },
+            )
+        };
+
+        if commit_hash.is_some() || contributor.is_some() {
+            self.cache.insert(email.to_string(), contributor.clone());
+        }
+        contributor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REPO_OWNER: &str = "shakespeare";
+    const REPO_NAME: &str = "globe-theatre";
+    const AVATAR_URL: &str = "https://gitea.com/avatars/2651292";
+
+    fn create_test_platform(api_url: &str) -> Platform {
+        Platform::Gitea {
+            url: format!("https://gitea.com/{}/{}", REPO_OWNER, REPO_NAME),
+            api_url: api_url.to_string(),
+            owner: REPO_OWNER.to_string(),
+            repo: REPO_NAME.to_string(),
+            token: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn resolves_gitea_username_using_commit_api() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "/repos/{}/{}/git/commits/599e13c",
+                REPO_OWNER, REPO_NAME
+            )))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "author": {
+                    "login": "ophelia",
+                    "avatar_url": AVATAR_URL,
+                    "full_name": "Ophelia",
+                }
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let platform = create_test_platform(&mock_server.uri());
+        let mut resolver = GiteaResolver::new(&platform).unwrap();
+
+        let (contributor1, contributor2) = tokio::task::spawn_blocking(move || {
+            let contributor1 = resolver.resolve(Some("599e13c"), "ophelia@globe-theatre.com");
+            let contributor2 = resolver.resolve(Some("599e13c"), "ophelia@globe-theatre.com");
+            (contributor1, contributor2)
+        })
+        .await
+        .unwrap();
+
+        let expected = Some(Contributor {
+            username: "ophelia".to_string(),
+            avatar_url: AVATAR_URL.to_string(),
+            is_bot: false,
+            is_ai: false,
+            name: Some("Ophelia".to_string()),
+        });
+        assert_eq!(contributor1, expected);
+        assert_eq!(contributor2, expected);
+    }
+
+    #[tokio::test]
+    async fn no_gitea_username_found_using_commit_api() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "/repos/{}/{}/git/commits/da49181",
+                REPO_OWNER, REPO_NAME
+            )))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&mock_server)
+            .await;
+
+        let platform = create_test_platform(&mock_server.uri());
+        let mut resolver = GiteaResolver::new(&platform).unwrap();
+
+        let contributor = tokio::task::spawn_blocking(move || {
+            resolver.resolve(Some("da49181"), "test@example.com")
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(contributor, None);
+    }
+}
@@ -1,16 +1,21 @@
-use super::{Contributor, PlatformResolver};
+use super::{Contributor, PlatformResolver, UserProfile};
 use crate::platform::Platform;
 use anyhow::Result;
+use std::cell::Cell;
 use std::collections::HashMap;
 use std::time::Duration;
 
 pub struct GitHubResolver {
     agent: ureq::Agent,
     cache: HashMap<String, Option<Contributor>>,
+    profiles: HashMap<String, Option<UserProfile>>,
     github_token: Option<String>,
     repo_owner: String,
     repo_name: String,
     api_url: String,
+    search_fallback: bool,
+    display_names: bool,
+    token_scopes_checked: Cell<bool>,
 }
 
 impl GitHubResolver {
@@ -23,34 +28,52 @@ impl GitHubResolver {
                 token,
                 ..
             } => Ok(Self {
-                agent: Self::build_agent(),
+                agent: super::build_agent(super::DEFAULT_TIMEOUT),
                 cache: HashMap::new(),
+                profiles: HashMap::new(),
                 github_token: token.clone(),
                 repo_owner: owner.clone(),
                 repo_name: repo.clone(),
                 api_url: api_url.clone(),
+                search_fallback: false,
+                display_names: false,
+                token_scopes_checked: Cell::new(false),
             }),
             _ => anyhow::bail!("GitHubResolver requires a GitHub platform"),
         }
     }
 
-    fn build_agent() -> ureq::Agent {
-        let config = ureq::Agent::config_builder()
-            .timeout_connect(Some(Duration::from_secs(10)))
-            .timeout_per_call(Some(Duration::from_secs(30)))
-            .build();
-        ureq::Agent::new_with_config(config)
+    /// Abandons each API request after the given timeout, falling back as though it
+    /// had failed.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.agent = super::build_agent(timeout);
+        self
     }
 
-    fn extract_username_from_noreply(email: &str) -> Option<String> {
-        email
+    /// Falls back to searching commits by author email when the commit API cannot
+    /// resolve the author. The search API has a much lower rate limit.
+    pub fn with_search_fallback(mut self, enabled: bool) -> Self {
+        self.search_fallback = enabled;
+        self
+    }
+
+    /// Fetches each contributor's display name, even when the avatar could be derived
+    /// from a noreply email without querying the user API.
+    pub fn with_display_names(mut self, enabled: bool) -> Self {
+        self.display_names = enabled;
+        self
+    }
+
+    /// Extracts the numeric user id and username from a noreply email of the form
+    /// `{id}+{username}@users.noreply.github.com`.
+    fn extract_username_from_noreply(email: &str) -> Option<(u64, String)> {
+        let (id, username) = email
             .strip_suffix("@users.noreply.github.com")?
-            .split('+')
-            .nth(1)
-            .map(str::to_string)
+            .split_once('+')?;
+        Some((id.parse().ok()?, username.to_string()))
     }
 
-    fn query_user_api(&self, username: &str) -> Option<(String, bool)> {
+    fn query_user_api(&self, username: &str) -> Option<UserProfile> {
         let url = format!("{}/users/{}", self.api_url, urlencoding::encode(username));
 
         let mut request = self
@@ -69,6 +92,7 @@ impl GitHubResolver {
 
         match request.call() {
             Ok(resp) => {
+                self.check_token_scopes(&resp);
                 if let Ok(json) = resp.into_body().read_json::<serde_json::Value>()
                     && let Some(avatar_url) = json.pointer("/avatar_url").and_then(|v| v.as_str())
                 {
@@ -77,8 +101,17 @@ impl GitHubResolver {
                         .and_then(|v| v.as_str())
                         .map(|t| t.eq_ignore_ascii_case("Bot"))
                         .unwrap_or(false);
-
-                    return Some((avatar_url.to_string(), is_bot));
+                    let name = json
+                        .pointer("/name")
+                        .and_then(|v| v.as_str())
+                        .filter(|name| !name.trim().is_empty())
+                        .map(str::to_string);
+
+                    return Some(UserProfile {
+                        avatar_url: avatar_url.to_string(),
+                        is_bot,
+                        name,
+                    });
                 }
                 None
             }
@@ -93,6 +126,41 @@ impl GitHubResolver {
         }
     }
 
+    /// Looks up a user's profile once, as several emails (such as an author and a
+    /// co-author) may resolve to the same username.
+    fn user_profile(&mut self, username: &str) -> Option<UserProfile> {
+        if let Some(profile) = self.profiles.get(username) {
+            return profile.clone();
+        }
+        let profile = self.query_user_api(username);
+        self.profiles.insert(username.to_string(), profile.clone());
+        profile
+    }
+
+    /// Whether a classic token's `X-OAuth-Scopes` header lacks the `repo` scope needed
+    /// to read commits from private repositories.
+    fn lacks_repo_scope(scopes: &str) -> bool {
+        !scopes.split(',').any(|scope| scope.trim() == "repo")
+    }
+
+    fn check_token_scopes(&self, resp: &ureq::http::Response<ureq::Body>) {
+        if self.github_token.is_none() || self.token_scopes_checked.replace(true) {
+            return;
+        }
+
+        // Fine-grained tokens do not report scopes, so only classic tokens are checked
+        if let Some(scopes) = resp
+            .headers()
+            .get("X-OAuth-Scopes")
+            .and_then(|v| v.to_str().ok())
+            && Self::lacks_repo_scope(scopes)
+        {
+            log::warn!(
+                "GITHUB_TOKEN lacks 'repo' scope; contributor resolution for private repos will fail"
+            );
+        }
+    }
+
     fn query_commit_api(&self, commit_hash: &str) -> Option<String> {
         let url = format!(
             "{}/repos/{}/{}/commits/{}",
@@ -115,6 +183,7 @@ impl GitHubResolver {
 
         match request.call() {
             Ok(resp) => {
+                self.check_token_scopes(&resp);
                 if let Ok(json) = resp.into_body().read_json::<serde_json::Value>()
                     && let Some(login) = json.pointer("/author/login").and_then(|v| v.as_str())
                 {
@@ -135,6 +204,49 @@ impl GitHubResolver {
             }
         }
     }
+
+    fn query_commit_search_api(&self, email: &str) -> Option<String> {
+        let url = format!(
+            "{}/search/commits?q={}&per_page=1",
+            self.api_url,
+            urlencoding::encode(&format!(
+                "author-email:{} repo:{}/{}",
+                email, self.repo_owner, self.repo_name
+            ))
+        );
+
+        let mut request = self
+            .agent
+            .get(&url)
+            .header(
+                "User-Agent",
+                &format!("release-note/{}", env!("CARGO_PKG_VERSION")),
+            )
+            .header("Accept", "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", "2022-11-28");
+
+        if let Some(token) = &self.github_token {
+            request = request.header("Authorization", &format!("Bearer {}", token));
+        }
+
+        match request.call() {
+            Ok(resp) => {
+                if let Ok(json) = resp.into_body().read_json::<serde_json::Value>()
+                    && let Some(login) = json
+                        .pointer("/items/0/author/login")
+                        .and_then(|v| v.as_str())
+                {
+                    return Some(login.to_string());
+                }
+                log::debug!("no commits found on GitHub for author {}", email);
+                None
+            }
+            Err(e) => {
+                log::warn!("failed to query GitHub commit search API: {}", e);
+                None
+            }
+        }
+    }
 }
 
 impl PlatformResolver for GitHubResolver {
@@ -145,14 +257,53 @@ impl PlatformResolver for GitHubResolver {
 
         let is_ai = Self::resolve_ai_contributor(email).is_some();
 
+        // The user id within a noreply email identifies the avatar, so neither the
+        // commit nor user API need to be queried
+        if !is_ai && let Some((id, username)) = Self::extract_username_from_noreply(email) {
+            let is_bot = username.ends_with("[bot]");
+            log::info!(
+                "resolved contributor {} for noreply email: {} (bot: {})",
+                username,
+                email,
+                is_bot
+            );
+
+            let name = if self.display_names {
+                self.user_profile(&username)
+                    .and_then(|profile| profile.name)
+            } else {
+                None
+            };
+
+            let contributor = Some(Contributor {
+                username,
+                avatar_url: format!("https://avatars.githubusercontent.com/u/{}?v=4", id),
+                is_bot,
+                is_ai,
+                name,
+            });
+            self.cache.insert(email.to_string(), contributor.clone());
+            return contributor;
+        }
+
         let username = Self::resolve_ai_contributor(email)
-            .or_else(|| Self::extract_username_from_noreply(email))
-            .or_else(|| commit_hash.and_then(|h| self.query_commit_api(h)));
+            .or_else(|| commit_hash.and_then(|h| self.query_commit_api(h)))
+            .or_else(|| {
+                (self.search_fallback && commit_hash.is_some())
+                    .then(|| self.query_commit_search_api(email))
+                    .flatten()
+            });
 
         let contributor = username.map(|username| {
-            let (avatar_url, is_bot) = self
-                .query_user_api(&username)
-                .unwrap_or_else(|| (Self::generate_gravatar_url(email), false));
+            let UserProfile {
+                avatar_url,
+                is_bot,
+                name,
+            } = self.user_profile(&username).unwrap_or_else(|| UserProfile {
+                avatar_url: Self::generate_gravatar_url(email),
+                is_bot: false,
+                name: None,
+            });
 
             log::info!(
                 "resolved contributor {} for email: {} (bot: {}, ai: {})",
@@ -167,6 +318,7 @@ impl PlatformResolver for GitHubResolver {
                 avatar_url,
                 is_bot,
                 is_ai,
+                name,
             }
         });
 
@@ -243,6 +395,7 @@ mod tests {
                 avatar_url: AVATAR_URL.to_string(),
                 is_bot: true,
                 is_ai: false,
+                name: None,
             })
         );
     }
@@ -293,11 +446,64 @@ mod tests {
             avatar_url: AVATAR_URL.to_string(),
             is_bot: false,
             is_ai: false,
+            name: None,
         });
         assert_eq!(contributor1, expected);
         assert_eq!(contributor2, expected);
     }
 
+    #[tokio::test]
+    async fn only_queries_user_api_once_for_emails_sharing_a_username() {
+        use wiremock::matchers::{method, path, path_regex};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path_regex(format!(
+                r"^/repos/{}/{}/commits/[a-f0-9]+$",
+                REPO_OWNER, REPO_NAME
+            )))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "author": {
+                    "login": "ophelia",
+                }
+            })))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/users/ophelia"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "avatar_url": AVATAR_URL
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let platform = create_test_platform(&mock_server.uri());
+        let mut resolver = GitHubResolver::new(&platform).unwrap();
+
+        let (author, coauthor) = tokio::task::spawn_blocking(move || {
+            let author = resolver.resolve(Some("3a1d4ed"), "ophelia@globe-theatre.com");
+            let coauthor = resolver.resolve(Some("cbd3d5a"), "ophelia@elsinore.dk");
+            (author, coauthor)
+        })
+        .await
+        .unwrap();
+
+        let expected = Some(Contributor {
+            username: "ophelia".to_string(),
+            avatar_url: AVATAR_URL.to_string(),
+            is_bot: false,
+            is_ai: false,
+            name: None,
+        });
+        assert_eq!(author, expected);
+        assert_eq!(coauthor, expected);
+    }
+
     #[tokio::test]
     async fn no_github_username_found_using_commit_api() {
         use wiremock::matchers::{method, path};
@@ -327,8 +533,8 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn resolves_username_from_github_noreply_email_without_commit_api_call() {
-        use wiremock::matchers::{method, path, path_regex};
+    async fn resolves_github_noreply_email_without_any_api_calls() {
+        use wiremock::matchers::{method, path_regex};
         use wiremock::{Mock, MockServer, ResponseTemplate};
 
         let mock_server = MockServer::start().await;
@@ -344,16 +550,76 @@ mod tests {
             .await;
 
         Mock::given(method("GET"))
-            .and(path("/users/prospero"))
+            .and(path_regex(r"^/users/"))
             .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
                 "avatar_url": AVATAR_URL
             })))
+            .expect(0)
             .mount(&mock_server)
             .await;
 
         let platform = create_test_platform(&mock_server.uri());
         let mut resolver = GitHubResolver::new(&platform).unwrap();
 
+        let (contributor, bot) = tokio::task::spawn_blocking(move || {
+            (
+                resolver.resolve(
+                    Some("127fca5"),
+                    "12345678+prospero@users.noreply.github.com",
+                ),
+                resolver.resolve(
+                    Some("b0a136d"),
+                    "49699333+dependabot[bot]@users.noreply.github.com",
+                ),
+            )
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            contributor,
+            Some(Contributor {
+                username: "prospero".to_string(),
+                avatar_url: "https://avatars.githubusercontent.com/u/12345678?v=4".to_string(),
+                is_bot: false,
+                is_ai: false,
+                name: None,
+            })
+        );
+        assert_eq!(
+            bot,
+            Some(Contributor {
+                username: "dependabot[bot]".to_string(),
+                avatar_url: "https://avatars.githubusercontent.com/u/49699333?v=4".to_string(),
+                is_bot: true,
+                is_ai: false,
+                name: None,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn resolves_display_name_of_noreply_email_when_enabled() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/users/prospero"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "avatar_url": AVATAR_URL,
+                "name": "Prospero of Milan"
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let platform = create_test_platform(&mock_server.uri());
+        let mut resolver = GitHubResolver::new(&platform)
+            .unwrap()
+            .with_display_names(true);
+
         let contributor = tokio::task::spawn_blocking(move || {
             resolver.resolve(
                 Some("127fca5"),
@@ -367,9 +633,10 @@ mod tests {
             contributor,
             Some(Contributor {
                 username: "prospero".to_string(),
-                avatar_url: AVATAR_URL.to_string(),
+                avatar_url: "https://avatars.githubusercontent.com/u/12345678?v=4".to_string(),
                 is_bot: false,
                 is_ai: false,
+                name: Some("Prospero of Milan".to_string()),
             })
         );
     }
@@ -418,6 +685,7 @@ mod tests {
                 avatar_url: AVATAR_URL.to_string(),
                 is_bot: false,
                 is_ai: true,
+                name: None,
             })
         );
     }
@@ -496,6 +764,7 @@ mod tests {
                 avatar_url: AVATAR_URL.to_string(),
                 is_bot: false,
                 is_ai: false,
+                name: None,
             })
         );
     }
@@ -542,7 +811,192 @@ mod tests {
                 avatar_url: "https://www.gravatar.com/avatar/7d6b35201428278c124e8bb39b932896790646965aec6df4b8673f0bc850d029?d=retro".to_string(),
                 is_bot: false,
                 is_ai: false,
+                name: None,
             })
         );
     }
+
+    #[tokio::test]
+    async fn falls_back_to_gravatar_when_user_api_times_out() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "/repos/{}/{}/commits/a1b2c3d",
+                REPO_OWNER, REPO_NAME
+            )))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "author": {
+                    "login": "hamlet"
+                }
+            })))
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/users/hamlet"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "avatar_url": AVATAR_URL }))
+                    .set_delay(Duration::from_secs(5)),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let platform = create_test_platform(&mock_server.uri());
+        let mut resolver = GitHubResolver::new(&platform)
+            .unwrap()
+            .with_timeout(Duration::from_millis(500));
+
+        let contributor = tokio::task::spawn_blocking(move || {
+            resolver.resolve(Some("a1b2c3d"), "hamlet@denmark.dk")
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            contributor,
+            Some(Contributor {
+                username: "hamlet".to_string(),
+                avatar_url: "https://www.gravatar.com/avatar/7d6b35201428278c124e8bb39b932896790646965aec6df4b8673f0bc850d029?d=retro".to_string(),
+                is_bot: false,
+                is_ai: false,
+                name: None,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn resolves_contributor_with_platform_from_config() {
+        use crate::platform::PlatformKind;
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "/repos/{}/{}/commits/e4f5a6b",
+                REPO_OWNER, REPO_NAME
+            )))
+            .and(header("Authorization", "Bearer ghp_macbeth"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "author": { "login": "macbeth" }
+            })))
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/users/macbeth"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "avatar_url": AVATAR_URL,
+                "type": "User",
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let platform = Platform::from_config(
+            PlatformKind::GitHub,
+            REPO_OWNER,
+            REPO_NAME,
+            &mock_server.uri(),
+            Some("ghp_macbeth".to_string()),
+        );
+        let mut resolver = GitHubResolver::new(&platform).unwrap();
+
+        let contributor = tokio::task::spawn_blocking(move || {
+            resolver.resolve(Some("e4f5a6b"), "macbeth@dunsinane.sc")
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            contributor,
+            Some(Contributor {
+                username: "macbeth".to_string(),
+                avatar_url: AVATAR_URL.to_string(),
+                is_bot: false,
+                is_ai: false,
+                name: None,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn resolves_github_username_using_commit_search_when_commit_api_fails() {
+        use wiremock::matchers::{method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "/repos/{}/{}/commits/7c3e2a1",
+                REPO_OWNER, REPO_NAME
+            )))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/search/commits"))
+            .and(query_param(
+                "q",
+                format!(
+                    "author-email:horatio@globe-theatre.com repo:{}/{}",
+                    REPO_OWNER, REPO_NAME
+                ),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "total_count": 1,
+                "items": [{
+                    "author": {
+                        "login": "horatio"
+                    }
+                }]
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/users/horatio"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "avatar_url": AVATAR_URL
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let platform = create_test_platform(&mock_server.uri());
+        let mut resolver = GitHubResolver::new(&platform)
+            .unwrap()
+            .with_search_fallback(true);
+
+        let contributor = tokio::task::spawn_blocking(move || {
+            resolver.resolve(Some("7c3e2a1"), "horatio@globe-theatre.com")
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            contributor,
+            Some(Contributor {
+                username: "horatio".to_string(),
+                avatar_url: AVATAR_URL.to_string(),
+                is_bot: false,
+                is_ai: false,
+                name: None,
+            })
+        );
+    }
+
+    #[test]
+    fn detects_token_scopes_without_repo_access() {
+        assert!(GitHubResolver::lacks_repo_scope("public_repo, read:user"));
+        assert!(GitHubResolver::lacks_repo_scope(""));
+        assert!(!GitHubResolver::lacks_repo_scope("read:user, repo"));
+    }
 }
@@ -1,6 +1,7 @@
-use super::{Contributor, PlatformResolver};
+use super::{Contributor, PlatformResolver, UserProfile};
 use crate::platform::Platform;
 use anyhow::Result;
+use std::cell::Cell;
 use std::collections::HashMap;
 use std::time::Duration;
 
@@ -8,6 +9,10 @@ pub struct GitLabResolver {
     agent: ureq::Agent,
     cache: HashMap<String, Option<Contributor>>,
     gitlab_token: Option<String>,
+    job_token: Option<String>,
+    job_token_warned: Cell<bool>,
+    merge_request_authors: bool,
+    merge_request_author_cache: HashMap<String, Contributor>,
     project_path: String,
     graphql_url: String,
     rest_api_url: String,
@@ -23,9 +28,13 @@ impl GitLabResolver {
                 token,
                 ..
             } => Ok(Self {
-                agent: Self::build_agent(),
+                agent: super::build_agent(super::DEFAULT_TIMEOUT),
                 cache: HashMap::new(),
                 gitlab_token: token.clone(),
+                job_token: None,
+                job_token_warned: Cell::new(false),
+                merge_request_authors: false,
+                merge_request_author_cache: HashMap::new(),
                 project_path: project_path.clone(),
                 graphql_url: graphql_url.clone(),
                 rest_api_url: api_url.clone(),
@@ -34,12 +43,47 @@ impl GitLabResolver {
         }
     }
 
-    fn build_agent() -> ureq::Agent {
-        let config = ureq::Agent::config_builder()
-            .timeout_connect(Some(Duration::from_secs(10)))
-            .timeout_per_call(Some(Duration::from_secs(30)))
-            .build();
-        ureq::Agent::new_with_config(config)
+    /// Abandons each API request after the given timeout, falling back as though it
+    /// had failed.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.agent = super::build_agent(timeout);
+        self
+    }
+
+    /// Authenticates with a CI/CD job token when no personal access token is available.
+    /// Job tokens cannot query users, so avatars fall back to Gravatar.
+    pub fn with_job_token(mut self, job_token: Option<String>) -> Self {
+        self.job_token = job_token;
+        self
+    }
+
+    /// Attributes each commit to the author of the merge request that introduced it,
+    /// rather than its git author. Squash commits are otherwise authored by whoever
+    /// merged them.
+    pub fn with_merge_request_authors(mut self, enabled: bool) -> Self {
+        self.merge_request_authors = enabled;
+        self
+    }
+
+    fn authorize<B>(&self, request: ureq::RequestBuilder<B>) -> ureq::RequestBuilder<B> {
+        if let Some(token) = &self.gitlab_token {
+            request.header("Authorization", &format!("Bearer {}", token))
+        } else if let Some(token) = &self.job_token {
+            request.header("JOB-TOKEN", token)
+        } else {
+            request
+        }
+    }
+
+    fn warn_job_token_forbidden(&self) {
+        if self.gitlab_token.is_none()
+            && self.job_token.is_some()
+            && !self.job_token_warned.replace(true)
+        {
+            log::warn!(
+                "CI_JOB_TOKEN cannot query GitLab users; set GITLAB_TOKEN with 'read_user' scope to resolve avatars"
+            );
+        }
     }
 
     fn extract_username_from_noreply(email: &str) -> Option<String> {
@@ -90,14 +134,10 @@ impl GitLabResolver {
             "variables": variables,
         });
 
-        let mut request = self.agent.post(&self.graphql_url).header(
+        let request = self.authorize(self.agent.post(&self.graphql_url).header(
             "User-Agent",
             &format!("release-note/{}", env!("CARGO_PKG_VERSION")),
-        );
-
-        if let Some(token) = &self.gitlab_token {
-            request = request.header("Authorization", &format!("Bearer {}", token));
-        }
+        ));
 
         match request.send_json(body) {
             Ok(resp) => {
@@ -150,6 +190,56 @@ impl GitLabResolver {
         }
     }
 
+    /// Returns the username of the author of the merge request that introduced a commit,
+    /// along with an avatar to fall back to if their profile cannot be queried. As the
+    /// merge request author's email is not exposed, the fallback is their merge request
+    /// avatar, or a Gravatar for their GitLab noreply address.
+    fn query_merge_request_author(&self, commit_hash: &str) -> Option<(String, String)> {
+        let url = format!(
+            "{}/projects/{}/repository/commits/{}/merge_requests",
+            self.rest_api_url,
+            urlencoding::encode(&self.project_path),
+            commit_hash
+        );
+
+        let request = self.authorize(self.agent.get(&url).header(
+            "User-Agent",
+            &format!("release-note/{}", env!("CARGO_PKG_VERSION")),
+        ));
+
+        match request.call() {
+            Ok(resp) => {
+                if let Ok(json) = resp.into_body().read_json::<serde_json::Value>()
+                    && let Some(username) =
+                        json.pointer("/0/author/username").and_then(|v| v.as_str())
+                {
+                    let avatar_url = json
+                        .pointer("/0/author/avatar_url")
+                        .and_then(|v| v.as_str())
+                        .map(str::to_string)
+                        .unwrap_or_else(|| {
+                            let id = json.pointer("/0/author/id").and_then(|v| v.as_u64());
+                            Self::generate_gravatar_url(&match id {
+                                Some(id) => format!("{id}-{username}@users.noreply.gitlab.com"),
+                                None => format!("{username}@users.noreply.gitlab.com"),
+                            })
+                        });
+                    return Some((username.to_string(), avatar_url));
+                }
+                log::debug!("no merge request found for commit {}", commit_hash);
+                None
+            }
+            Err(ureq::Error::StatusCode(404)) => {
+                log::debug!("commit {} not found on GitLab", commit_hash);
+                None
+            }
+            Err(e) => {
+                log::warn!("failed to query GitLab commit merge requests API: {}", e);
+                None
+            }
+        }
+    }
+
     fn query_user_search(&self, username: &str) -> Option<u64> {
         let search_url = format!(
             "{}/users?username={}",
@@ -157,14 +247,10 @@ impl GitLabResolver {
             urlencoding::encode(username)
         );
 
-        let mut request = self.agent.get(&search_url).header(
+        let request = self.authorize(self.agent.get(&search_url).header(
             "User-Agent",
             &format!("release-note/{}", env!("CARGO_PKG_VERSION")),
-        );
-
-        if let Some(token) = &self.gitlab_token {
-            request = request.header("Authorization", &format!("Bearer {}", token));
-        }
+        ));
 
         match request.call() {
             Ok(resp) => {
@@ -179,6 +265,11 @@ impl GitLabResolver {
                 }
                 None
             }
+            Err(ureq::Error::StatusCode(401 | 403)) => {
+                self.warn_job_token_forbidden();
+                log::debug!("authorization failed when searching for user {}", username);
+                None
+            }
             Err(ureq::Error::StatusCode(404)) => {
                 log::debug!("user {} not found on GitLab", username);
                 None
@@ -190,17 +281,13 @@ impl GitLabResolver {
         }
     }
 
-    fn query_user_details(&self, user_id: u64) -> Option<(String, bool)> {
+    fn query_user_details(&self, user_id: u64) -> Option<UserProfile> {
         let details_url = format!("{}/users/{}", self.rest_api_url, user_id);
 
-        let mut request = self.agent.get(&details_url).header(
+        let request = self.authorize(self.agent.get(&details_url).header(
             "User-Agent",
             &format!("release-note/{}", env!("CARGO_PKG_VERSION")),
-        );
-
-        if let Some(token) = &self.gitlab_token {
-            request = request.header("Authorization", &format!("Bearer {}", token));
-        }
+        ));
 
         match request.call() {
             Ok(resp) => {
@@ -215,14 +302,26 @@ impl GitLabResolver {
                         .and_then(|v| v.as_bool())
                         .unwrap_or(false);
 
-                    return Some((avatar_url, is_bot));
+                    let name = user
+                        .pointer("/name")
+                        .and_then(|v| v.as_str())
+                        .filter(|name| !name.trim().is_empty())
+                        .map(str::to_string);
+
+                    return Some(UserProfile {
+                        avatar_url,
+                        is_bot,
+                        name,
+                    });
                 }
                 None
             }
-            Err(ureq::Error::StatusCode(403)) => {
+            Err(ureq::Error::StatusCode(status @ (401 | 403))) => {
+                self.warn_job_token_forbidden();
                 log::warn!(
-                    "authorization failed when querying user details for id {} (403 Forbidden)",
-                    user_id
+                    "authorization failed when querying user details for id {} ({})",
+                    user_id,
+                    status
                 );
                 None
             }
@@ -237,16 +336,65 @@ impl GitLabResolver {
         }
     }
 
-    fn query_user_api(&self, username: &str) -> Option<(String, bool)> {
+    fn query_user_api(&self, username: &str) -> Option<UserProfile> {
         let user_id = self.query_user_search(username)?;
         self.query_user_details(user_id)
     }
+
+    fn contributor_for_username(
+        &self,
+        username: String,
+        email: &str,
+        fallback_avatar_url: impl FnOnce() -> String,
+    ) -> Contributor {
+        let UserProfile {
+            avatar_url,
+            is_bot,
+            name,
+        } = self
+            .query_user_api(&username)
+            .unwrap_or_else(|| UserProfile {
+                avatar_url: fallback_avatar_url(),
+                is_bot: false,
+                name: None,
+            });
+
+        log::info!(
+            "resolved contributor {} for email: {} (bot: {})",
+            username,
+            email,
+            is_bot
+        );
+
+        Contributor {
+            username,
+            avatar_url,
+            is_bot,
+            is_ai: false,
+            name,
+        }
+    }
 }
 
 impl PlatformResolver for GitLabResolver {
     fn resolve(&mut self, commit_hash: Option<&str>, email: &str) -> Option<Contributor> {
         log::info!("resolving contributor for email: {}", email);
 
+        // Merge request authors are resolved per commit, as the same email may have
+        // merged the work of many authors
+        if self.merge_request_authors
+            && let Some(hash) = commit_hash
+            && let Some((username, avatar_url)) = self.query_merge_request_author(hash)
+        {
+            if let Some(cached) = self.merge_request_author_cache.get(&username) {
+                return Some(cached.clone());
+            }
+            let contributor = self.contributor_for_username(username.clone(), email, || avatar_url);
+            self.merge_request_author_cache
+                .insert(username, contributor.clone());
+            return Some(contributor);
+        }
+
         if let Some(cached) = self.cache.get(email) {
             return cached.clone();
         }
@@ -257,6 +405,7 @@ impl PlatformResolver for GitLabResolver {
                 avatar_url: Self::generate_gravatar_url(email),
                 is_bot: false,
                 is_ai: true,
+                name: None,
             };
 
             log::info!("resolved AI contributor {} for email: {}", username, email);
@@ -270,23 +419,7 @@ impl PlatformResolver for GitLabResolver {
             .or_else(|| commit_hash.and_then(|h| self.query_commit_graphql(h)));
 
         let contributor = username.map(|username| {
-            let (avatar_url, is_bot) = self
-                .query_user_api(&username)
-                .unwrap_or_else(|| (Self::generate_gravatar_url(email), false));
-
-            log::info!(
-                "resolved contributor {} for email: {} (bot: {})",
-                username,
-                email,
-                is_bot
-            );
-
-            Contributor {
-                username,
-                avatar_url,
-                is_bot,
-                is_ai: false,
-            }
+            self.contributor_for_username(username, email, || Self::generate_gravatar_url(email))
         });
 
         if commit_hash.is_some() || contributor.is_some() {
@@ -429,6 +562,144 @@ mod tests {
                 avatar_url: AVATAR_URL.to_string(),
                 is_bot: false,
                 is_ai: false,
+                name: None,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn resolves_squash_commit_to_merge_request_author() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/api/graphql"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path(
+                "/api/v4/projects/shakespeare%2Fglobe-theatre/repository/commits/a1b2c3d/merge_requests",
+            ))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!([{
+                    "iid": 7,
+                    "author": {
+                        "id": 12345,
+                        "username": "ophelia"
+                    }
+                }])),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v4/users"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!([{
+                    "id": 12345,
+                    "username": "ophelia"
+                }])),
+            )
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v4/users/12345"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": 12345,
+                "username": "ophelia",
+                "avatar_url": AVATAR_URL,
+                "bot": false
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let platform = create_test_platform(
+            PROJECT_PATH,
+            &format!("{}/api/v4", mock_server.uri()),
+            &format!("{}/api/graphql", mock_server.uri()),
+        );
+        let mut resolver = GitLabResolver::new(&platform)
+            .unwrap()
+            .with_merge_request_authors(true);
+
+        let contributor = tokio::task::spawn_blocking(move || {
+            resolver.resolve(Some("a1b2c3d"), "hamlet@globe-theatre.com")
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            contributor,
+            Some(Contributor {
+                username: "ophelia".to_string(),
+                avatar_url: AVATAR_URL.to_string(),
+                is_bot: false,
+                is_ai: false,
+                name: None,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_merge_request_author_avatar_when_user_api_fails() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path(
+                "/api/v4/projects/shakespeare%2Fglobe-theatre/repository/commits/a1b2c3d/merge_requests",
+            ))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!([{
+                    "iid": 7,
+                    "author": {
+                        "id": 12345,
+                        "username": "ophelia",
+                        "avatar_url": AVATAR_URL
+                    }
+                }])),
+            )
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v4/users"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&mock_server)
+            .await;
+
+        let platform = create_test_platform(
+            PROJECT_PATH,
+            &format!("{}/api/v4", mock_server.uri()),
+            &format!("{}/api/graphql", mock_server.uri()),
+        );
+        let mut resolver = GitLabResolver::new(&platform)
+            .unwrap()
+            .with_merge_request_authors(true);
+
+        let contributor = tokio::task::spawn_blocking(move || {
+            resolver.resolve(Some("a1b2c3d"), "hamlet@globe-theatre.com")
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            contributor,
+            Some(Contributor {
+                username: "ophelia".to_string(),
+                avatar_url: AVATAR_URL.to_string(),
+                is_bot: false,
+                is_ai: false,
+                name: None,
             })
         );
     }
@@ -490,6 +761,63 @@ mod tests {
                 avatar_url: AVATAR_URL.to_string(),
                 is_bot: false,
                 is_ai: false,
+                name: None,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn captures_display_name_from_gitlab_user_details() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v4/users"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!([{
+                    "id": 22222,
+                    "username": "ophelia",
+                    "avatar_url": AVATAR_URL
+                }])),
+            )
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v4/users/22222"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": 22222,
+                "username": "ophelia",
+                "avatar_url": AVATAR_URL,
+                "bot": false,
+                "name": "Ophelia"
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let platform = create_test_platform(
+            PROJECT_PATH,
+            &format!("{}/api/v4", mock_server.uri()),
+            &format!("{}/api/graphql", mock_server.uri()),
+        );
+        let mut resolver = GitLabResolver::new(&platform).unwrap();
+
+        let contributor = tokio::task::spawn_blocking(move || {
+            resolver.resolve(Some("e4f5g6h"), "123456-ophelia@users.noreply.gitlab.com")
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            contributor,
+            Some(Contributor {
+                username: "ophelia".to_string(),
+                avatar_url: AVATAR_URL.to_string(),
+                is_bot: false,
+                is_ai: false,
+                name: Some("Ophelia".to_string()),
             })
         );
     }
@@ -528,6 +856,7 @@ mod tests {
                 avatar_url: "https://www.gravatar.com/avatar/cd29c5ac348a026a3ec5286890908fffb5bf6ab77f20672171be323a70c95026?d=retro".to_string(),
                 is_bot: false,
                 is_ai: true,
+                name: None,
             })
         );
     }
@@ -603,6 +932,7 @@ mod tests {
             avatar_url: AVATAR_URL.to_string(),
             is_bot: false,
             is_ai: false,
+            name: None,
         });
         assert_eq!(contributor1, expected);
         assert_eq!(contributor2, expected);
@@ -697,6 +1027,7 @@ mod tests {
                 avatar_url: AVATAR_URL.to_string(),
                 is_bot: true,
                 is_ai: false,
+                name: None,
             })
         );
     }
@@ -786,6 +1117,71 @@ mod tests {
                 avatar_url: "https://www.gravatar.com/avatar/7d6b35201428278c124e8bb39b932896790646965aec6df4b8673f0bc850d029?d=retro".to_string(),
                 is_bot: false,
                 is_ai: false,
+                name: None,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_gravatar_when_job_token_is_forbidden_from_user_api() {
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/api/graphql"))
+            .and(header("JOB-TOKEN", "job-token-123"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": {
+                    "project": {
+                        "repository": {
+                            "commit": {
+                                "author": {
+                                    "username": "hamlet"
+                                }
+                            }
+                        }
+                    }
+                }
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v4/users"))
+            .and(header("JOB-TOKEN", "job-token-123"))
+            .respond_with(ResponseTemplate::new(403).set_body_json(serde_json::json!({
+                "message": "403 Forbidden"
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let platform = create_test_platform(
+            PROJECT_PATH,
+            &format!("{}/api/v4", mock_server.uri()),
+            &format!("{}/api/graphql", mock_server.uri()),
+        );
+        let mut resolver = GitLabResolver::new(&platform)
+            .unwrap()
+            .with_job_token(Some("job-token-123".to_string()));
+
+        let contributor = tokio::task::spawn_blocking(move || {
+            resolver.resolve(Some("a1b2c3d"), "hamlet@denmark.dk")
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            contributor,
+            Some(Contributor {
+                username: "hamlet".to_string(),
+                avatar_url: "https://www.gravatar.com/avatar/7d6b35201428278c124e8bb39b932896790646965aec6df4b8673f0bc850d029?d=retro".to_string(),
+                is_bot: false,
+                is_ai: false,
+                name: None,
             })
         );
     }
@@ -905,6 +1301,7 @@ mod tests {
                 avatar_url: AVATAR_URL.to_string(),
                 is_bot: false,
                 is_ai: false,
+                name: None,
             })
         );
     }
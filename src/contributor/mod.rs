@@ -1,11 +1,16 @@
+mod gitea;
 mod github;
 mod gitlab;
+mod noop;
 
+pub use gitea::GiteaResolver;
 pub use github::GitHubResolver;
 pub use gitlab::GitLabResolver;
+pub use noop::NoopResolver;
 
 use anyhow::Result;
 use serde::Serialize;
+use std::time::Duration;
 
 use crate::git::Commit;
 use crate::platform::Platform;
@@ -16,6 +21,19 @@ pub struct Contributor {
     pub avatar_url: String,
     pub is_bot: bool,
     pub is_ai: bool,
+    /// Display name from the platform's user profile, when one is set
+    pub name: Option<String>,
+}
+
+/// How long a single platform API request may take before it is abandoned.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Profile details of a user fetched from a platform's user API.
+#[derive(Clone)]
+pub(crate) struct UserProfile {
+    pub avatar_url: String,
+    pub is_bot: bool,
+    pub name: Option<String>,
 }
 
 pub trait PlatformResolver {
@@ -85,23 +103,65 @@ pub trait PlatformResolver {
     }
 }
 
+#[derive(Debug, Clone, Default)]
+pub struct ResolverOptions {
+    /// Search commits by author email when the commit API cannot resolve an author.
+    /// Only supported on GitHub.
+    pub search_fallback: bool,
+    /// Attribute commits to the author of their merge request, such as the real author
+    /// of a squash commit. Only supported on GitLab.
+    pub merge_request_authors: bool,
+    /// Fetch each contributor's display name from the platform's user API.
+    pub display_names: bool,
+    /// How long each platform API request may take, defaulting to [`DEFAULT_TIMEOUT`].
+    pub timeout: Option<Duration>,
+}
+
 pub struct ContributorResolver {
     platform_resolver: Box<dyn PlatformResolver>,
 }
 
 impl ContributorResolver {
-    pub fn new(platform: &Platform) -> Result<Option<Self>> {
+    /// Builds a resolver for the given platform, returning `None` when the platform is
+    /// not recognized and contributor resolution should be skipped.
+    pub fn from_platform(platform: &Platform) -> Result<Option<Self>> {
+        Self::from_platform_with_options(platform, &ResolverOptions::default())
+    }
+
+    pub fn from_platform_with_options(
+        platform: &Platform,
+        options: &ResolverOptions,
+    ) -> Result<Option<Self>> {
+        let timeout = options.timeout.unwrap_or(DEFAULT_TIMEOUT);
         match platform {
             Platform::GitHub { .. } => {
                 log::info!("project is hosted on GitHub");
                 Ok(Some(Self {
-                    platform_resolver: Box::new(GitHubResolver::new(platform)?),
+                    platform_resolver: Box::new(
+                        GitHubResolver::new(platform)?
+                            .with_timeout(timeout)
+                            .with_search_fallback(options.search_fallback)
+                            .with_display_names(options.display_names),
+                    ),
                 }))
             }
             Platform::GitLab { .. } => {
                 log::info!("project is hosted on GitLab");
                 Ok(Some(Self {
-                    platform_resolver: Box::new(GitLabResolver::new(platform)?),
+                    platform_resolver: Box::new(
+                        GitLabResolver::new(platform)?
+                            .with_timeout(timeout)
+                            .with_job_token(gitlab_job_token(platform))
+                            .with_merge_request_authors(options.merge_request_authors),
+                    ),
+                }))
+            }
+            Platform::Gitea { .. } => {
+                log::info!("project is hosted on Gitea");
+                Ok(Some(Self {
+                    platform_resolver: Box::new(
+                        GiteaResolver::new(platform)?.with_timeout(timeout),
+                    ),
                 }))
             }
             Platform::Unknown => {
@@ -111,6 +171,19 @@ impl ContributorResolver {
         }
     }
 
+    /// Builds a resolver around an explicit platform resolver, bypassing platform
+    /// detection.
+    pub fn with_resolver(platform_resolver: impl PlatformResolver + 'static) -> Self {
+        Self {
+            platform_resolver: Box::new(platform_resolver),
+        }
+    }
+
+    #[deprecated(note = "use `ContributorResolver::from_platform` instead")]
+    pub fn new(platform: &Platform) -> Result<Option<Self>> {
+        Self::from_platform(platform)
+    }
+
     pub fn resolve_contributors(&mut self, commits: &mut [Commit]) {
         use crate::git::GitTrailer;
 
@@ -123,8 +196,8 @@ impl ContributorResolver {
             }
 
             for trailer in &commit.trailers {
-                if let GitTrailer::CoAuthoredBy { name: _, email } = trailer
-                    && let Some(email_addr) = email
+                if let GitTrailer::CoAuthoredBy { .. } = trailer
+                    && let Some(email_addr) = trailer.email()
                     && let Some(contributor) = self.platform_resolver.resolve(None, email_addr)
                     && !commit
                         .contributors
@@ -137,3 +210,64 @@ impl ContributorResolver {
         }
     }
 }
+
+/// Builds the HTTP agent a resolver reuses across all of its requests. ureq already
+/// routes requests through any proxy set by the `ALL_PROXY`, `HTTPS_PROXY` or
+/// `HTTP_PROXY` environment variables, honouring `NO_PROXY`.
+///
+/// Each request is abandoned after the timeout, so a hung API falls back to the same
+/// defaults as a failed one rather than stalling the release note.
+fn build_agent(timeout: Duration) -> ureq::Agent {
+    let config = ureq::Agent::config_builder()
+        .timeout_connect(Some(timeout))
+        .timeout_per_call(Some(timeout))
+        .build();
+    ureq::Agent::new_with_config(config)
+}
+
+/// The CI/CD job token of a GitLab pipeline, used when no other token is available.
+/// Only sent to the GitLab instance running the pipeline.
+fn gitlab_job_token(platform: &Platform) -> Option<String> {
+    if platform.token().is_some() {
+        return None;
+    }
+
+    let server_url = std::env::var("CI_SERVER_URL").ok()?;
+    if !platform
+        .url()
+        .starts_with(&format!("{}/", server_url.trim_end_matches('/')))
+    {
+        return None;
+    }
+
+    let token = std::env::var("CI_JOB_TOKEN").ok()?;
+    log::info!("no GITLAB_TOKEN found; falling back to CI_JOB_TOKEN");
+    Some(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn abandons_requests_that_exceed_the_timeout() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(5)))
+            .mount(&mock_server)
+            .await;
+
+        let agent = build_agent(Duration::from_millis(200));
+        let url = format!("{}/users/hamlet", mock_server.uri());
+
+        let result = tokio::task::spawn_blocking(move || agent.get(&url).call())
+            .await
+            .unwrap();
+
+        assert!(matches!(result, Err(ureq::Error::Timeout(_))));
+    }
+}
@@ -0,0 +1,11 @@
+use super::{Contributor, PlatformResolver};
+
+/// A resolver that never resolves a contributor. Useful for exercising the analyzer
+/// without any network access.
+pub struct NoopResolver;
+
+impl PlatformResolver for NoopResolver {
+    fn resolve(&mut self, _commit_hash: Option<&str>, _email: &str) -> Option<Contributor> {
+        None
+    }
+}
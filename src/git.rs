@@ -1,23 +1,53 @@
-use std::collections::HashMap;
-use std::path::{Path, PathBuf};
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
 
 use anyhow::{Context, Result};
-use git2::{DiffOptions, Oid, Repository, Sort};
+use git2::{BranchType, DiffOptions, Oid, Repository, Sort};
 use once_cell::sync::Lazy;
 use regex::Regex;
 use semver::Version;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::contributor::Contributor;
+use crate::platform::Platform;
 
 #[derive(Error, Debug)]
 pub enum GitRepoError {
-    #[error("repository is a shallow clone with incomplete history")]
-    ShallowClone,
-
     #[error("repository is empty and contains no commits")]
     EmptyRepository,
+
+    #[error(
+        "repository object database error: the repository may be corrupted or incompletely cloned; try 'git fsck' or 'git fetch --unshallow'"
+    )]
+    CorruptObjectDatabase(#[source] git2::Error),
+
+    #[error(
+        "failed to find commit: object is missing from the repository; try 'git fsck' or 'git fetch --unshallow'"
+    )]
+    CommitNotFound(#[source] git2::Error),
+
+    #[error("tag {0} does not exist; run 'git fetch --tags' if it was created remotely")]
+    TagNotFound(String),
+
+    #[error("tag {0} is not a semantic version")]
+    NotSemverTag(String),
+
+    #[error("path {0} lies outside the repository")]
+    PathOutsideRepository(PathBuf),
+}
+
+impl GitRepoError {
+    fn from_revwalk(err: git2::Error) -> anyhow::Error {
+        match err.code() {
+            git2::ErrorCode::NotFound => GitRepoError::CommitNotFound(err).into(),
+            _ if err.class() == git2::ErrorClass::Odb => {
+                GitRepoError::CorruptObjectDatabase(err).into()
+            }
+            _ => err.into(),
+        }
+    }
 }
 
 static GIT_TRAILER: Lazy<Regex> =
@@ -29,36 +59,206 @@ static LINKED_ISSUE: Lazy<Regex> = Lazy::new(|| {
     ).unwrap()
 });
 
+static JIRA_KEY: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b([A-Z][A-Z0-9]+)-(\d+)\b").unwrap());
+
+static GITHUB_MERGE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^Merge pull request #(\d+) from (\S+)").unwrap());
+
+static TAG_SIGNATURE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?s)-----BEGIN (?:PGP|SSH) SIGNATURE-----.*?-----END (?:PGP|SSH) SIGNATURE-----\s*",
+    )
+    .unwrap()
+});
+
+static GITHUB_REVERT: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^Revert "(.+)""#).unwrap());
+
+static REVERTED_COMMIT: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^This reverts commit ([0-9a-f]{7,40})").unwrap());
+
+static REVERTS_PULL_REQUEST: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^Reverts\s+(?:[\w.-]+/[\w.-]+)?#(\d+)\s*$").unwrap());
+
+static GITHUB_SQUASH: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(.+?)\s+\((#\d+(?:\s*,\s*#\d+)*)\)$").unwrap());
+
+static BLANK_LINE_RUN: Lazy<Regex> = Lazy::new(|| Regex::new(r"\n{3,}").unwrap());
+
+static BLANK_OR_WHITESPACE_LINES: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\n(?:[ \t]*\n)+").unwrap());
+
 struct Tag {
     name: String,
     oid: Oid,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CommitOrder {
+    #[default]
+    NewestFirst,
+    OldestFirst,
+}
+
+/// Which of a commit's dates is used as its timestamp.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum DateSource {
+    /// When the commit was last applied, such as by a rebase or cherry-pick
+    #[default]
+    Committer,
+    /// When the change was originally written
+    Author,
+}
+
+/// How runs of blank lines within a commit body are normalized.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum BlankLinePolicy {
+    /// Collapse runs of empty lines to a single blank line, at most two newlines in a row
+    #[default]
+    #[value(name = "collapse-to-2")]
+    CollapseTo2,
+    /// Remove empty and whitespace-only lines, so no more than one newline is ever in a
+    /// row and paragraphs run onto consecutive lines
+    #[value(name = "collapse-to-1")]
+    CollapseTo1,
+    /// Keep blank lines exactly as written
+    Preserve,
+}
+
+/// Counts of commits within the range omitted from the history, by reason.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DroppedCommits {
+    pub outside_path: usize,
+    pub excluded_author: usize,
+    pub duplicate_subject: usize,
+    pub skipped: usize,
+}
+
+impl DroppedCommits {
+    pub fn total(&self) -> usize {
+        self.outside_path + self.excluded_author + self.duplicate_subject + self.skipped
+    }
+}
+
 pub struct GitRepo {
     repo: Repository,
     path_filter: Option<PathBuf>,
+    excluded_paths: Vec<PathBuf>,
     origin_url: Option<String>,
+    proxy_url: Option<String>,
+    commit_order: CommitOrder,
+    excluded_authors: Vec<String>,
+    blank_line_policy: BlankLinePolicy,
+    jira_projects: Vec<String>,
+    keep_issue_lines: bool,
+    dedupe_subjects: bool,
+    attribute_to_committer: bool,
+    hidden_refs: Vec<String>,
+    first_release_depth: Option<usize>,
+    date_source: DateSource,
+    dropped: Cell<DroppedCommits>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+/// Chooses the proxy for a remote from the environment, read through `env`: the
+/// `HTTPS_PROXY` or `HTTP_PROXY` variable matching the remote's scheme, unless its host
+/// is listed in `NO_PROXY`. SSH and local remotes never use a proxy.
+pub fn proxy_for_remote(remote_url: &str, env: impl Fn(&str) -> Option<String>) -> Option<String> {
+    let (keys, rest) = if let Some(rest) = remote_url.strip_prefix("https://") {
+        (["HTTPS_PROXY", "https_proxy"], rest)
+    } else if let Some(rest) = remote_url.strip_prefix("http://") {
+        (["HTTP_PROXY", "http_proxy"], rest)
+    } else {
+        return None;
+    };
+
+    let authority = rest.split('/').next().unwrap_or_default();
+    let host_port = authority.rsplit('@').next().unwrap_or(authority);
+    let host = host_port
+        .rsplit_once(':')
+        .map_or(host_port, |(host, _)| host)
+        .to_ascii_lowercase();
+    let no_proxy = ["NO_PROXY", "no_proxy"]
+        .iter()
+        .find_map(|key| env(key))
+        .unwrap_or_default();
+    let bypassed = no_proxy.split(',').map(str::trim).any(|entry| {
+        let entry = entry.split(':').next().unwrap_or_default();
+        let entry = entry.trim_start_matches('.').to_ascii_lowercase();
+        entry == "*"
+            || (!entry.is_empty() && (host == entry || host.ends_with(&format!(".{entry}"))))
+    });
+    if bypassed {
+        return None;
+    }
+
+    keys.iter()
+        .filter_map(|key| env(key))
+        .find(|value| !value.trim().is_empty())
+}
+
+/// Hides any credentials within a proxy URL, so it can be logged.
+fn redact_userinfo(url: &str) -> String {
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return url.to_string();
+    };
+    let authority_end = rest.find('/').unwrap_or(rest.len());
+    match rest[..authority_end].rfind('@') {
+        Some(at) => format!("{}://***@{}", scheme, &rest[at + 1..]),
+        None => url.to_string(),
+    }
+}
+
+fn strip_signature(message: &str) -> String {
+    TAG_SIGNATURE.replace_all(message, "").trim().to_string()
+}
+
+fn proxy_options(proxy_url: Option<&str>) -> git2::ProxyOptions<'_> {
+    let mut options = git2::ProxyOptions::new();
+    match proxy_url {
+        Some(url) => options.url(url),
+        None => options.auto(),
+    };
+    options
+}
+
+/// Callbacks offering the token as the password when the remote accepts plaintext
+/// credentials, and the SSH agent when it requests a key.
+fn remote_callbacks(token: Option<&str>) -> git2::RemoteCallbacks<'_> {
+    let mut callbacks = git2::RemoteCallbacks::new();
+    if let Some(token) = token {
+        callbacks.credentials(move |_url, username, allowed| {
+            if allowed.contains(git2::CredentialType::USER_PASS_PLAINTEXT) {
+                git2::Cred::userpass_plaintext("oauth2", token)
+            } else if allowed.contains(git2::CredentialType::SSH_KEY) {
+                git2::Cred::ssh_key_from_agent(username.unwrap_or("git"))
+            } else {
+                Err(git2::Error::from_str(
+                    "the remote does not accept token credentials",
+                ))
+            }
+        });
+    }
+    callbacks
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "kebab-case")]
 pub enum GitTrailer {
     #[serde(rename_all = "kebab-case")]
     CoAuthoredBy {
         name: String,
-        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         email: Option<String>,
     },
     #[serde(rename_all = "kebab-case")]
     ReviewedBy {
         name: String,
-        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         email: Option<String>,
     },
     #[serde(rename_all = "kebab-case")]
     SignedOffBy {
         name: String,
-        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         email: Option<String>,
     },
     Other {
@@ -83,6 +283,35 @@ impl GitTrailer {
         }
     }
 
+    pub fn key(&self) -> &str {
+        match self {
+            GitTrailer::CoAuthoredBy { .. } => "Co-authored-by",
+            GitTrailer::ReviewedBy { .. } => "Reviewed-by",
+            GitTrailer::SignedOffBy { .. } => "Signed-off-by",
+            GitTrailer::Other { key, .. } => key,
+        }
+    }
+
+    /// The name of the person a trailer refers to. `None` for trailers without a person.
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            GitTrailer::CoAuthoredBy { name, .. }
+            | GitTrailer::ReviewedBy { name, .. }
+            | GitTrailer::SignedOffBy { name, .. } => Some(name),
+            GitTrailer::Other { .. } => None,
+        }
+    }
+
+    /// The email address of the person a trailer refers to, when one was given.
+    pub fn email(&self) -> Option<&str> {
+        match self {
+            GitTrailer::CoAuthoredBy { email, .. }
+            | GitTrailer::ReviewedBy { email, .. }
+            | GitTrailer::SignedOffBy { email, .. } => email.as_deref(),
+            GitTrailer::Other { .. } => None,
+        }
+    }
+
     fn parse_name_email_trailer<F>(value: String, constructor: F) -> Self
     where
         F: FnOnce(String, Option<String>) -> Self,
@@ -109,11 +338,27 @@ impl GitTrailer {
     }
 }
 
+impl std::fmt::Display for GitTrailer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GitTrailer::CoAuthoredBy { name, email }
+            | GitTrailer::ReviewedBy { name, email }
+            | GitTrailer::SignedOffBy { name, email } => match email {
+                Some(email) if email != name => write!(f, "{}: {} <{}>", self.key(), name, email),
+                _ => write!(f, "{}: {}", self.key(), name),
+            },
+            GitTrailer::Other { key, value } => write!(f, "{}: {}", key, value),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct LinkedIssue {
     pub number: u32,
     pub owner: Option<String>,
     pub repo: Option<String>,
+    /// The Jira project key, set only for issues detected from a Jira key (e.g. PROJ-123)
+    pub project: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -126,31 +371,174 @@ pub struct Commit {
     pub type_: String,
     pub breaking: bool,
     pub breaking_description: Option<String>,
+    /// What a `Deprecated` footer says is deprecated by this commit.
+    pub deprecation_description: Option<String>,
+    /// Replaces the subject and body in the release note, given by a `Release-Note` trailer.
+    pub release_note: Option<String>,
+    /// The pull request reverted by a GitHub revert, given as `Reverts owner/repo#N`.
+    pub reverted_pull_request: Option<u32>,
     pub trailers: Vec<GitTrailer>,
     pub linked_issues: Vec<LinkedIssue>,
     pub author: String,
     pub email: String,
+    pub committer: String,
+    pub committer_email: String,
     pub contributors: Vec<Contributor>,
     pub timestamp: i64,
+    pub parent_hashes: Vec<String>,
+    /// The repository the commit came from, when notes from several are merged.
+    pub repository: Option<String>,
 }
 
 impl Commit {
-    fn from_git2_commit(commit: &git2::Commit) -> Self {
+    /// Whether the commit carries a `Signed-off-by` trailer, as required by the
+    /// Developer Certificate of Origin (DCO).
+    pub fn is_signed_off(&self) -> bool {
+        self.trailers
+            .iter()
+            .any(|t| matches!(t, GitTrailer::SignedOffBy { .. }))
+    }
+
+    /// Whether the author opted the commit out of the release note, with either a
+    /// `Release-Note: skip` trailer or `[skip changelog]` in the subject.
+    pub fn is_skipped(&self) -> bool {
+        self.first_line
+            .to_ascii_lowercase()
+            .contains("[skip changelog]")
+            || self.trailers.iter().any(|t| {
+                matches!(t, GitTrailer::Other { key, value }
+                    if key.eq_ignore_ascii_case("release-note")
+                        && value.trim().eq_ignore_ascii_case("skip"))
+            })
+    }
+
+    /// Whether this is a revert generated by git or GitHub, such as `Revert "feat: ..."`.
+    pub fn is_revert(&self) -> bool {
+        GITHUB_REVERT.is_match(&self.first_line)
+    }
+
+    /// The subject of the commit this reverts, taken from a `Revert "..."` subject.
+    pub fn reverted_subject(&self) -> Option<&str> {
+        GITHUB_REVERT
+            .captures(&self.first_line)
+            .and_then(|caps| caps.get(1))
+            .map(|m| m.as_str())
+    }
+
+    /// The hash of the commit this reverts, given by git as `This reverts commit <sha>`.
+    pub fn reverted_hash(&self) -> Option<&str> {
+        self.body
+            .as_deref()
+            .and_then(|body| REVERTED_COMMIT.captures(body))
+            .and_then(|caps| caps.get(1))
+            .map(|m| m.as_str())
+    }
+
+    /// Whether this commit reverts another, matched by its subject or by the hash
+    /// recorded in the body of the revert.
+    pub fn reverts(&self, other: &Commit) -> bool {
+        self.reverted_subject() == Some(other.first_line.as_str())
+            || self
+                .reverted_hash()
+                .is_some_and(|hash| other.hash.starts_with(hash))
+    }
+
+    /// The pull request number from a GitHub merge or squash-merge commit subject.
+    pub fn pull_request_number(&self) -> Option<u32> {
+        self.authored_pull_requests().first().copied()
+    }
+
+    /// Every pull request referenced by a GitHub merge, or by the trailing `(#1, #2)` of
+    /// a squash-merged subject.
+    pub fn authored_pull_requests(&self) -> Vec<u32> {
+        if let Some(caps) = GITHUB_MERGE.captures(&self.first_line) {
+            return caps[1].parse().into_iter().collect();
+        }
+
+        GITHUB_SQUASH
+            .captures(&self.first_line)
+            .map(|caps| {
+                caps[2]
+                    .split(',')
+                    .filter_map(|number| number.trim().trim_start_matches('#').parse().ok())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Summarizes a GitHub merge or squash-merge commit as `PR #N: {title}`, taking the
+    /// title of a merge commit from its body, or its branch if it has no body. Any other
+    /// commit is described by its first line.
+    pub fn merge_description(&self, platform: &Platform) -> String {
+        if !matches!(platform, Platform::GitHub { .. }) {
+            return self.first_line.clone();
+        }
+
+        if let Some(caps) = GITHUB_MERGE.captures(&self.first_line) {
+            let title = self
+                .body
+                .as_deref()
+                .and_then(|body| body.lines().map(str::trim).find(|line| !line.is_empty()))
+                .unwrap_or(&caps[2]);
+            return format!("PR #{}: {}", &caps[1], title);
+        }
+
+        if let Some(caps) = GITHUB_SQUASH.captures(&self.first_line) {
+            return format!("PR {}: {}", &caps[2], &caps[1]);
+        }
+
+        self.first_line.clone()
+    }
+
+    fn from_git2_commit(
+        commit: &git2::Commit,
+        blank_line_policy: BlankLinePolicy,
+        jira_projects: &[String],
+        keep_issue_lines: bool,
+    ) -> Self {
         let hash = commit.id().to_string();
         let author = commit.author().name().unwrap_or_default().to_string();
         let email = commit.author().email().unwrap_or_default().to_string();
+        let committer = commit.committer().name().unwrap_or_default().to_string();
+        let committer_email = commit.committer().email().unwrap_or_default().to_string();
         let timestamp = commit.time().seconds();
+        let parent_hashes = if commit.parent_count() > 1 {
+            commit.parent_ids().map(|oid| oid.to_string()).collect()
+        } else {
+            Vec::new()
+        };
 
         let message = commit.message().unwrap_or_default();
         let lines: Vec<&str> = message.lines().collect();
         let first_line = lines.first().unwrap_or(&"").to_string();
 
-        let (body, trailers, linked_issues) = if lines.len() > 1 {
-            Self::parse_body_and_trailers(&lines[1..])
+        let (body, trailers, mut linked_issues) = if lines.len() > 1 {
+            Self::parse_body_and_trailers(&lines[1..], blank_line_policy, keep_issue_lines)
         } else {
             (None, Vec::new(), Vec::new())
         };
 
+        let release_note = trailers.iter().find_map(|t| match t {
+            GitTrailer::Other { key, value }
+                if key.eq_ignore_ascii_case("release-note")
+                    && !value.trim().eq_ignore_ascii_case("skip") =>
+            {
+                Some(value.trim().to_string())
+            }
+            _ => None,
+        });
+
+        let reverted_pull_request = body
+            .as_deref()
+            .and_then(|body| REVERTS_PULL_REQUEST.captures(body))
+            .and_then(|caps| caps[1].parse().ok());
+
+        for issue in Self::extract_jira_keys(message, jira_projects) {
+            if !linked_issues.contains(&issue) {
+                linked_issues.push(issue);
+            }
+        }
+
         Commit {
             hash,
             first_line,
@@ -159,22 +547,36 @@ impl Commit {
             type_: String::new(),
             breaking: false,
             breaking_description: None,
+            deprecation_description: None,
+            release_note,
+            reverted_pull_request,
             trailers,
             linked_issues,
             author,
             email,
+            committer,
+            committer_email,
             contributors: Vec::new(),
             timestamp,
+            parent_hashes,
+            repository: None,
         }
     }
 
-    fn normalize_blank_lines(text: &str) -> String {
-        let re = regex::Regex::new(r"\n{3,}").unwrap();
-        re.replace_all(text, "\n\n").to_string()
+    fn normalize_blank_lines(text: &str, policy: BlankLinePolicy) -> String {
+        match policy {
+            BlankLinePolicy::CollapseTo2 => BLANK_LINE_RUN.replace_all(text, "\n\n").to_string(),
+            BlankLinePolicy::CollapseTo1 => BLANK_OR_WHITESPACE_LINES
+                .replace_all(text, "\n")
+                .to_string(),
+            BlankLinePolicy::Preserve => text.to_string(),
+        }
     }
 
     fn parse_body_and_trailers(
         lines: &[&str],
+        blank_line_policy: BlankLinePolicy,
+        keep_issue_lines: bool,
     ) -> (Option<String>, Vec<GitTrailer>, Vec<LinkedIssue>) {
         let mut linked_issues = Vec::new();
         let mut lines_to_strip = std::collections::HashSet::new();
@@ -190,6 +592,11 @@ impl Commit {
         let mut trailer_start_idx = lines.len();
 
         for (i, line) in lines.iter().enumerate().rev() {
+            // Linked issues are stripped separately and may sit amongst the trailers
+            if lines_to_strip.contains(&i) {
+                continue;
+            }
+
             let trimmed = line.trim();
             if trimmed.is_empty() && i == trailer_start_idx - 1 {
                 trailer_start_idx = i;
@@ -209,7 +616,7 @@ impl Commit {
             .iter()
             .enumerate()
             .filter_map(|(i, line)| {
-                if lines_to_strip.contains(&i) {
+                if !keep_issue_lines && lines_to_strip.contains(&i) {
                     None
                 } else {
                     Some(*line)
@@ -229,15 +636,16 @@ impl Commit {
 
         let body = if first_non_empty < last_non_empty {
             let joined = body_lines[first_non_empty..last_non_empty].join("\n");
-            // Normalize excessive blank lines (3+ consecutive) to 2 (single paragraph break)
-            Self::normalize_blank_lines(&joined)
+            Self::normalize_blank_lines(&joined, blank_line_policy)
         } else {
             String::new()
         };
 
         let trailers: Vec<GitTrailer> = lines[trailer_start_idx..]
             .iter()
-            .filter_map(|line| {
+            .enumerate()
+            .filter(|(i, _)| !lines_to_strip.contains(&(trailer_start_idx + i)))
+            .filter_map(|(_, line)| {
                 GIT_TRAILER.captures(line.trim()).map(|caps| {
                     GitTrailer::from_key_value(caps[1].to_string(), caps[2].trim().to_string())
                 })
@@ -254,6 +662,26 @@ impl Commit {
         )
     }
 
+    /// Finds Jira keys of the given projects, ignoring lookalikes such as UTF-8 or
+    /// CVE-2024 that are not keys of a listed project.
+    fn extract_jira_keys(message: &str, projects: &[String]) -> Vec<LinkedIssue> {
+        if projects.is_empty() {
+            return Vec::new();
+        }
+        JIRA_KEY
+            .captures_iter(message)
+            .filter(|cap| projects.iter().any(|project| project == &cap[1]))
+            .filter_map(|cap| {
+                Some(LinkedIssue {
+                    number: cap[2].parse().ok()?,
+                    owner: None,
+                    repo: None,
+                    project: Some(cap[1].to_string()),
+                })
+            })
+            .collect()
+    }
+
     fn extract_linked_issues_from_line(line: &str) -> Vec<LinkedIssue> {
         LINKED_ISSUE
             .captures(line)
@@ -263,12 +691,14 @@ impl Commit {
                         number: num.as_str().parse().unwrap(),
                         owner: cap.get(1).map(|m| m.as_str().to_string()),
                         repo: cap.get(2).map(|m| m.as_str().to_string()),
+                        project: None,
                     }]
                 } else if let Some(num) = cap.get(4) {
                     vec![LinkedIssue {
                         number: num.as_str().parse().unwrap(),
                         owner: None,
                         repo: None,
+                        project: None,
                     }]
                 } else {
                     Vec::new()
@@ -283,6 +713,165 @@ impl GitRepo {
         self.origin_url.as_deref()
     }
 
+    /// The directory commits are filtered to, relative to the repository root.
+    pub fn path_in_repo(&self) -> Option<&Path> {
+        self.path_filter.as_deref()
+    }
+
+    /// Filters commits to a directory relative to the location the repository was
+    /// opened from, rather than the current working directory. An absolute path is
+    /// taken as is. Fails if the path lies outside the repository.
+    pub fn with_path_filter<P: AsRef<Path>>(mut self, path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let outside = || GitRepoError::PathOutsideRepository(path.to_path_buf());
+
+        let (mut filter, relative) = if path.is_absolute() {
+            let workdir = self.repo.workdir().ok_or_else(outside)?;
+            let workdir = workdir
+                .canonicalize()
+                .unwrap_or_else(|_| workdir.to_path_buf());
+            let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+            let relative = path.strip_prefix(&workdir).map_err(|_| outside())?;
+            (PathBuf::new(), relative.to_path_buf())
+        } else {
+            (
+                self.path_filter.take().unwrap_or_default(),
+                path.to_path_buf(),
+            )
+        };
+
+        for component in relative.components() {
+            match component {
+                Component::Normal(part) => filter.push(part),
+                Component::ParentDir => filter.pop().then_some(()).ok_or_else(outside)?,
+                _ => {}
+            }
+        }
+
+        self.path_filter = (!filter.as_os_str().is_empty()).then_some(filter);
+        Ok(self)
+    }
+
+    pub fn with_commit_order(mut self, commit_order: CommitOrder) -> Self {
+        self.commit_order = commit_order;
+        self
+    }
+
+    pub fn with_blank_line_policy(mut self, blank_line_policy: BlankLinePolicy) -> Self {
+        self.blank_line_policy = blank_line_policy;
+        self
+    }
+
+    /// Detects Jira keys (e.g. PROJ-123) of these projects anywhere in a commit message
+    /// as linked issues.
+    pub fn with_jira_projects(mut self, projects: Vec<String>) -> Self {
+        self.jira_projects = projects;
+        self
+    }
+
+    /// Keeps linked-issue lines, such as `Closes #35`, within commit bodies rather than
+    /// stripping them. The issues are still collected as linked issues.
+    pub fn with_issue_lines_kept(mut self, enabled: bool) -> Self {
+        self.keep_issue_lines = enabled;
+        self
+    }
+
+    /// Attributes each commit to its committer rather than its author, such as when
+    /// commits are applied or rebased on behalf of others.
+    pub fn with_committer_attribution(mut self, enabled: bool) -> Self {
+        self.attribute_to_committer = enabled;
+        self
+    }
+
+    /// Commits omitted from the most recent call to `history`, by reason.
+    pub fn dropped_commits(&self) -> DroppedCommits {
+        self.dropped.get()
+    }
+
+    /// Drops commits that only modify files within these directories, given relative
+    /// to the repository root.
+    pub fn with_excluded_paths(mut self, paths: Vec<PathBuf>) -> Self {
+        self.excluded_paths = paths;
+        self
+    }
+
+    /// Excludes commits reachable from any of these references, alongside TO.
+    pub fn with_hidden_refs(mut self, refs: Vec<String>) -> Self {
+        self.hidden_refs = refs;
+        self
+    }
+
+    /// Collapses consecutive commits with identical subjects, such as cherry-picks of the
+    /// same change, into the first one seen.
+    pub fn with_dedupe_subjects(mut self, enabled: bool) -> Self {
+        self.dedupe_subjects = enabled;
+        self
+    }
+
+    /// Caps the history of a first release, where no earlier tag bounds it, to the
+    /// given number of most recent commits.
+    pub fn with_first_release_depth(mut self, depth: Option<usize>) -> Self {
+        self.first_release_depth = depth;
+        self
+    }
+
+    /// Selects whether commit timestamps, such as the date range of contributor commit
+    /// links, come from the author or committer date.
+    pub fn with_date_source(mut self, date_source: DateSource) -> Self {
+        self.date_source = date_source;
+        self
+    }
+
+    /// Drops commits authored by any of the given email addresses from the history, or
+    /// committed by them when commits are attributed to their committer. Emails are
+    /// matched case-insensitively.
+    pub fn with_excluded_authors(mut self, emails: Vec<String>) -> Self {
+        self.excluded_authors = emails
+            .into_iter()
+            .map(|email| email.trim().to_lowercase())
+            .collect();
+        self
+    }
+
+    /// Whether the repository is a shallow clone, whose history stops at a grafted
+    /// commit rather than the true root.
+    pub fn is_shallow(&self) -> bool {
+        self.repo.is_shallow()
+    }
+
+    pub fn proxy_url(&self) -> Option<&str> {
+        self.proxy_url.as_deref()
+    }
+
+    /// Proxy settings for any git2 network operation against the origin remote. A proxy
+    /// chosen by [`proxy_for_remote`] takes precedence over git's own configuration.
+    pub fn proxy_options(&self) -> git2::ProxyOptions<'_> {
+        proxy_options(self.proxy_url.as_deref())
+    }
+
+    /// Fetches all tags from the origin remote, so release ranges can be detected in
+    /// clones made without tags. As with [`GitRepo::clone_and_open`], the token is
+    /// offered when the remote requests plaintext credentials, so callers must only
+    /// pass one intended for the origin's host.
+    pub fn fetch_tags(&self, token: Option<&str>) -> Result<()> {
+        let mut remote = self
+            .repo
+            .find_remote("origin")
+            .context("failed to find remote 'origin'")?;
+
+        let mut fetch_options = git2::FetchOptions::new();
+        fetch_options
+            .proxy_options(self.proxy_options())
+            .remote_callbacks(remote_callbacks(token));
+
+        log::info!("fetching tags from origin");
+        remote
+            .fetch(&["refs/tags/*:refs/tags/*"], Some(&mut fetch_options), None)
+            .context("failed to fetch tags from origin")?;
+
+        Ok(())
+    }
+
     pub fn current_ref(&self) -> Result<String> {
         let head = self.repo.head()?;
         let head_oid = head.peel_to_commit()?.id();
@@ -302,6 +891,99 @@ impl GitRepo {
         Ok(head_oid.to_string()[..7].to_string())
     }
 
+    /// Returns the default branch of the origin remote, resolved through the symbolic
+    /// `refs/remotes/origin/HEAD` reference. This is only present once a repository has
+    /// been cloned, or after running `git remote set-head origin --auto`.
+    pub fn default_branch(&self) -> Option<String> {
+        let head = self.repo.find_reference("refs/remotes/origin/HEAD").ok()?;
+        let target = head.symbolic_target().ok().flatten()?;
+        target
+            .strip_prefix("refs/remotes/origin/")
+            .map(|branch| branch.to_string())
+    }
+
+    /// Whether the reference names a local or remote-tracking branch, rather than a tag
+    /// or commit.
+    pub fn is_branch(&self, reference: &str) -> bool {
+        self.repo.find_branch(reference, BranchType::Local).is_ok()
+            || self.repo.find_branch(reference, BranchType::Remote).is_ok()
+    }
+
+    /// Returns the release date of the tag at the given reference (or HEAD), preferring
+    /// the annotation date of an annotated tag over the date of the tagged commit.
+    /// Returns `None` when the reference is not tagged.
+    pub fn tag_time(&self, reference: Option<&str>) -> Result<Option<i64>> {
+        let Some(reference) = self.find_tag_reference(reference)? else {
+            return Ok(None);
+        };
+
+        let commit = reference.peel_to_commit()?;
+        let annotated = reference
+            .peel_to_tag()
+            .ok()
+            .and_then(|tag| tag.tagger().map(|tagger| tagger.when().seconds()));
+        Ok(Some(annotated.unwrap_or_else(|| commit.time().seconds())))
+    }
+
+    /// Returns the annotation message of the tag at the given reference (or HEAD), with
+    /// any PGP or SSH signature block of a signed tag removed. Returns `None` when the
+    /// reference is not tagged, or the tag is lightweight.
+    pub fn tag_message(&self, reference: Option<&str>) -> Result<Option<String>> {
+        let Some(reference) = self.find_tag_reference(reference)? else {
+            return Ok(None);
+        };
+
+        // A lightweight tag points straight at a commit, so has no annotation to read
+        let Some(oid) = reference.target() else {
+            return Ok(None);
+        };
+        let object = self.repo.find_object(oid, None)?;
+        let Some(tag) = object.as_tag() else {
+            return Ok(None);
+        };
+
+        Ok(tag
+            .message()?
+            .map(strip_signature)
+            .filter(|message| !message.is_empty()))
+    }
+
+    fn find_tag_reference(&self, reference: Option<&str>) -> Result<Option<git2::Reference<'_>>> {
+        let target = match reference {
+            Some(reference) => self.repo.revparse_single(reference)?.peel_to_commit()?,
+            None => self.repo.head()?.peel_to_commit()?,
+        };
+
+        let mut tag_refs = Vec::new();
+        if let Some(reference) = reference {
+            tag_refs.push(if reference.starts_with("refs/tags/") {
+                reference.to_string()
+            } else {
+                format!("refs/tags/{}", reference)
+            });
+        }
+        if let Ok(tag_names) = self.repo.tag_names(None) {
+            tag_refs.extend(
+                tag_names
+                    .iter()
+                    .flatten()
+                    .flatten()
+                    .map(|name| format!("refs/tags/{}", name)),
+            );
+        }
+
+        for tag_ref in tag_refs {
+            if let Ok(reference) = self.repo.find_reference(&tag_ref)
+                && let Ok(commit) = reference.peel_to_commit()
+                && commit.id() == target.id()
+            {
+                return Ok(Some(reference));
+            }
+        }
+
+        Ok(None)
+    }
+
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
         let provided_path = path.as_ref();
         let abs_path = if provided_path.is_absolute() {
@@ -315,32 +997,34 @@ impl GitRepo {
         let repo = Repository::discover(&abs_path)
             .context("failed to find git repository from the specified location")?;
 
-        let work_dir = repo
-            .workdir()
-            .context("repository has no working directory")?;
-
         if repo.is_empty()? {
             return Err(GitRepoError::EmptyRepository.into());
         }
 
-        if repo.is_shallow() {
-            return Err(GitRepoError::ShallowClone.into());
-        }
-
-        let canonical_abs_path = abs_path.canonicalize().unwrap_or_else(|_| abs_path.clone());
-        let canonical_work_dir = work_dir
-            .canonicalize()
-            .unwrap_or_else(|_| work_dir.to_path_buf());
-
-        let path_filter = if canonical_abs_path.starts_with(&canonical_work_dir)
-            && canonical_abs_path != canonical_work_dir
-        {
-            canonical_abs_path
-                .strip_prefix(&canonical_work_dir)
-                .ok()
-                .map(|p| p.to_path_buf())
-        } else {
-            None
+        // A bare repository has no working directory to derive a path filter from
+        let path_filter = match repo.workdir() {
+            Some(work_dir) => {
+                let canonical_abs_path =
+                    abs_path.canonicalize().unwrap_or_else(|_| abs_path.clone());
+                let canonical_work_dir = work_dir
+                    .canonicalize()
+                    .unwrap_or_else(|_| work_dir.to_path_buf());
+
+                if canonical_abs_path.starts_with(&canonical_work_dir)
+                    && canonical_abs_path != canonical_work_dir
+                {
+                    canonical_abs_path
+                        .strip_prefix(&canonical_work_dir)
+                        .ok()
+                        .map(|p| p.to_path_buf())
+                } else {
+                    None
+                }
+            }
+            None => {
+                log::info!("opened bare repository; filtering by path is unsupported");
+                None
+            }
         };
 
         let origin_url = repo
@@ -348,25 +1032,122 @@ impl GitRepo {
             .ok()
             .and_then(|remote| remote.url().ok().map(|s| s.to_string()));
 
+        let proxy_url = origin_url
+            .as_deref()
+            .and_then(|url| proxy_for_remote(url, |key| std::env::var(key).ok()));
+        if let Some(ref url) = proxy_url {
+            log::info!("using proxy {} for git operations", redact_userinfo(url));
+        }
+
         Ok(GitRepo {
             repo,
             path_filter,
+            excluded_paths: Vec::new(),
             origin_url,
+            proxy_url,
+            commit_order: CommitOrder::default(),
+            excluded_authors: Vec::new(),
+            blank_line_policy: BlankLinePolicy::default(),
+            jira_projects: Vec::new(),
+            keep_issue_lines: false,
+            dedupe_subjects: false,
+            attribute_to_committer: false,
+            hidden_refs: Vec::new(),
+            first_release_depth: None,
+            date_source: DateSource::default(),
+            dropped: Cell::new(DroppedCommits::default()),
         })
     }
 
-    fn is_semver_tag(tag_name: &str) -> bool {
+    /// Clones a remote repository into `dest` and opens it for analysis. The token, if
+    /// provided, is offered as the password when the remote requests plaintext
+    /// credentials, so callers must only pass one intended for the remote's host.
+    pub fn clone_and_open(url: &str, dest: &Path, token: Option<&str>) -> Result<Self> {
+        let proxy_url = proxy_for_remote(url, |key| std::env::var(key).ok());
+
+        let mut fetch_options = git2::FetchOptions::new();
+        fetch_options
+            .proxy_options(proxy_options(proxy_url.as_deref()))
+            .remote_callbacks(remote_callbacks(token))
+            .download_tags(git2::AutotagOption::All);
+
+        log::info!("cloning {} into {}", url, dest.display());
+        git2::build::RepoBuilder::new()
+            .fetch_options(fetch_options)
+            .clone(url, dest)
+            .with_context(|| format!("failed to clone repository from {}", url))?;
+
+        Self::open(dest)
+    }
+
+    fn parse_semver_tag(tag_name: &str) -> Option<Version> {
         let version_part = tag_name.rsplit('/').next().unwrap_or(tag_name);
         let to_parse = version_part.strip_prefix('v').unwrap_or(version_part);
-        Version::parse(to_parse).is_ok()
+        Version::parse(to_parse).ok()
+    }
+
+    fn is_semver_tag(tag_name: &str) -> bool {
+        Self::parse_semver_tag(tag_name).is_some()
+    }
+
+    /// Whether a reference (a commit, tag or branch) resolves within the repository.
+    pub fn has_reference(&self, reference: &str) -> bool {
+        self.repo.revparse_single(reference).is_ok()
+    }
+
+    /// Validates that a released tag exists and is a semantic version, returning its
+    /// name without any `refs/tags/` prefix.
+    pub fn release_tag(&self, name: &str) -> Result<String> {
+        let name = name.strip_prefix("refs/tags/").unwrap_or(name);
+        if self
+            .repo
+            .find_reference(&format!("refs/tags/{}", name))
+            .is_err()
+        {
+            return Err(GitRepoError::TagNotFound(name.to_string()).into());
+        }
+        if !Self::is_semver_tag(name) {
+            return Err(GitRepoError::NotSemverTag(name.to_string()).into());
+        }
+        Ok(name.to_string())
+    }
+
+    /// Returns the semantic version of the tag at the given reference (or HEAD).
+    /// Returns `None` when the reference is not a semver tag.
+    pub fn version(&self, reference: Option<&str>) -> Result<Option<Version>> {
+        let target = match reference {
+            Some(reference) => self.repo.revparse_single(reference)?.peel_to_commit()?,
+            None => self.repo.head()?.peel_to_commit()?,
+        };
+
+        Ok(Self::load_tags_sorted(&self.repo)?
+            .into_iter()
+            .find(|tag| tag.oid == target.id())
+            .and_then(|tag| Self::parse_semver_tag(&tag.name)))
+    }
+
+    /// Finds the tag to display for a commit, preferring the one named by the
+    /// user when several tags point at it, and otherwise the highest version
+    fn tag_for_ref<'a>(tags: &'a [Tag], reference: &str, id: Oid) -> Option<&'a Tag> {
+        let name = reference.strip_prefix("refs/tags/").unwrap_or(reference);
+        tags.iter()
+            .find(|t| t.oid == id && t.name == name)
+            .or_else(|| {
+                tags.iter()
+                    .filter(|t| t.oid == id)
+                    .max_by_key(|t| Self::parse_semver_tag(&t.name))
+            })
     }
 
     fn load_tags_sorted(repo: &Repository) -> Result<Vec<Tag>> {
         let mut tags = Vec::new();
+        let mut seen = HashSet::new();
         let tag_names = repo.tag_names(None)?;
 
+        // A stale packed ref is shadowed by a loose ref of the same name, such as an
+        // annotated tag that replaced a lightweight one, so each name is loaded once
         for tag_name in tag_names.iter().flatten().flatten() {
-            if !Self::is_semver_tag(tag_name) {
+            if !Self::is_semver_tag(tag_name) || !seen.insert(tag_name) {
                 continue;
             }
 
@@ -378,13 +1159,69 @@ impl GitRepo {
             }
         }
 
-        tags.sort_by(|a, b| b.2.cmp(&a.2));
+        tags.sort_by_key(|t| std::cmp::Reverse(t.2));
         Ok(tags
             .into_iter()
             .map(|(name, oid, _)| Tag { name, oid })
             .collect())
     }
 
+    /// Splits the history between FROM and TO at each intervening tag, newest first.
+    /// Each segment is named by the tag it ends at, or by FROM (defaulting to HEAD) for
+    /// any commits after the newest tag.
+    ///
+    /// When several tags point at the commit a segment ends at, the segment is named
+    /// by the tag given as FROM, if it is one of them, and otherwise by the highest
+    /// version, so a release candidate never labels the release it became.
+    pub fn history_by_tag(
+        &self,
+        from: Option<String>,
+        to: Option<String>,
+    ) -> Result<Vec<(String, Vec<Commit>)>> {
+        let sorted = Self::load_tags_sorted(&self.repo)?;
+        let mut tags: HashMap<String, String> = HashMap::new();
+        for tag in &sorted {
+            let name = tags
+                .entry(tag.oid.to_string())
+                .or_insert_with(|| tag.name.clone());
+            if Self::parse_semver_tag(&tag.name) > Self::parse_semver_tag(name) {
+                *name = tag.name.clone();
+            }
+        }
+        if let Some(ref from) = from {
+            let id = self.repo.revparse_single(from)?.peel_to_commit()?.id();
+            if let Some(tag) = Self::tag_for_ref(&sorted, from, id) {
+                tags.insert(id.to_string(), tag.name.clone());
+            }
+        }
+
+        let mut commits = self.history(from.clone(), to)?;
+        if self.commit_order == CommitOrder::OldestFirst {
+            commits.reverse();
+        }
+
+        let mut segments: Vec<(String, Vec<Commit>)> = Vec::new();
+        for commit in commits {
+            match tags.get(&commit.hash) {
+                Some(name) => segments.push((name.clone(), vec![commit])),
+                None => match segments.last_mut() {
+                    Some((_, segment)) => segment.push(commit),
+                    None => segments.push((
+                        from.clone().unwrap_or_else(|| "HEAD".to_string()),
+                        vec![commit],
+                    )),
+                },
+            }
+        }
+
+        if self.commit_order == CommitOrder::OldestFirst {
+            for (_, segment) in &mut segments {
+                segment.reverse();
+            }
+        }
+        Ok(segments)
+    }
+
     pub fn history(&self, from: Option<String>, to: Option<String>) -> Result<Vec<Commit>> {
         let tags = Self::load_tags_sorted(&self.repo)?;
 
@@ -399,7 +1236,7 @@ impl GitRepo {
                 let object = self.repo.revparse_single(from)?;
                 let id = object.peel_to_commit()?.id();
 
-                if let Some(tag) = tags.iter().find(|t| t.oid == id) {
+                if let Some(tag) = Self::tag_for_ref(&tags, from, id) {
                     (id, format!("{} ({})", tag.name, &id.to_string()[..7]))
                 } else {
                     (id, id.to_string()[..7].to_string())
@@ -471,7 +1308,7 @@ impl GitRepo {
             to_ref.map_or_else(|| "".to_string(), |v| format!(" to {}", v)),
         );
 
-        if let Some(ref path) = self.path_filter {
+        if let Some(path) = self.path_in_repo() {
             log::info!("filtering commits to path: {}", path.display());
         }
 
@@ -481,26 +1318,121 @@ impl GitRepo {
             .revwalk()
             .context("failed to create revision walker")?;
 
-        revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
-        revwalk.push(from_oid)?;
+        let sorting = match self.commit_order {
+            CommitOrder::NewestFirst => Sort::TOPOLOGICAL | Sort::TIME,
+            CommitOrder::OldestFirst => Sort::TOPOLOGICAL | Sort::TIME | Sort::REVERSE,
+        };
+        revwalk.set_sorting(sorting)?;
+        revwalk.push(from_oid).map_err(GitRepoError::from_revwalk)?;
 
         if let Some(to_oid) = to_oid {
-            revwalk.hide(to_oid)?;
+            revwalk.hide(to_oid).map_err(GitRepoError::from_revwalk)?;
         }
 
+        for hidden_ref in &self.hidden_refs {
+            let id = self
+                .repo
+                .revparse_single(hidden_ref)?
+                .peel_to_commit()?
+                .id();
+            log::info!("excluding commits reachable from {}", hidden_ref);
+            revwalk.hide(id).map_err(GitRepoError::from_revwalk)?;
+        }
+
+        let mut dropped = DroppedCommits::default();
+        let pathspec = self
+            .path_filter
+            .as_deref()
+            .map(|path| self.pathspec_for(path, from_oid));
         for oid in revwalk {
+            let oid = oid.map_err(GitRepoError::from_revwalk)?;
             let git_commit = self
                 .repo
-                .find_commit(oid?)
-                .context("failed to find commit")?;
+                .find_commit(oid)
+                .map_err(GitRepoError::from_revwalk)?;
+
+            if (pathspec.is_some() || !self.excluded_paths.is_empty())
+                && !Self::commit_touches_path(
+                    &self.repo,
+                    &git_commit,
+                    pathspec.as_deref(),
+                    &self.excluded_paths,
+                )?
+            {
+                dropped.outside_path += 1;
+                continue;
+            }
+
+            if !self.excluded_authors.is_empty() {
+                // Match the same identity the commit is attributed to
+                let signature = if self.attribute_to_committer {
+                    git_commit.committer()
+                } else {
+                    git_commit.author()
+                };
+                let email = signature.email().unwrap_or_default().to_lowercase();
+                if self.excluded_authors.contains(&email) {
+                    dropped.excluded_author += 1;
+                    continue;
+                }
+            }
+
+            let mut commit = Commit::from_git2_commit(
+                &git_commit,
+                self.blank_line_policy,
+                &self.jira_projects,
+                self.keep_issue_lines,
+            );
+            if self.attribute_to_committer {
+                commit.author = commit.committer.clone();
+                commit.email = commit.committer_email.clone();
+            }
+            if self.date_source == DateSource::Author {
+                commit.timestamp = git_commit.author().when().seconds();
+            }
+
+            if commit.is_skipped() {
+                log::info!(
+                    "skipping commit {} marked to skip the release note",
+                    &commit.hash[..7]
+                );
+                dropped.skipped += 1;
+                continue;
+            }
 
-            if let Some(ref path) = self.path_filter
-                && !Self::commit_touches_path(&self.repo, &git_commit, path)?
+            if self.dedupe_subjects
+                && commits
+                    .last()
+                    .is_some_and(|c: &Commit| c.first_line == commit.first_line)
             {
+                log::info!(
+                    "skipping commit {} with duplicate subject: {}",
+                    &commit.hash[..7],
+                    commit.first_line
+                );
+                dropped.duplicate_subject += 1;
                 continue;
             }
 
-            commits.push(Commit::from_git2_commit(&git_commit));
+            commits.push(commit);
+        }
+        self.dropped.set(dropped);
+
+        if to_oid.is_none()
+            && let Some(depth) = self.first_release_depth
+            && commits.len() > depth
+        {
+            log::warn!(
+                "no previous release found; only including the latest {} of {} commits",
+                depth,
+                commits.len()
+            );
+            match self.commit_order {
+                CommitOrder::NewestFirst => commits.truncate(depth),
+                CommitOrder::OldestFirst => {
+                    commits.drain(..commits.len() - depth);
+                }
+            }
         }
         Ok(commits)
     }
@@ -524,33 +1456,61 @@ impl GitRepo {
         Ok(None)
     }
 
-    fn commit_touches_path(repo: &Repository, commit: &git2::Commit, path: &Path) -> Result<bool> {
-        let mut path_str = path.to_string_lossy().to_string();
+    /// Builds the pathspec for a path filter. A directory gains a trailing `/` so it
+    /// cannot match a sibling sharing its prefix, whereas a file must match exactly.
+    fn pathspec_for(&self, path: &Path, from_oid: Oid) -> String {
+        let is_file = self
+            .repo
+            .find_commit(from_oid)
+            .and_then(|commit| commit.tree())
+            .and_then(|tree| tree.get_path(path))
+            .map(|entry| entry.kind() == Some(git2::ObjectType::Blob))
+            .unwrap_or_else(|_| {
+                self.repo
+                    .workdir()
+                    .is_some_and(|dir| dir.join(path).is_file())
+            });
 
-        if !path_str.ends_with('/') {
+        let mut path_str = path.to_string_lossy().to_string();
+        if !is_file && !path_str.ends_with('/') {
             path_str.push('/');
         }
+        path_str
+    }
 
-        match commit.parent_count() {
-            0 => {
-                let tree = commit.tree()?;
-                let pathspec = git2::Pathspec::new(std::iter::once(path_str.as_str()))?;
-                let matches = pathspec.match_tree(&tree, git2::PathspecFlags::empty())?;
-                Ok(matches.entries().count() > 0)
-            }
-            _ => {
-                let parent = commit.parent(0)?;
-                let mut diff_opts = DiffOptions::new();
-                diff_opts.pathspec(&path_str);
+    /// Whether the commit modifies a file matching the pathspec (or any file without
+    /// one) that does not lie within an excluded directory.
+    fn commit_touches_path(
+        repo: &Repository,
+        commit: &git2::Commit,
+        path_str: Option<&str>,
+        excluded: &[PathBuf],
+    ) -> Result<bool> {
+        let is_kept = |path: Option<&Path>| {
+            path.is_some_and(|path| !excluded.iter().any(|dir| path.starts_with(dir)))
+        };
 
-                let diff = repo.diff_tree_to_tree(
-                    Some(&parent.tree()?),
-                    Some(&commit.tree()?),
-                    Some(&mut diff_opts),
-                )?;
+        // A root commit is diffed against an empty tree, so every file it adds is a delta
+        let parent_tree = match commit.parent_count() {
+            0 => None,
+            _ => Some(commit.parent(0)?.tree()?),
+        };
 
-                Ok(diff.deltas().count() > 0)
-            }
+        // Only whether a delta exists matters, so avoid inspecting file contents
+        let mut diff_opts = DiffOptions::new();
+        diff_opts.skip_binary_check(true).context_lines(0);
+        if let Some(path_str) = path_str {
+            diff_opts.pathspec(path_str);
         }
+
+        let diff = repo.diff_tree_to_tree(
+            parent_tree.as_ref(),
+            Some(&commit.tree()?),
+            Some(&mut diff_opts),
+        )?;
+
+        Ok(diff
+            .deltas()
+            .any(|delta| is_kept(delta.new_file().path()) || is_kept(delta.old_file().path())))
     }
 }
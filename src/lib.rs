@@ -1,6 +1,7 @@
 pub mod analyzer;
 pub mod contributor;
 pub mod git;
+pub mod locale;
 pub mod markdown;
 pub mod platform;
 pub mod template;
@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+const EN: &[(&str, &str)] = &[
+    ("unreleased", "Unreleased"),
+    ("contributors", "Contributors"),
+    ("more_contributors", "and {n} more"),
+    ("more_commits", "… and {n} more"),
+    ("no_changes", "None"),
+    ("breaking_changes", "Breaking Changes"),
+    ("deprecations", "Deprecations"),
+    ("hot_fixes", "Hot Fixes"),
+    ("new_features", "New Features"),
+    ("bug_fixes", "Bug Fixes"),
+    ("performance_improvements", "Performance Improvements"),
+    ("reverts", "Reverts"),
+    ("dependency_updates", "Dependency Updates"),
+    ("full_changelog", "Full Changelog"),
+    ("breaking_change_stat", "breaking change"),
+    ("breaking_changes_stat", "breaking changes"),
+    ("hotfix_stat", "hotfix"),
+    ("hotfixes_stat", "hotfixes"),
+    ("new_feature_stat", "new feature"),
+    ("new_features_stat", "new features"),
+    ("bug_fix_stat", "bug fix"),
+    ("bug_fixes_stat", "bug fixes"),
+];
+
+const ES: &[(&str, &str)] = &[
+    ("unreleased", "Sin Publicar"),
+    ("contributors", "Colaboradores"),
+    ("more_contributors", "y {n} más"),
+    ("more_commits", "… y {n} más"),
+    ("no_changes", "Ninguno"),
+    ("breaking_changes", "Cambios Incompatibles"),
+    ("deprecations", "Obsolescencias"),
+    ("hot_fixes", "Correcciones Urgentes"),
+    ("new_features", "Nuevas Funcionalidades"),
+    ("bug_fixes", "Corrección de Errores"),
+    ("performance_improvements", "Mejoras de Rendimiento"),
+    ("reverts", "Reversiones"),
+    ("dependency_updates", "Actualizaciones de Dependencias"),
+    ("full_changelog", "Registro Completo de Cambios"),
+    ("breaking_change_stat", "cambio incompatible"),
+    ("breaking_changes_stat", "cambios incompatibles"),
+    ("hotfix_stat", "corrección urgente"),
+    ("hotfixes_stat", "correcciones urgentes"),
+    ("new_feature_stat", "nueva funcionalidad"),
+    ("new_features_stat", "nuevas funcionalidades"),
+    ("bug_fix_stat", "error corregido"),
+    ("bug_fixes_stat", "errores corregidos"),
+];
+
+const FR: &[(&str, &str)] = &[
+    ("unreleased", "Non Publié"),
+    ("contributors", "Contributeurs"),
+    ("more_contributors", "et {n} de plus"),
+    ("more_commits", "… et {n} de plus"),
+    ("no_changes", "Aucun"),
+    ("breaking_changes", "Changements Majeurs"),
+    ("deprecations", "Dépréciations"),
+    ("hot_fixes", "Correctifs Urgents"),
+    ("new_features", "Nouvelles Fonctionnalités"),
+    ("bug_fixes", "Corrections de Bugs"),
+    ("performance_improvements", "Améliorations des Performances"),
+    ("reverts", "Annulations"),
+    ("dependency_updates", "Mises à Jour des Dépendances"),
+    ("full_changelog", "Journal Complet des Modifications"),
+    ("breaking_change_stat", "changement majeur"),
+    ("breaking_changes_stat", "changements majeurs"),
+    ("hotfix_stat", "correctif urgent"),
+    ("hotfixes_stat", "correctifs urgents"),
+    ("new_feature_stat", "nouvelle fonctionnalité"),
+    ("new_features_stat", "nouvelles fonctionnalités"),
+    ("bug_fix_stat", "bug corrigé"),
+    ("bug_fixes_stat", "bugs corrigés"),
+];
+
+const DE: &[(&str, &str)] = &[
+    ("unreleased", "Unveröffentlicht"),
+    ("contributors", "Mitwirkende"),
+    ("more_contributors", "und {n} weitere"),
+    ("more_commits", "… und {n} weitere"),
+    ("no_changes", "Keine"),
+    ("breaking_changes", "Inkompatible Änderungen"),
+    ("deprecations", "Abkündigungen"),
+    ("hot_fixes", "Hotfixes"),
+    ("new_features", "Neue Funktionen"),
+    ("bug_fixes", "Fehlerbehebungen"),
+    ("performance_improvements", "Leistungsverbesserungen"),
+    ("reverts", "Rücknahmen"),
+    ("dependency_updates", "Aktualisierte Abhängigkeiten"),
+    ("full_changelog", "Vollständiges Änderungsprotokoll"),
+    ("breaking_change_stat", "inkompatible Änderung"),
+    ("breaking_changes_stat", "inkompatible Änderungen"),
+    ("hotfix_stat", "Hotfix"),
+    ("hotfixes_stat", "Hotfixes"),
+    ("new_feature_stat", "neue Funktion"),
+    ("new_features_stat", "neue Funktionen"),
+    ("bug_fix_stat", "behobener Fehler"),
+    ("bug_fixes_stat", "behobene Fehler"),
+];
+
+/// Built-in translations for the section titles and stats of the default template.
+///
+/// Anchors are never translated so links into a release note remain stable
+/// regardless of the language it was rendered in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Locale {
+    #[default]
+    En,
+    Es,
+    Fr,
+    De,
+}
+
+impl Locale {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Es => "es",
+            Locale::Fr => "fr",
+            Locale::De => "de",
+        }
+    }
+
+    pub fn translations(&self) -> HashMap<&'static str, &'static str> {
+        let entries = match self {
+            Locale::En => EN,
+            Locale::Es => ES,
+            Locale::Fr => FR,
+            Locale::De => DE,
+        };
+        entries.iter().copied().collect()
+    }
+}
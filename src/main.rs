@@ -1,12 +1,17 @@
 use anyhow::{Context, Result};
-use clap::{Parser, arg};
+use clap::Parser;
 use release_note::platform::Platform;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
-use release_note::analyzer::CommitAnalyzer;
-use release_note::contributor;
-use release_note::git::GitRepo;
-use release_note::markdown;
+use release_note::analyzer::{
+    AnalyzerOptions, CategorizedCommits, CategoryPrecedence, CommitAnalyzer, CommitCategory,
+};
+use release_note::contributor::{self, ResolverOptions};
+use release_note::git::{BlankLinePolicy, CommitOrder, DateSource, GitRepo};
+use release_note::locale::Locale;
+use release_note::markdown::{self, RenderOptions, StatsStyle};
 use release_note::template::TemplateResolver;
 
 pub mod built_info {
@@ -31,6 +36,30 @@ struct Args {
     #[arg(value_name = "TO", required = false, verbatim_doc_comment)]
     to: Option<String>,
 
+    /// Generate the release note for an existing semver tag, up to the tag before it.
+    /// Equivalent to passing the tag as FROM, but fails if the tag does not exist.
+    #[arg(long, value_name = "NAME", conflicts_with_all = ["from", "to"])]
+    tag: Option<String>,
+
+    /// Require both FROM and TO, rather than defaulting FROM to HEAD and detecting TO
+    /// from the previous tag, so the range is deterministic (e.g. in CI)
+    #[arg(long, requires_all = ["from", "to"])]
+    no_autodetect_range: bool,
+
+    /// Additionally exclude commits reachable from REF, alongside TO. Can be repeated
+    /// to exclude several already-released branches.
+    #[arg(long, value_name = "REF")]
+    exclude_ref: Vec<String>,
+
+    /// Only include the latest N commits when there is no previous release to stop at,
+    /// rather than the entire history
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    first_release_depth: Option<usize>,
+
     /// Path to a directory within the repository.
     ///
     /// Can be:
@@ -39,6 +68,102 @@ struct Args {
     #[arg(value_name = "DIR", long, default_value = ".", verbatim_doc_comment)]
     path: PathBuf,
 
+    /// Drop commits that only modify files within this directory, relative to the
+    /// repository root. Can be repeated.
+    #[arg(long, value_name = "DIR", value_parser = parse_exclude_path)]
+    exclude_path: Vec<PathBuf>,
+
+    /// Location of the repository, if not the current directory. When set, DIR is
+    /// resolved relative to ROOT rather than the current directory.
+    #[arg(long, value_name = "ROOT")]
+    root: Option<PathBuf>,
+
+    /// Clone a remote repository into a temporary directory and generate the release
+    /// note from it. The clone is removed on exit. ROOT and DIR are resolved within the
+    /// clone.
+    #[arg(long, value_name = "URL")]
+    clone: Option<String>,
+
+    /// Merge the commits of another repository into the release note, prefixing each
+    /// with the name of its repository. Can be repeated. Each repository is scanned
+    /// between FROM and TO where they exist in it, otherwise from HEAD to its latest
+    /// tag, and its commits link to the platform detected from its own origin.
+    #[arg(long, value_name = "PATH")]
+    merge_repo: Vec<PathBuf>,
+
+    /// Override the project URL used to detect the hosting platform
+    /// (e.g. https://github.example.com/owner/repo). Takes precedence over CI
+    /// environment variables and the origin remote.
+    #[arg(long, value_name = "URL", env = "RELEASE_NOTE_BASE_URL")]
+    base_url: Option<String>,
+
+    /// Skip hosting platform detection entirely, rendering the release note without any
+    /// commit, issue or contributor links.
+    #[arg(long)]
+    no_metadata: bool,
+
+    /// Only detect the hosting platform from CI environment variables, ignoring the
+    /// origin remote. Useful for repositories with multiple remotes.
+    #[arg(long)]
+    no_origin_url: bool,
+
+    /// Override the web host used for commit and issue links (e.g. https://github.com)
+    /// when the origin remote is a mirror of the canonical project. The API host is
+    /// still detected as usual.
+    #[arg(long, value_name = "BASE", env = "RELEASE_NOTE_WEB_URL")]
+    web_url: Option<String>,
+
+    /// API token used for contributor resolution and cloning, overriding GITHUB_TOKEN,
+    /// GH_TOKEN, GITLAB_TOKEN and GITEA_TOKEN. Avoid logging commands that include it.
+    #[arg(long, value_name = "TOKEN")]
+    token: Option<String>,
+
+    /// Search commits by author email to resolve contributors the commit API cannot
+    /// (GitHub only). Subject to the much lower rate limit of the search API.
+    #[arg(long)]
+    search_contributors: bool,
+
+    /// Seconds to wait for each platform API request before falling back as though it
+    /// had failed
+    #[arg(
+        long,
+        value_name = "SECS",
+        default_value_t = contributor::DEFAULT_TIMEOUT.as_secs(),
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    api_timeout: u64,
+
+    /// Attribute commits to the author of their merge request rather than whoever
+    /// squashed and merged them (GitLab only). Costs an extra API call per commit.
+    #[arg(long)]
+    merge_request_authors: bool,
+
+    /// Exclude commits authored by this email address (e.g. a release bot), or committed
+    /// by it with --attribute-to-committer. Can be repeated or comma-separated.
+    #[arg(long, value_name = "EMAIL", value_delimiter = ',')]
+    exclude_author: Vec<String>,
+
+    /// Drop commits whose subject matches this regular expression. Can be repeated.
+    #[arg(long, value_name = "REGEX")]
+    ignore_pattern: Vec<String>,
+
+    /// Collapse consecutive commits with identical subjects (e.g. cherry-picks) into one
+    #[arg(long)]
+    dedupe_subjects: bool,
+
+    /// How runs of blank lines within commit bodies are normalized
+    #[arg(long, value_name = "POLICY", value_enum, default_value_t = BlankLinePolicy::CollapseTo2)]
+    blank_lines: BlankLinePolicy,
+
+    /// Which category wins when a commit matches several, such as a breaking
+    /// dependency update (e.g. feat(deps)!:)
+    #[arg(long, value_name = "ORDER", value_enum, default_value_t = CategoryPrecedence::BreakingFirst)]
+    category_precedence: CategoryPrecedence,
+
+    /// List contributors alphabetically by username rather than by commit count
+    #[arg(long)]
+    sort_contributors_by_name: bool,
+
     /// Trust a host for token attachment (e.g. a self-hosted GitHub Enterprise or GitLab
     /// instance). Can be repeated or comma-separated. Without this flag, tokens are only
     /// sent to github.com, *.github.com, and gitlab.com.
@@ -50,17 +175,270 @@ struct Args {
     )]
     trusted_host: Vec<String>,
 
+    /// Search this directory for a release-note.tera template before the repository.
+    #[arg(long, value_name = "DIR")]
+    template_dir: Option<PathBuf>,
+
+    /// Language used for the section titles and stats of the default template.
+    #[arg(long, value_enum, default_value_t = Locale::En, env = "RELEASE_NOTE_LANG")]
+    lang: Locale,
+
+    /// Rename a section of the default template, e.g. "new-features=What's New". Stats
+    /// link to the renamed heading. Can be repeated
+    #[arg(long, value_name = "CATEGORY=TITLE", value_parser = parse_section_title)]
+    section_title: Vec<(CommitCategory, String)>,
+
+    /// Omit the summary of breaking changes, features and fixes beneath the heading.
+    #[arg(long)]
+    no_stats: bool,
+
+    /// Join the counts within the stats summary with this separator (defaults to " • ")
+    #[arg(long, value_name = "SEP")]
+    stats_separator: Option<String>,
+
+    /// Style of each count within the stats summary
+    #[arg(long, value_enum, default_value_t = StatsStyle::Linked)]
+    stats_style: StatsStyle,
+
+    /// Render commit bodies exactly as written, without unwrapping hard-wrapped lines.
+    #[arg(long)]
+    no_wrap: bool,
+
+    /// Hard-wrap the prose of commit bodies at N columns, including their indentation,
+    /// for diff-friendly changelogs. Code blocks, tables and lists are left as written
+    #[arg(long, value_name = "N", conflicts_with = "no_wrap")]
+    wrap_width: Option<usize>,
+
+    /// Prefix each line of a commit body with this string rather than two spaces,
+    /// e.g. "> " to render bodies as blockquotes, or "" for no indentation
+    #[arg(long, value_name = "PREFIX")]
+    body_indent: Option<String>,
+
+    /// Title the release note "Unreleased" rather than the git reference.
+    #[arg(long)]
+    unreleased: bool,
+
+    /// Only strip the listed trailers (e.g. Signed-off-by) from commit bodies. Any other
+    /// trailer is rendered beneath the body. Without this flag, all trailers are stripped.
+    #[arg(long, value_name = "KEYS", value_delimiter = ',')]
+    strip_trailers: Option<Vec<String>>,
+
+    /// Only render the listed trailers (e.g. Co-authored-by) beneath commit bodies,
+    /// stripping every other trailer.
+    #[arg(
+        long,
+        value_name = "KEYS",
+        value_delimiter = ',',
+        conflicts_with = "strip_trailers"
+    )]
+    keep_trailers: Option<Vec<String>>,
+
+    /// Link commits to a web URL derived from the origin remote when the hosting
+    /// platform is not recognized (e.g. a self-hosted forge).
+    #[arg(long)]
+    include_commit_links_for_unknown: bool,
+
+    /// Link Jira keys (e.g. PROJ-123) of each --jira-project found in commit messages
+    /// to this Jira instance (e.g. https://company.atlassian.net)
+    #[arg(long, value_name = "URL", env = "RELEASE_NOTE_JIRA_URL")]
+    jira_url: Option<String>,
+
+    /// Detect Jira keys of this project (e.g. PROJ) in commit messages as linked
+    /// issues. Can be repeated or comma-separated.
+    #[arg(
+        long,
+        value_name = "KEY",
+        value_delimiter = ',',
+        requires = "jira_url",
+        env = "RELEASE_NOTE_JIRA_PROJECT"
+    )]
+    jira_project: Vec<String>,
+
+    /// Keep linked-issue lines such as "Closes #35" within commit bodies, rather than
+    /// only listing them as linked issues
+    #[arg(long)]
+    no_issue_stripping: bool,
+
+    /// Show each contributor's commit count as a shields.io badge
+    #[arg(long)]
+    commit_badges: bool,
+
+    /// List contributors as text only, without avatar images fetched from the platform
+    #[arg(long)]
+    no_avatars: bool,
+
+    /// Number of characters of each commit hash to display (links always use the full hash)
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = markdown::DEFAULT_HASH_LENGTH,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(4..=40)
+    )]
+    hash_length: usize,
+
+    /// Show contributors by their display name, linked to their profile, rather than
+    /// their @username
+    #[arg(long)]
+    display_names: bool,
+
+    /// Omit the Contributors section while keeping the inline mentions on each commit
+    #[arg(long)]
+    no_contributors_section: bool,
+
+    /// Only list the N most active contributors, summarizing the rest as "and N more"
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    max_contributors: Option<usize>,
+
+    /// Only list the first N commits within each section, summarizing the rest as
+    /// "… and N more"
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    max_per_category: Option<usize>,
+
+    /// Replace the "Generated with release-note" line at the end of the release note.
+    #[arg(long, value_name = "TEXT", conflicts_with = "no_footer")]
+    footer: Option<String>,
+
+    /// Remove the "Generated with release-note" line from the end of the release note.
+    #[arg(long)]
+    no_footer: bool,
+
+    /// Render every section of the default template, noting "None" beneath any without
+    /// commits.
+    #[arg(long)]
+    always_show_sections: bool,
+
+    /// Keep breaking changes within the section of their commit type, marked with ⚠️,
+    /// rather than gathering them under a dedicated Breaking Changes section
+    #[arg(long)]
+    breaking_inline: bool,
+
+    /// Combine dependency updates squash-merged from the same pull request, such as a
+    /// grouped Dependabot update, into a single entry.
+    #[arg(long)]
+    merge_dependency_prs: bool,
+
+    /// Wrap each section in a collapsible <details> block, summarized by its title and
+    /// number of commits.
+    #[arg(long)]
+    collapsible: bool,
+
+    /// Prepend a YAML front matter block with the title and date of the release note,
+    /// for use with static site generators.
+    #[arg(long)]
+    front_matter: bool,
+
+    /// Fetch tags from the origin remote before analyzing history. Useful in CI
+    /// pipelines that clone without tags.
+    #[arg(long)]
+    fetch_tags: bool,
+
+    /// Date the release note using the tag at FROM (or HEAD) instead of the current time.
+    /// Uses the annotation date of an annotated tag, otherwise the date of the tagged commit.
+    #[arg(long)]
+    date_from_ref: bool,
+
+    /// Date the release note with a fixed date, either as a Unix timestamp, as
+    /// YYYY-MM-DD or as an RFC 3339 date-time, for reproducible output
+    #[arg(
+        long,
+        value_name = "EPOCH|DATE|DATETIME",
+        value_parser = parse_release_date,
+        conflicts_with = "date_from_ref"
+    )]
+    release_date: Option<i64>,
+
+    /// Format of the date beside the release heading, using strftime specifiers
+    /// (e.g. "%Y-%m-%d"). Defaults to "%B %d, %Y"
+    #[arg(long, value_name = "FORMAT", value_parser = parse_date_format)]
+    date_format: Option<String>,
+
+    /// Whether commit timestamps, such as the date range of contributor commit links,
+    /// use the author or committer date
+    #[arg(long, value_enum, default_value_t = DateSource::Committer)]
+    date_source: DateSource,
+
+    /// Title the release note with NAME rather than the git reference. A {version}
+    /// placeholder is replaced with the semantic version of the tag at FROM (or HEAD).
+    /// Not supported with --per-tag, which titles each note with its own tag.
+    #[arg(long, value_name = "NAME", conflicts_with = "per_tag")]
+    ref_name: Option<String>,
+
+    /// Attribute commits to their committer rather than their author, such as when
+    /// patches are applied on behalf of others.
+    #[arg(long)]
+    attribute_to_committer: bool,
+
+    /// Split the range at each intervening tag, rendering a release note per version,
+    /// newest first.
+    #[arg(long, conflicts_with_all = ["merge_repo", "unreleased", "check", "output_template_context", "print_contributors"])]
+    per_tag: bool,
+
+    /// List commits within each section from oldest to newest.
+    #[arg(long)]
+    oldest_first: bool,
+
+    /// Exit with an error if any commit does not follow a recognized conventional type.
+    #[arg(long)]
+    fail_on_uncategorized: bool,
+
+    /// Print the usernames of all resolved contributors (excluding bots), one per line,
+    /// instead of the release note.
+    #[arg(long)]
+    print_contributors: bool,
+
+    /// Print the subjects of all commits lacking a Signed-off-by trailer, one per line,
+    /// instead of the release note. Useful for projects enforcing the DCO.
+    #[arg(long)]
+    dco_report: bool,
+
+    /// Print the context passed to the template as JSON, instead of the release note.
+    /// Useful for discovering the variables available to custom templates.
+    #[arg(long)]
+    output_template_context: bool,
+
+    /// Fail unless the template context is exported with this schema version, guarding
+    /// consumers of --output-template-context against incompatible changes
+    #[arg(long, value_name = "VERSION", requires = "output_template_context")]
+    output_format_version: Option<u32>,
+
+    /// Compare the release note against FILE rather than printing it, exiting with an
+    /// error and printing a unified diff if they differ
+    #[arg(long, value_name = "FILE")]
+    check: Option<PathBuf>,
+
+    /// Exit with an error if any breaking change lacks a description, given either as a
+    /// BREAKING CHANGE footer or as the body of a `!` commit.
+    #[arg(long)]
+    require_breaking_description: bool,
+
     /// Enable verbose logging
     #[arg(short, long)]
     verbose: bool,
 
+    /// Always colour warnings and errors in verbose output, even when not writing to a
+    /// terminal. Ignored if NO_COLOR is set.
+    #[arg(long, conflicts_with = "no_color")]
+    force_color: bool,
+
+    /// Never colour verbose output, overriding CLICOLOR_FORCE
+    #[arg(long)]
+    no_color: bool,
+
     /// Print build time version information
     #[arg(short = 'V', long)]
     version: bool,
 }
 
 fn main() -> Result<()> {
-    let args = Args::parse();
+    let mut args = Args::parse();
 
     if args.version {
         print_version_info();
@@ -68,45 +446,567 @@ fn main() -> Result<()> {
     }
 
     if args.verbose {
-        env_logger::Builder::new()
-            .format(|buf, record| {
-                use std::io::Write;
-                writeln!(buf, "{}", record.args())
-            })
-            .filter_level(log::LevelFilter::Info)
-            .init();
+        log_builder(log_write_style(args.force_color, args.no_color, |key| {
+            std::env::var(key).ok()
+        }))
+        .init();
+    }
+
+    if args.token.is_some() {
+        log::warn!(
+            "API token provided with --token; avoid logging commands that include it, especially in CI"
+        );
+    }
+
+    if args.jira_url.is_some() && args.jira_project.is_empty() {
+        log::warn!("no --jira-project given; Jira keys will not be linked");
+    }
+
+    let analyzer_options = AnalyzerOptions {
+        precedence: args.category_precedence,
+        sort_contributors_by_name: args.sort_contributors_by_name,
+        breaking_inline: args.breaking_inline,
+        ..Default::default()
     }
+    .with_ignore_patterns(&args.ignore_pattern)?;
+
+    let clone_dir = match &args.clone {
+        Some(url) => Some(clone_repository(
+            url,
+            &args.trusted_host,
+            args.token.as_deref(),
+        )?),
+        None => None,
+    };
+    let root = match (&clone_dir, &args.root) {
+        (Some(_), Some(root)) if root.is_absolute() => {
+            anyhow::bail!("--root must be relative to the clone when used with --clone")
+        }
+        (Some(dir), Some(root)) => Some(dir.path().join(root)),
+        (Some(dir), None) => Some(dir.path().to_path_buf()),
+        (None, root) => root.clone(),
+    };
+    let work_dir = match &root {
+        Some(root) => root.join(&args.path),
+        None => args.path.clone(),
+    };
 
-    let template = TemplateResolver::new(args.path.clone()).resolve()?;
+    let resolver = match &args.template_dir {
+        Some(dir) => TemplateResolver::new(work_dir.clone()).with_template_dir(dir.clone()),
+        None => TemplateResolver::new(work_dir.clone()),
+    };
+    log::info!("searching for custom templates");
+    for path in resolver.list_candidates() {
+        log::info!(
+            "  * {} [{}]",
+            path.display(),
+            if path.is_file() { "found" } else { "not found" }
+        );
+    }
+    let template = resolver.resolve()?;
 
-    let repo = GitRepo::open(&args.path)?;
-    let mut history = repo.history(args.from.clone(), args.to.clone())?;
+    let commit_order = if args.oldest_first {
+        CommitOrder::OldestFirst
+    } else {
+        CommitOrder::NewestFirst
+    };
+    let repo = match &root {
+        Some(root) => GitRepo::open(root)?.with_path_filter(&args.path)?,
+        None => GitRepo::open(&work_dir)?,
+    };
+    let repo = configure_repo(repo, &args, commit_order);
+    if args.verbose && repo.is_shallow() {
+        log::warn!(
+            "repository is a shallow clone; history may be incomplete. Run 'git fetch --unshallow' for full history."
+        );
+    }
+
+    if args.fetch_tags {
+        let token = repo.origin_url().and_then(|url| {
+            remote_token(
+                url,
+                &args.trusted_host,
+                args.token.as_deref(),
+                "fetching tags",
+            )
+        });
+        repo.fetch_tags(token.as_deref())?;
+    }
+
+    if let Some(tag) = args.tag.take() {
+        args.from = Some(repo.release_tag(&tag)?);
+    }
 
     let git_ref = args.from.clone().map(Ok).unwrap_or_else(|| {
         repo.current_ref()
             .context("failed to determine current reference")
     })?;
-    let platform = Platform::detect(repo.origin_url(), &args.trusted_host);
+    if args.dco_report {
+        for commit in repo.history(args.from.clone(), args.to.clone())? {
+            if !commit.is_signed_off() {
+                println!("{}", commit.first_line);
+            }
+        }
+        return Ok(());
+    }
+
+    let platform = detect_platform(&args, repo.origin_url());
+
+    let resolver_options = ResolverOptions {
+        search_fallback: args.search_contributors,
+        merge_request_authors: args.merge_request_authors,
+        display_names: args.display_names,
+        timeout: Some(std::time::Duration::from_secs(args.api_timeout)),
+    };
+    let mut resolver =
+        contributor::ContributorResolver::from_platform_with_options(&platform, &resolver_options)
+            .ok()
+            .flatten();
+    let tag_time = if args.date_from_ref {
+        let tag_time = repo.tag_time(args.from.as_deref())?;
+        if tag_time.is_none() {
+            log::warn!("{} is not a tag; using the current date instead", git_ref);
+        }
+        tag_time
+    } else {
+        None
+    };
 
-    if let Ok(Some(mut resolver)) = contributor::ContributorResolver::new(&platform) {
-        resolver.resolve_contributors(&mut history);
+    let ref_name = match args.ref_name.as_deref() {
+        Some(name) if name.contains("{version}") => {
+            let version = repo.version(args.from.as_deref())?;
+            if version.is_none() {
+                log::warn!("{} is not a semver tag; ignoring --ref-name", git_ref);
+            }
+            version.map(|version| expand_ref_name(name, &version))
+        }
+        name => name.map(|name| name.to_string()),
+    };
+
+    let release_date = tag_time.or(args.release_date).unwrap_or_else(|| {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64
+    });
+
+    let fallback_web_url = if args.include_commit_links_for_unknown
+        && !args.no_metadata
+        && platform == Platform::Unknown
+        && args.merge_repo.is_empty()
+    {
+        repo.origin_url().and_then(Platform::derive_web_url)
+    } else {
+        None
+    };
+
+    if args.per_tag {
+        let mut notes = Vec::new();
+        for (tag, mut commits) in repo.history_by_tag(args.from.clone(), args.to.clone())? {
+            if let Some(resolver) = resolver.as_mut() {
+                resolver.resolve_contributors(&mut commits);
+            }
+            let categorized = CommitAnalyzer::analyze_with_options(&commits, &analyzer_options);
+            if args.fail_on_uncategorized {
+                categorized.ensure_categorized()?;
+            }
+            if args.require_breaking_description {
+                categorized.ensure_breaking_described()?;
+            }
+
+            let options = RenderOptions {
+                tag_message: repo.tag_message(Some(&tag))?,
+                ..render_options(&args, fallback_web_url.clone())
+            };
+            notes.push(markdown::render_history_with_options(
+                &categorized,
+                &platform,
+                &tag,
+                repo.tag_time(Some(&tag))?.unwrap_or(release_date),
+                &template,
+                &options,
+            )?);
+        }
+        println!("{}", notes.join("\n\n"));
+        log_dropped_commits(&repo);
+        return Ok(());
     }
 
-    let categorized = CommitAnalyzer::analyze(&history);
+    let categorized = CommitAnalyzer::analyze_from_repo_with_options(
+        &repo,
+        args.from.clone(),
+        args.to.clone(),
+        resolver.as_mut(),
+        &analyzer_options,
+    )?;
+
+    let mut repository_platforms = HashMap::new();
+    let categorized = if args.merge_repo.is_empty() {
+        categorized
+    } else {
+        let name = repository_name(&work_dir);
+        repository_platforms.insert(name.clone(), platform.clone());
+        let mut repositories = vec![(name, categorized)];
+        for path in &args.merge_repo {
+            log::info!("merging commits from {}", path.display());
+            let other = configure_repo(GitRepo::open(path)?, &args, commit_order);
+            let other_platform = detect_merged_platform(&args, other.origin_url());
+            let mut other_resolver = contributor::ContributorResolver::from_platform_with_options(
+                &other_platform,
+                &resolver_options,
+            )
+            .ok()
+            .flatten();
+            let name = repository_name(path);
+            repositories.push((
+                name.clone(),
+                CommitAnalyzer::analyze_from_repo_with_options(
+                    &other,
+                    merged_ref(&other, &name, args.from.as_deref()),
+                    merged_ref(&other, &name, args.to.as_deref()),
+                    other_resolver.as_mut(),
+                    &analyzer_options,
+                )?,
+            ));
+            repository_platforms.insert(name, other_platform);
+        }
+        CategorizedCommits::merge(repositories, commit_order, &analyzer_options)
+    };
     log::info!("");
 
-    let release_date = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_secs() as i64;
+    if args.print_contributors {
+        for handle in categorized.contributor_handles() {
+            println!("{}", handle);
+        }
+        return Ok(());
+    }
+
+    if args.fail_on_uncategorized {
+        categorized.ensure_categorized()?;
+    }
+
+    if args.require_breaking_description {
+        categorized.ensure_breaking_described()?;
+    }
+
+    let compare_url = if args.merge_repo.is_empty() {
+        compare_base(&repo, args.to.as_deref())
+            .and_then(|base| platform.compare_url(&base, &git_ref))
+    } else {
+        None
+    };
+    let options = RenderOptions {
+        ref_name,
+        tag_message: repo.tag_message(args.from.as_deref())?,
+        compare_url,
+        repository_platforms,
+        ..render_options(&args, fallback_web_url)
+    };
+    if args.verbose {
+        for warning in
+            markdown::validate_template_context(&template, &categorized, &platform, &options)?
+        {
+            log::warn!("template references an undefined variable: {}", warning);
+        }
+    }
 
-    println!(
-        "{}",
-        markdown::render_history(&categorized, &platform, &git_ref, release_date, &template)?
-    );
+    if args.output_template_context {
+        if let Some(version) = args.output_format_version
+            && version != markdown::TEMPLATE_CONTEXT_SCHEMA_VERSION
+        {
+            anyhow::bail!(
+                "template context schema version {} is not supported; the current version is {}",
+                version,
+                markdown::TEMPLATE_CONTEXT_SCHEMA_VERSION
+            );
+        }
+        let context =
+            markdown::template_context(&categorized, &platform, &git_ref, release_date, &options);
+        println!("{}", serde_json::to_string_pretty(&context)?);
+        log_dropped_commits(&repo);
+        return Ok(());
+    }
+
+    if let Some(path) = &args.check {
+        let existing = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let rendered = markdown::render_history_with_options(
+            &categorized,
+            &platform,
+            &git_ref,
+            release_date,
+            &template,
+            &options,
+        )?;
+        if let Some(diff) =
+            markdown::diff_release_note(&existing, &rendered, &path.display().to_string())
+        {
+            print!("{}", diff);
+            anyhow::bail!("release note in {} is out of date", path.display());
+        }
+        log_dropped_commits(&repo);
+        return Ok(());
+    }
+
+    let mut stdout = std::io::stdout().lock();
+    markdown::render_history_to(
+        &mut stdout,
+        &categorized,
+        &platform,
+        &git_ref,
+        release_date,
+        &template,
+        &options,
+    )?;
+    writeln!(stdout)?;
+
+    log_dropped_commits(&repo);
     Ok(())
 }
 
+/// Summarizes the commits the most recent history walk omitted, whatever the output.
+fn log_dropped_commits(repo: &GitRepo) {
+    let dropped = repo.dropped_commits();
+    if dropped.total() > 0 {
+        log::info!(
+            "dropped {} commits: {} outside the path filter, {} by excluded authors, {} with duplicate subjects, {} marked to skip",
+            dropped.total(),
+            dropped.outside_path,
+            dropped.excluded_author,
+            dropped.duplicate_subject,
+            dropped.skipped
+        );
+    }
+}
+
+fn expand_ref_name(name: &str, version: &semver::Version) -> String {
+    name.replace("{version}", &version.to_string())
+}
+
+/// Parses a section title given as CATEGORY=TITLE, where the category is one the
+/// default template renders a section for.
+fn parse_section_title(value: &str) -> std::result::Result<(CommitCategory, String), String> {
+    let (category, title) = value
+        .split_once('=')
+        .ok_or_else(|| format!("'{}' is not in the form CATEGORY=TITLE", value))?;
+    let category = category
+        .trim()
+        .parse::<CommitCategory>()
+        .map_err(|e| e.to_string())?;
+    if !category.is_rendered_by_default() {
+        return Err(format!(
+            "the default template has no section for '{}'",
+            category
+        ));
+    }
+    Ok((category, title.trim().to_string()))
+}
+
+/// Parses a directory to exclude into a plain path relative to the repository root, so
+/// "./docs" and "docs/" both match files under "docs".
+fn parse_exclude_path(value: &str) -> std::result::Result<PathBuf, String> {
+    let mut path = PathBuf::new();
+    for component in std::path::Path::new(value).components() {
+        match component {
+            std::path::Component::Normal(part) => path.push(part),
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir if path.pop() => {}
+            _ => {
+                return Err(format!(
+                    "'{}' is not a directory within the repository",
+                    value
+                ));
+            }
+        }
+    }
+    if path.as_os_str().is_empty() {
+        return Err(format!("'{}' would exclude the entire repository", value));
+    }
+    Ok(path)
+}
+
+/// Parses a release date given as a Unix timestamp, a YYYY-MM-DD date (at midnight
+/// UTC) or an RFC 3339 date-time.
+fn parse_release_date(value: &str) -> std::result::Result<i64, String> {
+    if let Ok(epoch) = value.parse::<i64>() {
+        return Ok(epoch);
+    }
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_time(chrono::NaiveTime::MIN).and_utc().timestamp());
+    }
+    chrono::DateTime::parse_from_rfc3339(value)
+        .map(|date| date.timestamp())
+        .map_err(|_| {
+            format!(
+                "'{}' is not a Unix timestamp, YYYY-MM-DD date or RFC 3339 date-time",
+                value
+            )
+        })
+}
+
+fn parse_date_format(value: &str) -> std::result::Result<String, String> {
+    markdown::validate_date_format(value)
+        .map(|_| value.to_string())
+        .map_err(|err| err.to_string())
+}
+
+fn configure_repo(repo: GitRepo, args: &Args, commit_order: CommitOrder) -> GitRepo {
+    repo.with_commit_order(commit_order)
+        .with_excluded_authors(args.exclude_author.clone())
+        .with_blank_line_policy(args.blank_lines)
+        .with_jira_projects(args.jira_project.clone())
+        .with_issue_lines_kept(args.no_issue_stripping)
+        .with_dedupe_subjects(args.dedupe_subjects)
+        .with_committer_attribution(args.attribute_to_committer)
+        .with_hidden_refs(args.exclude_ref.clone())
+        .with_excluded_paths(args.exclude_path.clone())
+        .with_first_release_depth(args.first_release_depth)
+        .with_date_source(args.date_source)
+}
+
+/// Names a repository by its directory, as shown beside each commit in a merged note.
+fn repository_name(path: &Path) -> String {
+    path.canonicalize()
+        .ok()
+        .and_then(|path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+fn detect_platform(args: &Args, origin_url: Option<&str>) -> Platform {
+    if args.no_metadata {
+        log::info!("skipping platform detection as --no-metadata is set");
+        return Platform::Unknown;
+    }
+
+    let platform = match &args.base_url {
+        Some(url) => Platform::detect_from_base_url(url, &args.trusted_host),
+        None if args.no_origin_url => Platform::detect_from_env_only().unwrap_or_else(|| {
+            log::warn!("not running in CI; ignoring origin URL as --no-origin-url is set");
+            Platform::Unknown
+        }),
+        None => Platform::detect(origin_url, &args.trusted_host),
+    };
+    let platform = match &args.web_url {
+        Some(web_url) => platform.with_web_url(web_url),
+        None => platform,
+    };
+    match &args.token {
+        Some(token) => platform.with_token(token.clone()),
+        None => platform,
+    }
+}
+
+/// Detects the platform of a merged repository from its own origin alone, as the CI
+/// variables and overrides given for the primary repository don't describe it.
+fn detect_merged_platform(args: &Args, origin_url: Option<&str>) -> Platform {
+    match origin_url {
+        Some(url) if !args.no_metadata => {
+            Platform::detect_from_remote_url(url).with_trusted_token(&args.trusted_host)
+        }
+        _ => Platform::Unknown,
+    }
+}
+
+/// The render options given on the command line, before any that depend on the range
+/// being rendered, such as its tag.
+fn render_options(args: &Args, fallback_web_url: Option<String>) -> RenderOptions {
+    RenderOptions {
+        lang: args.lang,
+        no_stats: args.no_stats,
+        stats_separator: args.stats_separator.clone(),
+        stats_style: args.stats_style,
+        no_wrap: args.no_wrap,
+        wrap_width: args.wrap_width,
+        body_indent: args.body_indent.clone(),
+        unreleased: args.unreleased,
+        strip_trailers: args.strip_trailers.clone(),
+        keep_trailers: args.keep_trailers.clone(),
+        front_matter: args.front_matter,
+        collapsible: args.collapsible,
+        max_per_category: args.max_per_category,
+        footer: args.footer.clone(),
+        no_footer: args.no_footer,
+        always_show_sections: args.always_show_sections,
+        merge_dependency_prs: args.merge_dependency_prs,
+        commit_badges: args.commit_badges,
+        no_avatars: args.no_avatars,
+        hash_length: Some(args.hash_length),
+        no_contributors_section: args.no_contributors_section,
+        display_names: args.display_names,
+        section_titles: args.section_title.iter().cloned().collect(),
+        date_format: args.date_format.clone(),
+        fallback_web_url,
+        max_contributors: args.max_contributors,
+        jira_url: args.jira_url.clone(),
+        ..Default::default()
+    }
+}
+
+/// The base a release is compared against: the default branch of the origin remote
+/// when TO is a branch, otherwise TO itself.
+fn compare_base(repo: &GitRepo, to: Option<&str>) -> Option<String> {
+    let to = to?;
+    if repo.is_branch(to) {
+        repo.default_branch().or_else(|| Some(to.to_string()))
+    } else {
+        Some(to.to_string())
+    }
+}
+
+/// A reference of the release range to scan a merged repository with, provided it
+/// exists there. Otherwise the repository is scanned from HEAD to its latest tag.
+fn merged_ref(repo: &GitRepo, name: &str, reference: Option<&str>) -> Option<String> {
+    let reference = reference?;
+    if repo.has_reference(reference) {
+        Some(reference.to_string())
+    } else {
+        log::warn!(
+            "{} does not exist in {}; detecting its range instead",
+            reference,
+            name
+        );
+        None
+    }
+}
+
+fn clone_repository(
+    url: &str,
+    trusted_hosts: &[String],
+    token: Option<&str>,
+) -> Result<tempfile::TempDir> {
+    let dir = tempfile::TempDir::new().context("failed to create directory for clone")?;
+    let token = remote_token(url, trusted_hosts, token, "cloning");
+    GitRepo::clone_and_open(url, dir.path(), token.as_deref())?;
+    Ok(dir)
+}
+
+/// The token to offer a git remote, which is only ever one for a trusted host of the
+/// remote URL itself. CI variables may describe a different repository, such as the
+/// one being built rather than the one being cloned.
+fn remote_token(
+    url: &str,
+    trusted_hosts: &[String],
+    token: Option<&str>,
+    action: &str,
+) -> Option<String> {
+    if Platform::is_trusted_remote(url, trusted_hosts) {
+        let platform = Platform::detect_from_remote_url(url).with_trusted_token(trusted_hosts);
+        token.or(platform.token()).map(str::to_string)
+    } else {
+        if token.is_some() {
+            log::warn!(
+                "{} is not a trusted host; {} without the API token",
+                url,
+                action
+            );
+        }
+        None
+    }
+}
+
 fn print_version_info() {
     println!("version:    {}", built_info::PKG_VERSION);
     println!("rustc:      {}", built_info::RUSTC_VERSION);
@@ -124,3 +1024,277 @@ fn print_version_info() {
     }
     println!("build_date: {}", built_info::BUILT_TIME_UTC);
 }
+
+fn log_builder(write_style: env_logger::WriteStyle) -> env_logger::Builder {
+    let mut builder = env_logger::Builder::new();
+    builder
+        .format(|buf, record| {
+            if record.level() <= log::Level::Warn {
+                let style = buf.default_level_style(record.level());
+                writeln!(buf, "{style}{}{style:#}", record.args())
+            } else {
+                writeln!(buf, "{}", record.args())
+            }
+        })
+        .filter_level(log::LevelFilter::Info)
+        .write_style(write_style);
+    builder
+}
+
+fn log_write_style(
+    force_color: bool,
+    no_color: bool,
+    env: impl Fn(&str) -> Option<String>,
+) -> env_logger::WriteStyle {
+    let no_color_env = env("NO_COLOR").is_some_and(|v| !v.is_empty());
+
+    if no_color || no_color_env {
+        env_logger::WriteStyle::Never
+    } else if force_color {
+        env_logger::WriteStyle::Always
+    } else {
+        env_logger::WriteStyle::Auto
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_platform_detection_without_metadata() {
+        let args = Args::parse_from(["release-note", "--no-metadata"]);
+        let platform = detect_platform(&args, Some("https://github.com/owner/repo.git"));
+
+        assert_eq!(platform, Platform::Unknown);
+        assert_eq!(platform.commit_url("4cfa67f"), None);
+        assert_eq!(platform.issue_url(42), None);
+    }
+
+    #[test]
+    fn substitutes_version_into_ref_name() {
+        let version = semver::Version::parse("1.2.3-rc.1").unwrap();
+        assert_eq!(expand_ref_name("v{version}", &version), "v1.2.3-rc.1");
+        assert_eq!(expand_ref_name("Release", &version), "Release");
+    }
+
+    #[test]
+    fn dates_release_note_with_release_date_override() {
+        use release_note::analyzer::CategorizedCommits;
+
+        let categorized = CategorizedCommits {
+            by_category: std::collections::HashMap::from([(CommitCategory::Feature, Vec::new())]),
+            contributors: Vec::new(),
+        };
+        for date in ["2025-11-27", "1764201600", "2025-11-27T09:30:00+00:00"] {
+            let args = Args::parse_from(["release-note", "--release-date", date]);
+            let release_date = args.release_date.unwrap();
+
+            let rendered = markdown::render_history(
+                &categorized,
+                &Platform::Unknown,
+                "v1.0.0",
+                release_date,
+                release_note::template::DEFAULT_TEMPLATE,
+            )
+            .unwrap();
+            assert!(
+                rendered.starts_with("## v1.0.0 - November 27, 2025"),
+                "{}",
+                rendered
+            );
+        }
+        let err =
+            Args::try_parse_from(["release-note", "--release-date", "yesterday"]).unwrap_err();
+        assert!(err.to_string().contains("RFC 3339"));
+    }
+
+    #[test]
+    fn rejects_ref_name_with_per_tag() {
+        let err = Args::try_parse_from(["release-note", "--per-tag", "--ref-name", "Release"])
+            .unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn rejects_options_unsupported_by_per_tag() {
+        for flags in [
+            &["--merge-repo", "../props"][..],
+            &["--unreleased"],
+            &["--check", "CHANGELOG.md"],
+            &["--output-template-context"],
+            &["--print-contributors"],
+        ] {
+            let err = Args::try_parse_from(["release-note", "--per-tag"].iter().chain(flags))
+                .unwrap_err();
+            assert_eq!(
+                err.kind(),
+                clap::error::ErrorKind::ArgumentConflict,
+                "{:?}",
+                flags
+            );
+        }
+    }
+
+    #[test]
+    fn requires_explicit_range_without_autodetection() {
+        let err =
+            Args::try_parse_from(["release-note", "--no-autodetect-range", "v1.1.0"]).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::MissingRequiredArgument);
+
+        let args =
+            Args::try_parse_from(["release-note", "--no-autodetect-range", "v1.1.0", "v1.0.0"])
+                .unwrap();
+        assert_eq!(args.from.as_deref(), Some("v1.1.0"));
+        assert_eq!(args.to.as_deref(), Some("v1.0.0"));
+    }
+
+    fn render_log(write_style: env_logger::WriteStyle) -> String {
+        #[derive(Clone, Default)]
+        struct Capture(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+        impl Write for Capture {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().write(buf)
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let capture = Capture::default();
+        let logger = log_builder(write_style)
+            .target(env_logger::Target::Pipe(Box::new(capture.clone())))
+            .build();
+        log::Log::log(
+            &logger,
+            &log::Record::builder()
+                .level(log::Level::Warn)
+                .args(format_args!("tag v1.0.0 is not a semantic version"))
+                .build(),
+        );
+
+        String::from_utf8(capture.0.lock().unwrap().clone()).unwrap()
+    }
+
+    #[test]
+    fn rejects_zero_api_timeout() {
+        let err = Args::try_parse_from(["release-note", "--api-timeout", "0"]).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn restricts_hash_length_to_a_full_sha() {
+        for len in ["3", "41"] {
+            let err = Args::try_parse_from(["release-note", "--hash-length", len]).unwrap_err();
+            assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+        }
+
+        let args = Args::try_parse_from(["release-note", "--hash-length", "40"]).unwrap();
+        assert_eq!(args.hash_length, 40);
+    }
+
+    #[test]
+    fn rejects_zero_max_contributors() {
+        let err = Args::try_parse_from(["release-note", "--max-contributors", "0"]).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn rejects_zero_max_per_category() {
+        let err = Args::try_parse_from(["release-note", "--max-per-category", "0"]).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn rejects_zero_first_release_depth() {
+        let err = Args::try_parse_from(["release-note", "--first-release-depth", "0"]).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn normalizes_excluded_paths() {
+        let args = Args::try_parse_from([
+            "release-note",
+            "--exclude-path",
+            "./docs",
+            "--exclude-path",
+            "docs/",
+            "--exclude-path",
+            "ui/../site/assets",
+        ])
+        .unwrap();
+        assert_eq!(
+            args.exclude_path,
+            [
+                PathBuf::from("docs"),
+                PathBuf::from("docs"),
+                PathBuf::from("site/assets")
+            ]
+        );
+
+        for path in [".", "../docs", "/docs"] {
+            let err = Args::try_parse_from(["release-note", "--exclude-path", path]).unwrap_err();
+            assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+        }
+    }
+
+    #[test]
+    fn excludes_several_refs_alongside_to() {
+        let args = Args::try_parse_from([
+            "release-note",
+            "--exclude-ref",
+            "release/1.x",
+            "--exclude-ref",
+            "release/2.x",
+            "v3.0.0",
+            "v2.0.0",
+        ])
+        .unwrap();
+        assert_eq!(args.exclude_ref, ["release/1.x", "release/2.x"]);
+        assert_eq!(args.to.as_deref(), Some("v2.0.0"));
+
+        let err = Args::try_parse_from(["release-note", "--to", "v2.0.0"]).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::UnknownArgument);
+    }
+
+    #[test]
+    fn disables_log_colours_with_no_color() {
+        assert_eq!(
+            log_write_style(false, true, |_| None),
+            env_logger::WriteStyle::Never
+        );
+        assert_eq!(
+            log_write_style(true, false, |key| (key == "NO_COLOR")
+                .then(|| "1".to_string())),
+            env_logger::WriteStyle::Never
+        );
+    }
+
+    #[test]
+    fn forces_log_colours_unless_no_color_is_set() {
+        assert_eq!(
+            log_write_style(true, false, |_| None),
+            env_logger::WriteStyle::Always
+        );
+        assert_eq!(
+            log_write_style(true, false, |key| (key == "NO_COLOR").then(String::new)),
+            env_logger::WriteStyle::Always
+        );
+    }
+
+    #[test]
+    fn writes_uncoloured_logs_with_no_color() {
+        let style = log_write_style(false, true, |_| None);
+        assert_eq!(render_log(style), "tag v1.0.0 is not a semantic version\n");
+    }
+
+    #[test]
+    fn writes_coloured_warnings_when_forced() {
+        let style = log_write_style(true, false, |_| None);
+        let output = render_log(style);
+        assert!(output.contains("\u{1b}["));
+        assert!(output.contains("tag v1.0.0 is not a semantic version"));
+    }
+}
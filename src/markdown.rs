@@ -1,11 +1,16 @@
 use crate::{
     analyzer::{CategorizedCommits, CommitCategory},
+    git::{Commit, GitTrailer},
+    locale::Locale,
     platform::Platform,
+    template::DEFAULT_FOOTER,
 };
 use anyhow::{Context, Result};
 use once_cell::sync::Lazy;
 use regex::Regex;
+use serde::Serialize;
 use std::collections::HashMap;
+use std::io::Write;
 use tera::Value;
 
 static NUMBERED_LIST: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\d+\.\s").unwrap());
@@ -16,6 +21,10 @@ fn is_table_line(line: &str) -> bool {
     (trimmed.starts_with('|') && trimmed.ends_with('|')) || TABLE_SEPARATOR.is_match(trimmed)
 }
 
+fn is_code_fence(line: &str) -> bool {
+    line.trim_start().starts_with("```")
+}
+
 fn is_indented(line: &str) -> bool {
     line.starts_with("    ") || line.starts_with('\t')
 }
@@ -27,7 +36,7 @@ fn is_structured_content(para: &str) -> bool {
             || trimmed.starts_with("* ")
             || trimmed.starts_with("+ ")
             || trimmed.starts_with("> ")
-            || trimmed.starts_with("```")
+            || is_code_fence(line)
             || is_indented(line)
             || NUMBERED_LIST.is_match(trimmed)
             || is_table_line(line)
@@ -41,7 +50,7 @@ fn is_continuation_line(line: &str) -> bool {
         && !trimmed.starts_with("* ")
         && !trimmed.starts_with("+ ")
         && !trimmed.starts_with("> ")
-        && !trimmed.starts_with("```")
+        && !is_code_fence(line)
         && !is_indented(line)
         && !NUMBERED_LIST.is_match(trimmed)
         && !is_table_line(line)
@@ -56,7 +65,7 @@ fn unwrap_structured_content(para: &str) -> String {
     for line in para.lines() {
         let trimmed = line.trim_start();
 
-        if trimmed.starts_with("```") {
+        if is_code_fence(line) {
             if !current_item.is_empty() {
                 result.push(current_item.join(" "));
                 current_item.clear();
@@ -137,11 +146,7 @@ fn unwrap_filter(value: &Value, _args: &HashMap<String, Value>) -> tera::Result<
                 return para.to_string();
             }
 
-            let lines: Vec<&str> = para.lines().collect();
-            if lines
-                .iter()
-                .any(|line| line.trim_start().starts_with("```"))
-            {
+            if para.lines().any(is_code_fence) {
                 para.to_string()
             } else if is_structured_content(para) {
                 unwrap_structured_content(para)
@@ -155,26 +160,81 @@ fn unwrap_filter(value: &Value, _args: &HashMap<String, Value>) -> tera::Result<
     Ok(Value::String(unwrapped_paragraphs.join("\n\n")))
 }
 
-fn mention_filter(value: &Value, _args: &HashMap<String, Value>) -> tera::Result<Value> {
-    if let Some(arr) = value.as_array() {
-        let mentions: Vec<Value> = arr
-            .iter()
-            .filter_map(|v| {
-                if let Some(username) = v.get("username").and_then(|u| u.as_str()) {
-                    Some(Value::String(format!("@{}", username)))
-                } else {
-                    v.as_str().map(|s| Value::String(format!("@{}", s)))
-                }
-            })
-            .collect();
-        Ok(Value::Array(mentions))
-    } else if let Some(s) = value.as_str() {
-        Ok(Value::String(format!("@{}", s)))
-    } else {
-        Err(tera::Error::msg(
-            "mention filter requires a string or array value",
-        ))
-    }
+/// Hard-wraps prose paragraphs at the given width, leaving code blocks, tables, lists
+/// and other structured paragraphs exactly as written.
+fn register_rewrap_filter(tera: &mut tera::Tera, width: usize) {
+    tera.register_filter(
+        "rewrap",
+        move |value: &Value, _args: &HashMap<String, Value>| -> tera::Result<Value> {
+            let text = value
+                .as_str()
+                .ok_or_else(|| tera::Error::msg("rewrap filter requires a string value"))?;
+
+            let mut in_code_block = false;
+            let paragraphs: Vec<String> = text
+                .split("\n\n")
+                .map(|para| {
+                    let fences = para.lines().filter(|line| is_code_fence(line)).count();
+                    let verbatim = in_code_block || fences > 0 || is_structured_content(para);
+                    if fences % 2 == 1 {
+                        in_code_block = !in_code_block;
+                    }
+
+                    if para.trim().is_empty() {
+                        String::new()
+                    } else if verbatim {
+                        para.to_string()
+                    } else {
+                        let (unfilled, _) = textwrap::unfill(para);
+                        textwrap::fill(&unfilled, width)
+                    }
+                })
+                .collect();
+
+            Ok(Value::String(paragraphs.join("\n\n")))
+        },
+    );
+}
+
+fn verbatim_filter(value: &Value, _args: &HashMap<String, Value>) -> tera::Result<Value> {
+    value
+        .as_str()
+        .map(|text| Value::String(text.to_string()))
+        .ok_or_else(|| tera::Error::msg("the unwrap filter requires a string value"))
+}
+
+/// Mentions contributors by their @username or, when display names are enabled, by
+/// their display name linked to their profile.
+fn register_mention_filter(tera: &mut tera::Tera, platform: &Platform, display_names: bool) {
+    let platform = platform.clone();
+    tera.register_filter(
+        "mention",
+        move |value: &Value, _args: &HashMap<String, Value>| -> tera::Result<Value> {
+            let mention = |v: &Value| -> Option<Value> {
+                let username = v
+                    .get("username")
+                    .and_then(|u| u.as_str())
+                    .or_else(|| v.as_str())?;
+                let name = v
+                    .get("name")
+                    .and_then(|n| n.as_str())
+                    .filter(|_| display_names);
+
+                let mention = match (name, platform.user_url(username)) {
+                    (Some(name), Some(url)) => format!("[{}]({})", escape_markdown(name), url),
+                    _ => format!("@{}", username),
+                };
+                Some(Value::String(mention))
+            };
+
+            match value {
+                Value::Array(arr) => Ok(Value::Array(arr.iter().filter_map(mention).collect())),
+                _ => mention(value).ok_or_else(|| {
+                    tera::Error::msg("mention filter requires a string, contributor or array value")
+                }),
+            }
+        },
+    );
 }
 
 fn get_string_array(value: &Value) -> Vec<String> {
@@ -218,19 +278,114 @@ fn prefix_filter(value: &Value, args: &HashMap<String, Value>) -> tera::Result<V
     Ok(Value::Array(filtered))
 }
 
+fn strip_conventional_prefix(text: &str) -> String {
+    static CONVENTIONAL_COMMIT_PREFIX: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"(?i)^[a-z]+(?:\([a-z0-9_.-]+\))?!?\s*:\s*").unwrap());
+
+    CONVENTIONAL_COMMIT_PREFIX.replace(text, "").to_string()
+}
+
 fn strip_conventional_prefix_filter(
     value: &Value,
     _args: &HashMap<String, Value>,
 ) -> tera::Result<Value> {
-    static CONVENTIONAL_COMMIT_PREFIX: Lazy<Regex> =
-        Lazy::new(|| Regex::new(r"(?i)^[a-z]+(?:\([a-z-]+\))?!?\s*:\s*").unwrap());
-
     let text = value.as_str().ok_or_else(|| {
         tera::Error::msg("strip_conventional_prefix filter requires a string value")
     })?;
 
-    let stripped = CONVENTIONAL_COMMIT_PREFIX.replace(text, "").to_string();
-    Ok(Value::String(stripped))
+    Ok(Value::String(strip_conventional_prefix(text)))
+}
+
+/// Commits sharing a conventional type, such as every `wip:` commit within Other.
+#[derive(Serialize)]
+struct TypeGroup<'a> {
+    #[serde(rename = "type")]
+    type_: &'a str,
+    commits: Vec<&'a Commit>,
+}
+
+/// Groups commits by their conventional type in order of first appearance, with any
+/// commits lacking a type grouped last under an empty type.
+fn group_by_type(commits: &[Commit]) -> Vec<TypeGroup<'_>> {
+    let mut groups: Vec<TypeGroup> = Vec::new();
+    for commit in commits {
+        match groups.iter_mut().find(|g| g.type_ == commit.type_) {
+            Some(group) => group.commits.push(commit),
+            None => groups.push(TypeGroup {
+                type_: &commit.type_,
+                commits: vec![commit],
+            }),
+        }
+    }
+    groups.sort_by_key(|group| group.type_.is_empty());
+    groups
+}
+
+/// Collapses dependency updates squash-merged from the same pull request, such as a
+/// grouped Dependabot update, into a single entry listing each update. The entry keeps
+/// the `(#N)` suffix of a squash-merged commit, so it is linked like any other.
+fn merge_dependency_prs(commits: &[Commit]) -> Vec<Commit> {
+    let mut merged: Vec<Commit> = Vec::new();
+    let mut groups: HashMap<u32, (usize, String, Vec<String>)> = HashMap::new();
+
+    for commit in commits {
+        let Some(number) = commit.pull_request_number() else {
+            merged.push(commit.clone());
+            continue;
+        };
+
+        let suffix = format!("(#{})", number);
+        let subject = strip_conventional_prefix(&commit.first_line);
+        let title = subject.trim_end_matches(&suffix).trim_end().to_string();
+
+        match groups.get_mut(&number) {
+            Some((index, prefix, titles)) => {
+                titles.push(title);
+                let entry = &mut merged[*index];
+                entry.first_line = format!("{}{} {}", prefix, titles.join(", "), suffix);
+                for contributor in &commit.contributors {
+                    if !entry
+                        .contributors
+                        .iter()
+                        .any(|c| c.username == contributor.username)
+                    {
+                        entry.contributors.push(contributor.clone());
+                    }
+                }
+            }
+            None => {
+                let prefix =
+                    commit.first_line[..commit.first_line.len() - subject.len()].to_string();
+                groups.insert(number, (merged.len(), prefix, vec![title]));
+                merged.push(commit.clone());
+            }
+        }
+    }
+
+    merged
+}
+
+fn scope_badge_filter(value: &Value, _args: &HashMap<String, Value>) -> tera::Result<Value> {
+    static CONVENTIONAL_COMMIT_SCOPE: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"(?i)^[a-z]+(?:\(([a-z0-9_.-]+)\))?!?\s*:\s*").unwrap());
+
+    let text = value
+        .as_str()
+        .ok_or_else(|| tera::Error::msg("scope_badge filter requires a string value"))?;
+
+    let Some(caps) = CONVENTIONAL_COMMIT_SCOPE.captures(text) else {
+        return Ok(Value::String(text.to_string()));
+    };
+
+    let subject = &text[caps.get(0).unwrap().end()..];
+    match caps.get(1) {
+        Some(scope) => Ok(Value::String(format!(
+            "**[{}]** {}",
+            scope.as_str(),
+            subject
+        ))),
+        None => Ok(Value::String(subject.to_string())),
+    }
 }
 
 fn table_escape_filter(value: &Value, _args: &HashMap<String, Value>) -> tera::Result<Value> {
@@ -241,20 +396,254 @@ fn table_escape_filter(value: &Value, _args: &HashMap<String, Value>) -> tera::R
     Ok(Value::String(text.replace('|', "\\|")))
 }
 
-fn register_platform_functions(tera: &mut tera::Tera, git_ref: &str, platform: &Platform) {
+/// Escapes the characters that would let text taken from a platform, such as a display
+/// name, break out of a link or inject markup.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '(' | ')' | '<' | '>' | '!' | '|' | '~'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn register_trailer_filter(
+    tera: &mut tera::Tera,
+    strip_trailers: Option<&[String]>,
+    keep_trailers: Option<&[String]>,
+) {
+    let normalize = |keys: &[String]| {
+        keys.iter()
+            .map(|k| k.trim().to_ascii_lowercase())
+            .collect::<Vec<_>>()
+    };
+    let strip_trailers = strip_trailers.map(normalize);
+    let keep_trailers = keep_trailers.map(normalize);
+
+    tera.register_filter(
+        "kept_trailers",
+        move |value: &Value, _args: &HashMap<String, Value>| -> tera::Result<Value> {
+            if strip_trailers.is_none() && keep_trailers.is_none() {
+                return Ok(Value::String(String::new()));
+            }
+
+            let trailers: Vec<GitTrailer> = serde_json::from_value(value.clone())
+                .map_err(|_| tera::Error::msg("kept_trailers filter requires commit trailers"))?;
+
+            let kept: Vec<String> = trailers
+                .iter()
+                .filter(|t| {
+                    let key = t.key().to_ascii_lowercase();
+                    keep_trailers
+                        .as_ref()
+                        .is_none_or(|keep| keep.contains(&key))
+                        && strip_trailers
+                            .as_ref()
+                            .is_none_or(|strip| !strip.contains(&key))
+                })
+                .map(|t| t.to_string())
+                .collect();
+
+            Ok(Value::String(kept.join("\n")))
+        },
+    );
+}
+
+fn register_category_cap_filter(tera: &mut tera::Tera, max_per_category: Option<usize>) {
+    tera.register_filter(
+        "capped",
+        move |value: &Value, _args: &HashMap<String, Value>| -> tera::Result<Value> {
+            let commits = value
+                .as_array()
+                .ok_or_else(|| tera::Error::msg("capped filter requires a list of commits"))?;
+
+            let shown = match max_per_category {
+                Some(max) => commits.iter().take(max).cloned().collect(),
+                None => commits.clone(),
+            };
+            Ok(Value::Array(shown))
+        },
+    );
+}
+
+fn register_short_hash_filter(tera: &mut tera::Tera, hash_length: usize) {
+    tera.register_filter(
+        "short_hash",
+        move |value: &Value, args: &HashMap<String, Value>| -> tera::Result<Value> {
+            let hash = value
+                .as_str()
+                .ok_or_else(|| tera::Error::msg("short_hash filter requires a string"))?;
+            let len = args
+                .get("len")
+                .and_then(|v| v.as_u64())
+                .map_or(hash_length, |len| len as usize);
+
+            Ok(Value::String(hash.chars().take(len).collect()))
+        },
+    );
+}
+
+fn register_stat_function(tera: &mut tera::Tera, style: StatsStyle) {
+    tera.register_function(
+        "stat",
+        move |args: &HashMap<String, Value>| -> tera::Result<Value> {
+            let count = args
+                .get("count")
+                .and_then(|v| v.as_u64())
+                .ok_or_else(|| tera::Error::msg("stat requires a numeric 'count'"))?;
+            let anchor = args.get("anchor").and_then(|v| v.as_str()).unwrap_or("");
+
+            let stat = match style {
+                StatsStyle::Linked => format!("[**`{}`**](#{})", count, anchor),
+                StatsStyle::Bold => format!("**`{}`**", count),
+                StatsStyle::Plain => count.to_string(),
+            };
+            Ok(Value::String(stat))
+        },
+    );
+}
+
+/// The platform to link a commit with: that of the repository it came from in a merged
+/// note, otherwise the platform the note is rendered for.
+fn repository_platform<'a>(
+    platform: &'a Platform,
+    repository_platforms: &'a HashMap<String, Platform>,
+    repository: Option<&str>,
+) -> &'a Platform {
+    repository
+        .and_then(|name| repository_platforms.get(name))
+        .unwrap_or(platform)
+}
+
+fn repository_arg(args: &HashMap<String, Value>) -> Option<&str> {
+    args.get("repository").and_then(|v| v.as_str())
+}
+
+fn register_platform_functions(
+    tera: &mut tera::Tera,
+    git_ref: &str,
+    platform: &Platform,
+    repository_platforms: &HashMap<String, Platform>,
+    fallback_web_url: Option<&str>,
+    jira_url: Option<&str>,
+    hash_length: usize,
+) {
     let platform = platform.clone();
+    let repository_platforms = repository_platforms.clone();
+
+    tera.register_function("issue_link", {
+        let platform = platform.clone();
+        let repository_platforms = repository_platforms.clone();
+        let jira_url = jira_url.map(|url| url.trim_end_matches('/').to_string());
+        move |args: &HashMap<String, Value>| -> tera::Result<Value> {
+            let platform =
+                repository_platform(&platform, &repository_platforms, repository_arg(args));
+            let issue = args
+                .get("issue")
+                .ok_or_else(|| tera::Error::msg("issue_link requires 'issue'"))?;
+            let number = issue
+                .get("number")
+                .and_then(|v| v.as_u64())
+                .ok_or_else(|| tera::Error::msg("issue_link requires an issue with a number"))?;
+            let field = |name: &str| issue.get(name).and_then(|v| v.as_str());
+
+            let link = if let Some(project) = field("project") {
+                let key = format!("{}-{}", project, number);
+                match &jira_url {
+                    Some(base) => format!("[{}]({}/browse/{})", key, base, key),
+                    None => key,
+                }
+            } else if let (Some(owner), Some(repo)) = (field("owner"), field("repo")) {
+                format!("{}/{}#{}", owner, repo, number)
+            } else {
+                match platform.issue_url(number as u32) {
+                    Some(url) => format!("[#{}]({})", number, url),
+                    None => format!("#{}", number),
+                }
+            };
+
+            Ok(Value::String(link))
+        }
+    });
+
+    tera.register_filter("link_pull_requests", {
+        static PULL_REQUESTS: Lazy<Regex> =
+            Lazy::new(|| Regex::new(r"\((#\d+(?:\s*,\s*#\d+)*)\)$").unwrap());
+        static PULL_REQUEST: Lazy<Regex> = Lazy::new(|| Regex::new(r"#(\d+)").unwrap());
+
+        let platform = platform.clone();
+        let repository_platforms = repository_platforms.clone();
+        move |value: &Value, args: &HashMap<String, Value>| -> tera::Result<Value> {
+            let text = value.as_str().ok_or_else(|| {
+                tera::Error::msg("link_pull_requests filter requires a string value")
+            })?;
+            let platform =
+                repository_platform(&platform, &repository_platforms, repository_arg(args));
+
+            let linked = PULL_REQUESTS.replace(text, |caps: &regex::Captures| {
+                let refs = PULL_REQUEST.replace_all(&caps[1], |pr: &regex::Captures| {
+                    match pr[1]
+                        .parse()
+                        .ok()
+                        .and_then(|n| platform.pull_request_url(n))
+                    {
+                        Some(url) => format!("[#{}]({})", &pr[1], url),
+                        None => pr[0].to_string(),
+                    }
+                });
+                format!("({})", refs)
+            });
+            Ok(Value::String(linked.into_owned()))
+        }
+    });
+
+    tera.register_function("pull_request_link", {
+        let platform = platform.clone();
+        let repository_platforms = repository_platforms.clone();
+        move |args: &HashMap<String, Value>| -> tera::Result<Value> {
+            let number = args
+                .get("number")
+                .and_then(|v| v.as_u64())
+                .ok_or_else(|| tera::Error::msg("pull_request_link requires 'number'"))?;
+            let platform =
+                repository_platform(&platform, &repository_platforms, repository_arg(args));
+
+            match platform.pull_request_url(number as u32) {
+                Some(url) => Ok(Value::String(format!("[#{}]({})", number, url))),
+                None => Ok(Value::String(format!("#{}", number))),
+            }
+        }
+    });
 
     tera.register_function("commit_url", {
         let platform = platform.clone();
+        let repository_platforms = repository_platforms.clone();
+        let fallback_web_url = fallback_web_url.map(|url| url.trim_end_matches('/').to_string());
         move |args: &HashMap<String, Value>| -> tera::Result<Value> {
             let sha = args
                 .get("sha")
                 .and_then(|v| v.as_str())
                 .ok_or_else(|| tera::Error::msg("commit_url requires 'sha'"))?;
+            let platform =
+                repository_platform(&platform, &repository_platforms, repository_arg(args));
 
-            let short_sha = &sha[..7.min(sha.len())];
+            let short_sha = match args.get("text").and_then(|v| v.as_str()) {
+                Some(text) => text,
+                None => &sha[..hash_length.min(sha.len())],
+            };
 
-            if let Some(url) = platform.commit_url(sha) {
+            let url = platform.commit_url(sha).or_else(|| {
+                fallback_web_url
+                    .as_ref()
+                    .map(|base| format!("{}/commit/{}", base, sha))
+            });
+
+            if let Some(url) = url {
                 Ok(Value::String(format!("[**`{}`**]({})", short_sha, url)))
             } else {
                 Ok(Value::String(format!("**`{}`**", short_sha)))
@@ -270,6 +659,11 @@ fn register_platform_functions(tera: &mut tera::Tera, git_ref: &str, platform: &
             let since = args.get("since").and_then(|v| v.as_str()).unwrap_or("");
             let until = args.get("until").and_then(|v| v.as_str()).unwrap_or("");
 
+            // The counts of a merged note span several repositories, so no single
+            // repository's commit history matches them
+            if !repository_platforms.is_empty() {
+                return Ok(Value::Null);
+            }
             if let Some(url) = platform.commits_url(&git_ref, author, since, until) {
                 Ok(Value::String(url))
             } else {
@@ -279,6 +673,83 @@ fn register_platform_functions(tera: &mut tera::Tera, git_ref: &str, platform: &
     });
 }
 
+const FRONT_MATTER_TEMPLATE: &str = r#"---
+title: {% if unreleased %}{{ i18n.unreleased | json_encode }}{% else %}{{ git_ref | json_encode }}{% endif %}
+date: {{ release_date | date(format="%Y-%m-%d") }}
+---
+"#;
+
+/// How each count within the stats line beneath the heading is rendered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum StatsStyle {
+    /// Bold counts linking to their section, e.g. [**`2`**](#new-features)
+    #[default]
+    Linked,
+    /// Bold counts without a link
+    Bold,
+    /// Counts as plain text
+    Plain,
+}
+
+pub const DEFAULT_STATS_SEPARATOR: &str = " • ";
+
+pub const DEFAULT_BODY_INDENT: &str = "  ";
+
+pub const DEFAULT_HASH_LENGTH: usize = 7;
+
+pub const DEFAULT_DATE_FORMAT: &str = "%B %d, %Y";
+
+/// Fails if the strftime-style format contains a specifier chrono does not recognize,
+/// which would otherwise abort rendering.
+pub fn validate_date_format(format: &str) -> Result<()> {
+    if chrono::format::StrftimeItems::new(format)
+        .any(|item| matches!(item, chrono::format::Item::Error))
+    {
+        anyhow::bail!("'{}' is not a valid date format", format);
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RenderOptions {
+    pub lang: Locale,
+    pub no_stats: bool,
+    pub stats_separator: Option<String>,
+    pub stats_style: StatsStyle,
+    pub no_wrap: bool,
+    pub wrap_width: Option<usize>,
+    pub body_indent: Option<String>,
+    pub unreleased: bool,
+    pub ref_name: Option<String>,
+    /// The annotation message of the release tag, exposed to templates as `tag_message`
+    pub tag_message: Option<String>,
+    /// A link comparing the release against its base, exposed to templates as
+    /// `compare_url`
+    pub compare_url: Option<String>,
+    pub strip_trailers: Option<Vec<String>>,
+    pub keep_trailers: Option<Vec<String>>,
+    pub front_matter: bool,
+    pub fallback_web_url: Option<String>,
+    pub max_contributors: Option<usize>,
+    pub jira_url: Option<String>,
+    pub collapsible: bool,
+    pub max_per_category: Option<usize>,
+    pub footer: Option<String>,
+    pub no_footer: bool,
+    pub always_show_sections: bool,
+    pub merge_dependency_prs: bool,
+    pub commit_badges: bool,
+    pub no_avatars: bool,
+    pub hash_length: Option<usize>,
+    pub no_contributors_section: bool,
+    pub display_names: bool,
+    pub section_titles: HashMap<CommitCategory, String>,
+    pub date_format: Option<String>,
+    /// The platform of each repository in a merged note, by repository name, so each
+    /// commit links to the repository it came from.
+    pub repository_platforms: HashMap<String, Platform>,
+}
+
 pub fn render_history(
     categorized: &CategorizedCommits,
     platform: &Platform,
@@ -286,33 +757,380 @@ pub fn render_history(
     release_date: i64,
     template: &str,
 ) -> Result<String> {
+    render_history_with_options(
+        categorized,
+        platform,
+        git_ref,
+        release_date,
+        template,
+        &RenderOptions::default(),
+    )
+}
+
+pub fn render_history_with_options(
+    categorized: &CategorizedCommits,
+    platform: &Platform,
+    git_ref: &str,
+    release_date: i64,
+    template: &str,
+    options: &RenderOptions,
+) -> Result<String> {
+    render_history_with_tera(
+        categorized,
+        platform,
+        git_ref,
+        release_date,
+        template,
+        options,
+        &mut tera::Tera::default(),
+    )
+}
+
+/// Renders the release note with a caller-provided Tera instance, allowing custom
+/// filters and functions to be registered ahead of rendering. The built-in filters and
+/// functions are registered on top and take precedence over any with the same name.
+pub fn render_history_with_tera(
+    categorized: &CategorizedCommits,
+    platform: &Platform,
+    git_ref: &str,
+    release_date: i64,
+    template: &str,
+    options: &RenderOptions,
+    tera: &mut tera::Tera,
+) -> Result<String> {
+    let mut rendered = Vec::new();
+    write_history(
+        &mut rendered,
+        categorized,
+        platform,
+        git_ref,
+        release_date,
+        template,
+        options,
+        tera,
+    )?;
+    String::from_utf8(rendered).context("rendered release note is not valid UTF-8")
+}
+
+/// Streams the release note directly to a writer, rather than buffering it in memory.
+/// The output is identical to that of [`render_history_with_options`].
+pub fn render_history_to(
+    writer: &mut impl Write,
+    categorized: &CategorizedCommits,
+    platform: &Platform,
+    git_ref: &str,
+    release_date: i64,
+    template: &str,
+    options: &RenderOptions,
+) -> Result<()> {
+    write_history(
+        writer,
+        categorized,
+        platform,
+        git_ref,
+        release_date,
+        template,
+        options,
+        &mut tera::Tera::default(),
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn write_history(
+    writer: &mut impl Write,
+    categorized: &CategorizedCommits,
+    platform: &Platform,
+    git_ref: &str,
+    release_date: i64,
+    template: &str,
+    options: &RenderOptions,
+    tera: &mut tera::Tera,
+) -> Result<()> {
     if categorized.by_category.is_empty() {
-        return Ok(String::new());
+        return Ok(());
+    }
+
+    if let Some(format) = &options.date_format {
+        validate_date_format(format)?;
     }
 
-    let mut tera = tera::Tera::default();
     tera.add_raw_template("main", template)
         .context("failed to parse template")?;
 
-    tera.register_filter("unwrap", unwrap_filter);
-    tera.register_filter("mention", mention_filter);
+    register_builtins(tera, git_ref, platform, options);
+    let context = build_context(categorized, git_ref, release_date, options);
+
+    if options.front_matter {
+        tera.add_raw_template("front_matter", FRONT_MATTER_TEMPLATE)
+            .context("failed to parse front matter template")?;
+        let front_matter = tera
+            .render("front_matter", &context)
+            .context("failed to render front matter")?;
+        writeln!(writer, "{}", front_matter)?;
+    }
+
+    tera.render_to("main", &context, TrimStart::new(writer))
+        .context("failed to render template")?;
+    Ok(())
+}
+
+/// Drops any leading whitespace written ahead of the first visible character, so
+/// whitespace emitted by macros at the top of a template never reaches the output.
+struct TrimStart<W> {
+    inner: W,
+    started: bool,
+}
+
+impl<W: Write> TrimStart<W> {
+    fn new(inner: W) -> Self {
+        Self {
+            inner,
+            started: false,
+        }
+    }
+}
+
+impl<W: Write> Write for TrimStart<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.started {
+            return self.inner.write(buf);
+        }
+
+        if let Some(start) = buf.iter().position(|b| !b.is_ascii_whitespace()) {
+            self.started = true;
+            self.inner.write_all(&buf[start..])?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn register_builtins(
+    tera: &mut tera::Tera,
+    git_ref: &str,
+    platform: &Platform,
+    options: &RenderOptions,
+) {
+    if options.no_wrap {
+        tera.register_filter("unwrap", verbatim_filter);
+    } else {
+        tera.register_filter("unwrap", unwrap_filter);
+    }
+    if let Some(width) = options.wrap_width {
+        let indent = options
+            .body_indent
+            .as_deref()
+            .unwrap_or(DEFAULT_BODY_INDENT)
+            .chars()
+            .count();
+        register_rewrap_filter(tera, width.saturating_sub(indent).max(1));
+    }
     tera.register_filter("prefix", prefix_filter);
     tera.register_filter(
         "strip_conventional_prefix",
         strip_conventional_prefix_filter,
     );
+    tera.register_filter("scope_badge", scope_badge_filter);
     tera.register_filter("table_escape", table_escape_filter);
 
-    register_platform_functions(&mut tera, git_ref, platform);
+    let hash_length = options.hash_length.unwrap_or(DEFAULT_HASH_LENGTH);
+    register_trailer_filter(
+        tera,
+        options.strip_trailers.as_deref(),
+        options.keep_trailers.as_deref(),
+    );
+    register_category_cap_filter(tera, options.max_per_category);
+    register_mention_filter(tera, platform, options.display_names);
+    register_short_hash_filter(tera, hash_length);
+    register_stat_function(tera, options.stats_style);
+    register_platform_functions(
+        tera,
+        git_ref,
+        platform,
+        &options.repository_platforms,
+        options.fallback_web_url.as_deref(),
+        options.jira_url.as_deref(),
+        hash_length,
+    );
+}
+
+/// A release date at or before the Unix epoch is a missing timestamp rather than a
+/// real date, so the newest commit in the release is dated instead. The current time
+/// is only used if no commit carries a valid timestamp either.
+fn valid_release_date(release_date: i64, categorized: &CategorizedCommits) -> i64 {
+    if release_date > 0 {
+        return release_date;
+    }
+
+    let newest_commit = categorized
+        .by_category
+        .values()
+        .flatten()
+        .map(|commit| commit.timestamp)
+        .filter(|&timestamp| timestamp > 0)
+        .max();
 
+    if let Some(timestamp) = newest_commit {
+        log::warn!(
+            "invalid release date {}; using the date of the newest commit instead",
+            release_date
+        );
+        return timestamp;
+    }
+
+    log::warn!(
+        "invalid release date {}; using the current time instead",
+        release_date
+    );
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |now| now.as_secs() as i64)
+}
+
+/// The translation key of the title the default template gives a category's section.
+fn section_title_key(category: &CommitCategory) -> Option<&'static str> {
+    match category {
+        CommitCategory::Breaking => Some("breaking_changes"),
+        CommitCategory::Deprecation => Some("deprecations"),
+        CommitCategory::Hotfix => Some("hot_fixes"),
+        CommitCategory::Feature => Some("new_features"),
+        CommitCategory::Fix => Some("bug_fixes"),
+        CommitCategory::Performance => Some("performance_improvements"),
+        CommitCategory::Revert => Some("reverts"),
+        CommitCategory::Dependencies => Some("dependency_updates"),
+        _ => None,
+    }
+}
+
+/// Derives the anchor GitHub generates for a heading: lowercased, with punctuation
+/// removed and spaces replaced by hyphens.
+fn heading_anchor(title: &str) -> String {
+    title
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+fn build_context(
+    categorized: &CategorizedCommits,
+    git_ref: &str,
+    release_date: i64,
+    options: &RenderOptions,
+) -> tera::Context {
     let mut context = tera::Context::new();
     context.insert("contributors", &categorized.contributors);
-    context.insert("git_ref", git_ref);
-    context.insert("release_date", &release_date);
+    context.insert("git_ref", options.ref_name.as_deref().unwrap_or(git_ref));
+    context.insert("tag_message", &options.tag_message);
+    context.insert("compare_url", &options.compare_url);
+    context.insert(
+        "release_date",
+        &valid_release_date(release_date, categorized),
+    );
+    context.insert(
+        "date_format",
+        options
+            .date_format
+            .as_deref()
+            .unwrap_or(DEFAULT_DATE_FORMAT),
+    );
+    context.insert("lang", options.lang.code());
+    context.insert("no_stats", &options.no_stats);
+    context.insert(
+        "stats_separator",
+        options
+            .stats_separator
+            .as_deref()
+            .unwrap_or(DEFAULT_STATS_SEPARATOR),
+    );
+    context.insert("unreleased", &options.unreleased);
+    let body_indent = options
+        .body_indent
+        .as_deref()
+        .unwrap_or(DEFAULT_BODY_INDENT);
+    context.insert("body_indent", body_indent);
+    // A visible prefix (e.g. a blockquote) must continue across paragraph breaks
+    context.insert("indent_blank_lines", &!body_indent.trim().is_empty());
+    context.insert("wrap_width", &options.wrap_width);
+    context.insert("max_contributors", &options.max_contributors);
+    context.insert("collapsible", &options.collapsible);
+    context.insert("commit_badges", &options.commit_badges);
+    context.insert("no_avatars", &options.no_avatars);
+    context.insert("no_contributors_section", &options.no_contributors_section);
+    context.insert("always_show_sections", &options.always_show_sections);
+    context.insert(
+        "footer",
+        if options.no_footer {
+            ""
+        } else {
+            options.footer.as_deref().unwrap_or(DEFAULT_FOOTER)
+        },
+    );
+    let mut i18n = options.lang.translations();
+    for (category, title) in &options.section_titles {
+        if let Some(key) = section_title_key(category) {
+            i18n.insert(key, title);
+        }
+    }
+    context.insert("i18n", &i18n);
+
+    // Stats link to each section by an anchor derived from its title, so that renamed
+    // sections are still linked to the heading the platform generates for them
+    let anchors: HashMap<&str, String> = [
+        ("breaking", CommitCategory::Breaking),
+        ("hotfixes", CommitCategory::Hotfix),
+        ("features", CommitCategory::Feature),
+        ("fixes", CommitCategory::Fix),
+        ("deprecations", CommitCategory::Deprecation),
+    ]
+    .into_iter()
+    .filter_map(|(section, category)| {
+        let anchor = match options.section_titles.get(&category) {
+            Some(title) => heading_anchor(title),
+            None => category.anchor()?.to_string(),
+        };
+        Some((section, anchor))
+    })
+    .collect();
+    context.insert("anchors", &anchors);
+
+    if options.always_show_sections {
+        for section in [
+            "breaking",
+            "build",
+            "chore",
+            "ci",
+            "dependencies",
+            "deprecations",
+            "docs",
+            "features",
+            "fixes",
+            "hotfixes",
+            "other",
+            "perf",
+            "reverts",
+            "refactor",
+            "style",
+            "test",
+        ] {
+            context.insert(section, &Vec::<Value>::new());
+        }
+    }
 
     if let Some(breaking) = categorized.by_category.get(&CommitCategory::Breaking) {
         context.insert("breaking", breaking);
     }
+    if let Some(build) = categorized.by_category.get(&CommitCategory::Build) {
+        context.insert("build", build);
+    }
     if let Some(chore) = categorized.by_category.get(&CommitCategory::Chore) {
         context.insert("chore", chore);
     }
@@ -320,7 +1138,14 @@ pub fn render_history(
         context.insert("ci", ci);
     }
     if let Some(dependencies) = categorized.by_category.get(&CommitCategory::Dependencies) {
-        context.insert("dependencies", dependencies);
+        if options.merge_dependency_prs {
+            context.insert("dependencies", &merge_dependency_prs(dependencies));
+        } else {
+            context.insert("dependencies", dependencies);
+        }
+    }
+    if let Some(deprecations) = categorized.by_category.get(&CommitCategory::Deprecation) {
+        context.insert("deprecations", deprecations);
     }
     if let Some(docs) = categorized.by_category.get(&CommitCategory::Documentation) {
         context.insert("docs", docs);
@@ -331,22 +1156,197 @@ pub fn render_history(
     if let Some(fixes) = categorized.by_category.get(&CommitCategory::Fix) {
         context.insert("fixes", fixes);
     }
+    if let Some(hotfixes) = categorized.by_category.get(&CommitCategory::Hotfix) {
+        context.insert("hotfixes", hotfixes);
+    }
     if let Some(other) = categorized.by_category.get(&CommitCategory::Other) {
         context.insert("other", other);
+        context.insert("other_by_type", &group_by_type(other));
     }
     if let Some(perf) = categorized.by_category.get(&CommitCategory::Performance) {
         context.insert("perf", perf);
     }
+    if let Some(reverts) = categorized.by_category.get(&CommitCategory::Revert) {
+        context.insert("reverts", reverts);
+    }
     if let Some(refactor) = categorized.by_category.get(&CommitCategory::Refactor) {
         context.insert("refactor", refactor);
     }
+    if let Some(style) = categorized.by_category.get(&CommitCategory::Style) {
+        context.insert("style", style);
+    }
     if let Some(test) = categorized.by_category.get(&CommitCategory::Test) {
         context.insert("test", test);
     }
 
-    let rendered = tera
-        .render("main", &context)
-        .context("failed to render template")?;
+    context
+}
+
+/// The version of the exported template context, raised whenever a field is renamed
+/// or removed so consumers can detect an incompatible schema.
+pub const TEMPLATE_CONTEXT_SCHEMA_VERSION: u32 = 1;
+
+/// The context a template is rendered with, for template authors to inspect. It is
+/// tagged with a top-level `schema_version`.
+pub fn template_context(
+    categorized: &CategorizedCommits,
+    platform: &Platform,
+    git_ref: &str,
+    release_date: i64,
+    options: &RenderOptions,
+) -> serde_json::Value {
+    let mut context = build_context(categorized, git_ref, release_date, options).into_json();
+    if let Value::Object(sections) = &mut context {
+        for commits in sections.values_mut().filter_map(|v| v.as_array_mut()) {
+            for commit in commits.iter_mut().filter_map(|v| v.as_object_mut()) {
+                resolve_commit_links(commit, categorized, platform, options);
+            }
+        }
+        sections.insert(
+            "schema_version".to_string(),
+            TEMPLATE_CONTEXT_SCHEMA_VERSION.into(),
+        );
+    }
+    context
+}
+
+/// Adds the resolved URLs of a commit, its pull request and its linked issues, so
+/// consumers of the exported context don't need to derive them from the platform.
+fn resolve_commit_links(
+    commit: &mut serde_json::Map<String, Value>,
+    categorized: &CategorizedCommits,
+    platform: &Platform,
+    options: &RenderOptions,
+) {
+    let Some(hash) = commit
+        .get("hash")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+    else {
+        return;
+    };
+    let Some(source) = categorized
+        .by_category
+        .values()
+        .flatten()
+        .find(|c| c.hash == hash)
+    else {
+        return;
+    };
+    let platform = repository_platform(
+        platform,
+        &options.repository_platforms,
+        source.repository.as_deref(),
+    );
+
+    let fallback_web_url = options
+        .fallback_web_url
+        .as_deref()
+        .map(|url| url.trim_end_matches('/'));
+    let commit_url = platform
+        .commit_url(&hash)
+        .or_else(|| fallback_web_url.map(|base| format!("{}/commit/{}", base, hash)));
+    commit.insert("commit_url".to_string(), commit_url.into());
+    commit.insert(
+        "pull_request_url".to_string(),
+        source
+            .pull_request_number()
+            .and_then(|number| platform.pull_request_url(number))
+            .into(),
+    );
+
+    let jira_url = options
+        .jira_url
+        .as_deref()
+        .map(|url| url.trim_end_matches('/'));
+    if let Some(Value::Array(issues)) = commit.get_mut("linked_issues") {
+        for (issue, linked) in issues.iter_mut().zip(&source.linked_issues) {
+            let url = match (&linked.project, &linked.owner, &linked.repo) {
+                (Some(project), _, _) => {
+                    jira_url.map(|base| format!("{}/browse/{}-{}", base, project, linked.number))
+                }
+                (None, Some(owner), Some(repo)) => {
+                    platform.repository_issue_url(owner, repo, linked.number)
+                }
+                _ => platform.issue_url(linked.number),
+            };
+            if let Value::Object(issue) = issue {
+                issue.insert("url".to_string(), url.into());
+            }
+        }
+    }
+}
+
+/// Compares a rendered release note against the contents of an existing file, ignoring
+/// any trailing newlines. Returns a unified diff from the file to the rendered note
+/// when they differ.
+pub fn diff_release_note(existing: &str, rendered: &str, path: &str) -> Option<String> {
+    let existing = format!("{}\n", existing.trim_end_matches('\n'));
+    let rendered = format!("{}\n", rendered.trim_end_matches('\n'));
+    if existing == rendered {
+        return None;
+    }
+
+    Some(
+        similar::TextDiff::from_lines(&existing, &rendered)
+            .unified_diff()
+            .header(path, "release-note")
+            .to_string(),
+    )
+}
+
+/// Renders a template against the given commits, collecting every reference to a
+/// variable missing from the context rather than failing on the first. Each missing
+/// variable is stubbed out so rendering can continue past it.
+pub fn validate_template_context(
+    template: &str,
+    categorized: &CategorizedCommits,
+    platform: &Platform,
+    options: &RenderOptions,
+) -> Result<Vec<String>> {
+    static UNDEFINED_VARIABLE: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"Variable `([^`]+)` not found").unwrap());
+
+    let mut tera = tera::Tera::default();
+    tera.add_raw_template("main", template)
+        .context("failed to parse template")?;
+    register_builtins(&mut tera, "HEAD", platform, options);
+    let mut context = build_context(categorized, "HEAD", 0, options);
+
+    let mut undefined = Vec::new();
+    loop {
+        let Err(err) = tera.render("main", &context) else {
+            break;
+        };
+
+        let mut source: Option<&dyn std::error::Error> = Some(&err);
+        let mut missing = None;
+        while let Some(e) = source {
+            if let Some(caps) = UNDEFINED_VARIABLE.captures(&e.to_string()) {
+                missing = Some((e.to_string(), caps[1].to_string()));
+                break;
+            }
+            source = e.source();
+        }
+
+        let Some((message, variable)) = missing else {
+            break;
+        };
+        let root = variable
+            .split(['.', '['])
+            .next()
+            .unwrap_or(&variable)
+            .to_string();
+        if undefined.contains(&message) {
+            break;
+        }
+        undefined.push(message);
+
+        if context.contains_key(&root) {
+            break;
+        }
+        context.insert(root, "");
+    }
 
-    Ok(rendered.trim_start().to_string())
+    Ok(undefined)
 }
@@ -16,16 +16,80 @@ pub enum Platform {
         project_path: String,
         token: Option<String>,
     },
+    Gitea {
+        url: String,
+        api_url: String,
+        owner: String,
+        repo: String,
+        token: Option<String>,
+    },
     Unknown,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlatformKind {
+    GitHub,
+    GitLab,
+    Gitea,
+}
+
 impl Platform {
+    /// Constructs a platform from explicit settings, bypassing any detection from
+    /// CI environment variables or the origin URL.
+    pub fn from_config(
+        kind: PlatformKind,
+        owner: &str,
+        repo: &str,
+        api_url: &str,
+        token: Option<String>,
+    ) -> Self {
+        let api_url = api_url.trim_end_matches('/').to_string();
+
+        match kind {
+            PlatformKind::GitHub => {
+                let server_url = if api_url == "https://api.github.com" {
+                    "https://github.com"
+                } else {
+                    api_url.strip_suffix("/api/v3").unwrap_or(&api_url)
+                };
+                Platform::GitHub {
+                    url: format!("{}/{}/{}", server_url, owner, repo),
+                    owner: owner.to_string(),
+                    repo: repo.to_string(),
+                    api_url,
+                    token,
+                }
+            }
+            PlatformKind::GitLab => {
+                let server_url = api_url.strip_suffix("/api/v4").unwrap_or(&api_url);
+                let project_path = format!("{}/{}", owner, repo);
+                Platform::GitLab {
+                    url: format!("{}/{}", server_url, project_path),
+                    graphql_url: format!("{}/api/graphql", server_url),
+                    project_path,
+                    api_url,
+                    token,
+                }
+            }
+            PlatformKind::Gitea => {
+                let server_url = api_url.strip_suffix("/api/v1").unwrap_or(&api_url);
+                Platform::Gitea {
+                    url: format!("{}/{}/{}", server_url, owner, repo),
+                    owner: owner.to_string(),
+                    repo: repo.to_string(),
+                    api_url,
+                    token,
+                }
+            }
+        }
+    }
+
     pub fn detect(origin_url: Option<&str>, trusted_hosts: &[String]) -> Self {
         let (platform, from_ci) = if let Some(platform) = Self::from_ci_env() {
             (platform, true)
         } else {
             match origin_url {
-                Some(url) => (Self::from_origin_url(url), false),
+                Some(url) => (Self::detect_from_remote_url(url), false),
                 None => {
                     log::warn!("no origin URL and not running in CI");
                     return Platform::Unknown;
@@ -33,6 +97,123 @@ impl Platform {
             }
         };
 
+        Self::attach_token(platform, from_ci, trusted_hosts)
+    }
+
+    /// Detects the platform from CI environment variables alone, never falling back to
+    /// the origin URL. Returns `None` when not running in a recognized CI environment.
+    pub fn detect_from_env_only() -> Option<Self> {
+        Self::from_ci_env().map(|platform| Self::attach_token(platform, true, &[]))
+    }
+
+    /// Detects the platform from an explicit project URL, taking precedence over both
+    /// CI environment variables and the origin URL of the repository.
+    pub fn detect_from_base_url(base_url: &str, trusted_hosts: &[String]) -> Self {
+        Self::detect_from_remote_url(base_url).with_trusted_token(trusted_hosts)
+    }
+
+    /// Attaches the platform's token from the environment, provided its host is trusted.
+    pub fn with_trusted_token(self, trusted_hosts: &[String]) -> Self {
+        Self::attach_token(self, false, trusted_hosts)
+    }
+
+    /// Replaces the web host used to build commit, issue and commit-history links,
+    /// keeping the detected API host. Useful when the origin is a mirror of the
+    /// canonical project.
+    pub fn with_web_url(self, base_url: &str) -> Self {
+        let base_url = base_url.trim_end_matches('/');
+        match self {
+            Platform::GitHub {
+                api_url,
+                owner,
+                repo,
+                token,
+                ..
+            } => Platform::GitHub {
+                url: format!("{}/{}/{}", base_url, owner, repo),
+                api_url,
+                owner,
+                repo,
+                token,
+            },
+            Platform::GitLab {
+                api_url,
+                graphql_url,
+                project_path,
+                token,
+                ..
+            } => Platform::GitLab {
+                url: format!("{}/{}", base_url, project_path),
+                api_url,
+                graphql_url,
+                project_path,
+                token,
+            },
+            Platform::Gitea {
+                api_url,
+                owner,
+                repo,
+                token,
+                ..
+            } => Platform::Gitea {
+                url: format!("{}/{}/{}", base_url, owner, repo),
+                api_url,
+                owner,
+                repo,
+                token,
+            },
+            Platform::Unknown => Platform::Unknown,
+        }
+    }
+
+    /// Replaces the API token, taking precedence over any token read from the
+    /// environment.
+    pub fn with_token(self, token: String) -> Self {
+        match self {
+            Platform::GitHub {
+                url,
+                api_url,
+                owner,
+                repo,
+                ..
+            } => Platform::GitHub {
+                url,
+                api_url,
+                owner,
+                repo,
+                token: Some(token),
+            },
+            Platform::GitLab {
+                url,
+                api_url,
+                graphql_url,
+                project_path,
+                ..
+            } => Platform::GitLab {
+                url,
+                api_url,
+                graphql_url,
+                project_path,
+                token: Some(token),
+            },
+            Platform::Gitea {
+                url,
+                api_url,
+                owner,
+                repo,
+                ..
+            } => Platform::Gitea {
+                url,
+                api_url,
+                owner,
+                repo,
+                token: Some(token),
+            },
+            Platform::Unknown => Platform::Unknown,
+        }
+    }
+
+    fn attach_token(platform: Platform, from_ci: bool, trusted_hosts: &[String]) -> Self {
         match platform {
             Platform::GitHub {
                 url,
@@ -45,8 +226,8 @@ impl Platform {
                     &url,
                     from_ci,
                     trusted_hosts,
-                    "GITHUB_TOKEN",
-                    "no GITHUB_TOKEN found; API requests may be rate limited",
+                    &["GITHUB_TOKEN", "GH_TOKEN"],
+                    "no GITHUB_TOKEN or GH_TOKEN found; API requests may be rate limited",
                 );
                 Platform::GitHub {
                     url,
@@ -67,7 +248,7 @@ impl Platform {
                     &url,
                     from_ci,
                     trusted_hosts,
-                    "GITLAB_TOKEN",
+                    &["GITLAB_TOKEN"],
                     "no GITLAB_TOKEN found; contributor resolution requires a token with 'read_user' scope",
                 );
                 Platform::GitLab {
@@ -78,6 +259,28 @@ impl Platform {
                     token,
                 }
             }
+            Platform::Gitea {
+                url,
+                api_url,
+                owner,
+                repo,
+                ..
+            } => {
+                let token = Self::resolve_token(
+                    &url,
+                    from_ci,
+                    trusted_hosts,
+                    &["GITEA_TOKEN", "FORGEJO_TOKEN"],
+                    "no GITEA_TOKEN or FORGEJO_TOKEN found; API requests may fail for private repositories",
+                );
+                Platform::Gitea {
+                    url,
+                    api_url,
+                    owner,
+                    repo,
+                    token,
+                }
+            }
             Platform::Unknown => Platform::Unknown,
         }
     }
@@ -86,7 +289,7 @@ impl Platform {
         url: &str,
         from_ci: bool,
         trusted_hosts: &[String],
-        env_var: &str,
+        env_vars: &[&str],
         missing_token_warning: &str,
     ) -> Option<String> {
         let host = Self::extract_host_with_protocol(url)
@@ -96,7 +299,7 @@ impl Platform {
             from_ci,
             &host,
             trusted_hosts,
-            env_var,
+            env_vars,
             missing_token_warning,
         )
     }
@@ -148,6 +351,23 @@ impl Platform {
             }
         }
 
+        // Gitea and Forgejo runners also set GITHUB_ACTIONS, so must be checked first
+        if (std::env::var("GITEA_ACTIONS").is_ok() || std::env::var("FORGEJO_ACTIONS").is_ok())
+            && let (Ok(server_url), Ok(repository)) = (
+                std::env::var("GITHUB_SERVER_URL"),
+                std::env::var("GITHUB_REPOSITORY"),
+            )
+            && let Some((owner, repo)) = repository.split_once('/')
+        {
+            return Some(Platform::Gitea {
+                url: format!("{}/{}", server_url.trim_end_matches('/'), repository),
+                api_url: format!("{}/api/v1", server_url.trim_end_matches('/')),
+                owner: owner.to_string(),
+                repo: repo.to_string(),
+                token: None,
+            });
+        }
+
         if std::env::var("GITHUB_ACTIONS").is_ok()
             && let (Ok(server_url), Ok(repository)) = (
                 std::env::var("GITHUB_SERVER_URL"),
@@ -176,12 +396,18 @@ impl Platform {
         None
     }
 
-    fn from_origin_url(origin_url: &str) -> Self {
+    /// Detects the platform from a git remote URL alone, without consulting CI
+    /// environment variables or attaching a token.
+    pub fn detect_from_remote_url(origin_url: &str) -> Self {
         match parse_git_url(origin_url) {
             Ok((host, owner, repo)) => {
-                // Git URLs don't contain protocol info, so we assume HTTPS for web URLs
-                let url = format!("https://{}/{}/{}", host, owner, repo);
-                let protocol = "https";
+                // SSH URLs don't contain protocol info, so we assume HTTPS for web URLs
+                let protocol = if origin_url.starts_with("http://") {
+                    "http"
+                } else {
+                    "https"
+                };
+                let url = format!("{}://{}/{}/{}", protocol, host, owner, repo);
                 let host_lower = host.to_ascii_lowercase();
 
                 if host_lower == "github.com"
@@ -205,6 +431,18 @@ impl Platform {
                         project_path,
                         token: None,
                     }
+                } else if host_lower == "gitea.com"
+                    || host_lower == "codeberg.org"
+                    || host_lower.starts_with("gitea.")
+                    || host_lower.starts_with("forgejo.")
+                {
+                    Platform::Gitea {
+                        url,
+                        api_url: format!("{}://{}/api/v1", protocol, host),
+                        owner,
+                        repo,
+                        token: None,
+                    }
                 } else {
                     Platform::Unknown
                 }
@@ -216,6 +454,21 @@ impl Platform {
         }
     }
 
+    /// Whether credentials may be sent to the host of a git remote URL: one of the
+    /// public hosting services, or a host listed in `trusted_hosts`.
+    pub fn is_trusted_remote(remote_url: &str, trusted_hosts: &[String]) -> bool {
+        parse_git_url(remote_url).is_ok_and(|(host, _, _)| is_trusted_host(&host, trusted_hosts))
+    }
+
+    /// Derives a plausible web URL from a git remote URL on any host, converting SSH
+    /// remotes to HTTPS and dropping the `.git` suffix. Intended as a best-effort
+    /// fallback when the hosting platform is not recognized.
+    pub fn derive_web_url(origin_url: &str) -> Option<String> {
+        parse_git_url(origin_url)
+            .ok()
+            .map(|(host, owner, repo)| format!("https://{}/{}/{}", host, owner, repo))
+    }
+
     fn infer_github_api_url(protocol: &str, host: &str) -> String {
         let host_lower = host.to_ascii_lowercase();
         if host_lower == "github.com" || host_lower.ends_with(".github.com") {
@@ -237,6 +490,7 @@ impl Platform {
         match self {
             Platform::GitHub { url, .. } => url,
             Platform::GitLab { url, .. } => url,
+            Platform::Gitea { url, .. } => url,
             Platform::Unknown => "",
         }
     }
@@ -245,18 +499,76 @@ impl Platform {
         match self {
             Platform::GitHub { api_url, .. } => api_url,
             Platform::GitLab { api_url, .. } => api_url,
+            Platform::Gitea { api_url, .. } => api_url,
             Platform::Unknown => "",
         }
     }
 
+    pub fn token(&self) -> Option<&str> {
+        match self {
+            Platform::GitHub { token, .. } => token.as_deref(),
+            Platform::GitLab { token, .. } => token.as_deref(),
+            Platform::Gitea { token, .. } => token.as_deref(),
+            Platform::Unknown => None,
+        }
+    }
+
     pub fn commit_url(&self, sha: &str) -> Option<String> {
         match self {
             Platform::GitHub { url, .. } => Some(format!("{}/commit/{}", url, sha)),
             Platform::GitLab { url, .. } => Some(format!("{}/-/commit/{}", url, sha)),
+            Platform::Gitea { url, .. } => Some(format!("{}/commit/{}", url, sha)),
             Platform::Unknown => None,
         }
     }
 
+    pub fn issue_url(&self, number: u32) -> Option<String> {
+        match self {
+            Platform::GitHub { url, .. } => Some(format!("{}/issues/{}", url, number)),
+            Platform::GitLab { url, .. } => Some(format!("{}/-/issues/{}", url, number)),
+            Platform::Gitea { url, .. } => Some(format!("{}/issues/{}", url, number)),
+            Platform::Unknown => None,
+        }
+    }
+
+    pub fn pull_request_url(&self, number: u32) -> Option<String> {
+        match self {
+            Platform::GitHub { url, .. } => Some(format!("{}/pull/{}", url, number)),
+            Platform::GitLab { url, .. } => Some(format!("{}/-/merge_requests/{}", url, number)),
+            Platform::Gitea { url, .. } => Some(format!("{}/pulls/{}", url, number)),
+            Platform::Unknown => None,
+        }
+    }
+
+    /// Links the changes between two references, such as the previous release and the
+    /// branch being released.
+    pub fn compare_url(&self, base: &str, head: &str) -> Option<String> {
+        match self {
+            Platform::GitHub { url, .. } => Some(format!("{}/compare/{}...{}", url, base, head)),
+            Platform::GitLab { url, .. } => Some(format!("{}/-/compare/{}...{}", url, base, head)),
+            Platform::Gitea { url, .. } => Some(format!("{}/compare/{}...{}", url, base, head)),
+            Platform::Unknown => None,
+        }
+    }
+
+    /// Links an issue within another repository hosted on the same platform, such as
+    /// one referenced as `owner/repo#123`.
+    pub fn repository_issue_url(&self, owner: &str, repo: &str, number: u32) -> Option<String> {
+        let host = self.host_url()?;
+
+        match self {
+            Platform::GitLab { .. } => {
+                Some(format!("{}/{}/{}/-/issues/{}", host, owner, repo, number))
+            }
+            _ => Some(format!("{}/{}/{}/issues/{}", host, owner, repo, number)),
+        }
+    }
+
+    /// Links a user's profile on the platform.
+    pub fn user_url(&self, username: &str) -> Option<String> {
+        self.host_url().map(|host| format!("{}/{}", host, username))
+    }
+
     pub fn commits_url(
         &self,
         git_ref: &str,
@@ -269,9 +581,31 @@ impl Platform {
                 "{}/commits/{}?author={}&since={}&until={}",
                 url, git_ref, author, since, until
             )),
+            // Gitea filters the commits of a branch through the keywords of its search
+            Platform::Gitea { url, .. } => Some(format!(
+                "{}/commits/branch/{}/search?q=author:{}+after:{}+before:{}",
+                url, git_ref, author, since, until
+            )),
             _ => None,
         }
     }
+
+    /// The platform's web URL without the repository path, such as https://github.com.
+    fn host_url(&self) -> Option<&str> {
+        let (url, path) = match self {
+            Platform::GitHub {
+                url, owner, repo, ..
+            }
+            | Platform::Gitea {
+                url, owner, repo, ..
+            } => (url, format!("{}/{}", owner, repo)),
+            Platform::GitLab {
+                url, project_path, ..
+            } => (url, project_path.clone()),
+            Platform::Unknown => return None,
+        };
+        Some(url.strip_suffix(&path)?.trim_end_matches('/'))
+    }
 }
 
 fn is_trusted_host(host: &str, trusted_hosts: &[String]) -> bool {
@@ -279,6 +613,8 @@ fn is_trusted_host(host: &str, trusted_hosts: &[String]) -> bool {
     host == "github.com"
         || host.ends_with(".github.com")
         || host == "gitlab.com"
+        || host == "gitea.com"
+        || host == "codeberg.org"
         || trusted_hosts.iter().any(|h| h.to_ascii_lowercase() == host)
 }
 
@@ -286,11 +622,11 @@ fn load_trusted_token(
     from_ci: bool,
     host: &str,
     trusted_hosts: &[String],
-    env_var: &str,
+    env_vars: &[&str],
     missing_token_warning: &str,
 ) -> Option<String> {
     if from_ci || is_trusted_host(host, trusted_hosts) {
-        let token = std::env::var(env_var).ok();
+        let token = env_vars.iter().find_map(|var| std::env::var(var).ok());
         if token.is_none() {
             log::warn!("{}", missing_token_warning);
         }
@@ -305,17 +641,23 @@ fn load_trusted_token(
 
 fn parse_git_url(url: &str) -> Result<(String, String, String)> {
     let (host, path) = match url {
-        s if s.starts_with("https://") => {
-            let path = s.strip_prefix("https://").unwrap();
+        s if s.starts_with("https://") || s.starts_with("http://") => {
+            let path = s
+                .strip_prefix("https://")
+                .or_else(|| s.strip_prefix("http://"))
+                .unwrap();
             path.split_once('/')
-                .with_context(|| format!("Invalid HTTPS URL: {}", url))?
+                .with_context(|| format!("Invalid HTTP(S) URL: {}", url))?
         }
         s if s.starts_with("git@") => {
             let path = s.strip_prefix("git@").unwrap();
             path.split_once(':')
                 .with_context(|| format!("Invalid SSH URL: {}", url))?
         }
-        _ => anyhow::bail!("URL must start with 'https://' or 'git@': {}", url),
+        _ => anyhow::bail!(
+            "URL must start with 'https://', 'http://' or 'git@': {}",
+            url
+        ),
     };
 
     let path = path.trim_end_matches(".git");
@@ -5,13 +5,58 @@ pub const DEFAULT_TEMPLATE: &str = r#"{%- macro commit_contributors(commit) -%}
 {%- if commit.contributors %} ({{ commit.contributors | mention | join(sep=", ") }}){% endif -%}
 {%- endmacro commit_contributors -%}
 
+{%- macro commit_subject(commit) -%}
+{%- if commit.repository %}**{{ commit.repository }}**: {% endif -%}
+{%- if commit.release_note -%}
+{{ commit.release_note }}
+{%- else -%}
+{{ commit.first_line | strip_conventional_prefix | link_pull_requests(repository = commit.repository) }}
+{%- endif -%}
+{%- endmacro commit_subject -%}
+
+{%- macro breaking_marker(commit) -%}
+{%- if commit.breaking %}⚠️ {% endif -%}
+{%- endmacro breaking_marker -%}
+
+{%- macro commit_trailers(commit, indent) -%}
+{%- set trailers = commit.trailers | kept_trailers -%}
+{%- if trailers %}
+
+{{ trailers | indent(prefix = indent, first=true) }}
+{%- endif -%}
+{%- endmacro commit_trailers -%}
+
+{%- macro commit_body(text, indent, blank, wrap) -%}
+{%- if wrap -%}
+{{ text | rewrap | indent(prefix = indent, first=true, blank=blank) }}
+{%- else -%}
+{{ text | unwrap | indent(prefix = indent, first=true, blank=blank) }}
+{%- endif -%}
+{%- endmacro commit_body -%}
+
+{%- macro commit_entry(commit, indent, blank, wrap, description="") -%}
+- {{ commit_url(sha = commit.hash, text = commit.hash | short_hash, repository = commit.repository) }} {{ self::breaking_marker(commit=commit) }}{{ self::commit_subject(commit=commit) }}{{ self::commit_contributors(commit=commit) }}
+{%- if commit.body and not commit.release_note %}
+
+{{ self::commit_body(text=commit.body, indent=indent, blank=blank, wrap=wrap) }}
+{%- endif %}
+{%- if description and not commit.release_note %}
+
+{{ self::commit_body(text=description, indent=indent, blank=blank, wrap=wrap) }}
+{%- endif %}
+{{- self::commit_trailers(commit=commit, indent=indent) }}
+{%- endmacro commit_entry -%}
+
 {%- macro contributor_link(contributor) -%}
 {%- if contributor.is_ai -%}
 **`{{ contributor.count }}`** commit{% if contributor.count != 1 %}s{% endif %}
 {%- else -%}
+{%- set url = "" -%}
+{%- if contributor.first_commit_timestamp > 0 and contributor.last_commit_timestamp > 0 -%}
 {%- set since = contributor.first_commit_timestamp | date(format="%Y-%m-%d") -%}
 {%- set until = contributor.last_commit_timestamp | date(format="%Y-%m-%d") -%}
 {%- set url = contributor_commits_url(author=contributor.username, since=since, until=until) -%}
+{%- endif -%}
 {%- if url -%}
 [**`{{ contributor.count }}`**]({{ url }}) commit{% if contributor.count != 1 %}s{% endif %}
 {%- else -%}
@@ -20,16 +65,62 @@ pub const DEFAULT_TEMPLATE: &str = r#"{%- macro commit_contributors(commit) -%}
 {%- endif -%}
 {%- endmacro contributor_link -%}
 
-## {{ git_ref }} - {{ release_date | date(format="%B %d, %Y") }}
+{%- macro contributor_badge(contributor) -%}
+{%- set noun = "commits" -%}
+{%- if contributor.count == 1 %}{% set noun = "commit" %}{% endif -%}
+{%- set badge = "![" ~ contributor.count ~ " " ~ noun ~ "](https://img.shields.io/badge/" ~ noun ~ "-" ~ contributor.count ~ "-blue)" -%}
+{%- if contributor.is_ai -%}
+{{ badge }}
+{%- else -%}
+{%- set url = "" -%}
+{%- if contributor.first_commit_timestamp > 0 and contributor.last_commit_timestamp > 0 -%}
+{%- set since = contributor.first_commit_timestamp | date(format="%Y-%m-%d") -%}
+{%- set until = contributor.last_commit_timestamp | date(format="%Y-%m-%d") -%}
+{%- set url = contributor_commits_url(author=contributor.username, since=since, until=until) -%}
+{%- endif -%}
+{%- if url -%}
+[{{ badge }}]({{ url }})
+{%- else -%}
+{{ badge }}
+{%- endif -%}
+{%- endif -%}
+{%- endmacro contributor_badge -%}
+
+{%- macro section_heading(title, anchor, count, collapsible, lang) -%}
+{%- if collapsible -%}
+<details><summary><h2>{{ title }} ({{ count }}){% if anchor %} <a id="{{ anchor }}"></a>{% endif %}</h2></summary>
+{% else -%}
+## {{ title }}{% if lang != "en" and anchor %} <a id="{{ anchor }}"></a>{% endif %}
+{%- endif -%}
+{%- endmacro section_heading -%}
+
+{%- macro more_commits(commits, label, table=false) -%}
+{%- set remaining = commits | length - commits | capped | length -%}
+{%- if remaining > 0 %}
+{% if table %}| | {{ label | replace(from="{n}", to=remaining ~ "") }} | |{% else %}- {{ label | replace(from="{n}", to=remaining ~ "") }}{% endif %}
+{%- endif -%}
+{%- endmacro more_commits -%}
+
+## {% if unreleased %}{{ i18n.unreleased }}{% else %}{{ git_ref }}{% endif %} - {{ release_date | date(format=date_format) }}
 
 {%- set stats = [] -%}
 {%- if breaking -%}
   {%- set breaking_count = breaking | length -%}
   {%- if breaking_count > 0 -%}
     {%- if breaking_count == 1 -%}
-      {%- set_global stats = stats | concat(with="[**`" ~ breaking_count ~ "`**](#breaking-changes) breaking change") -%}
+      {%- set_global stats = stats | concat(with=stat(count=breaking_count, anchor=anchors.breaking) ~ " " ~ i18n.breaking_change_stat) -%}
     {%- else -%}
-      {%- set_global stats = stats | concat(with="[**`" ~ breaking_count ~ "`**](#breaking-changes) breaking changes") -%}
+      {%- set_global stats = stats | concat(with=stat(count=breaking_count, anchor=anchors.breaking) ~ " " ~ i18n.breaking_changes_stat) -%}
+    {%- endif -%}
+  {%- endif -%}
+{%- endif -%}
+{%- if hotfixes -%}
+  {%- set hotfixes_count = hotfixes | length -%}
+  {%- if hotfixes_count > 0 -%}
+    {%- if hotfixes_count == 1 -%}
+      {%- set_global stats = stats | concat(with=stat(count=hotfixes_count, anchor=anchors.hotfixes) ~ " " ~ i18n.hotfix_stat) -%}
+    {%- else -%}
+      {%- set_global stats = stats | concat(with=stat(count=hotfixes_count, anchor=anchors.hotfixes) ~ " " ~ i18n.hotfixes_stat) -%}
     {%- endif -%}
   {%- endif -%}
 {%- endif -%}
@@ -37,9 +128,9 @@ pub const DEFAULT_TEMPLATE: &str = r#"{%- macro commit_contributors(commit) -%}
   {%- set features_count = features | length -%}
   {%- if features_count > 0 -%}
     {%- if features_count == 1 -%}
-      {%- set_global stats = stats | concat(with="[**`" ~ features_count ~ "`**](#new-features) new feature") -%}
+      {%- set_global stats = stats | concat(with=stat(count=features_count, anchor=anchors.features) ~ " " ~ i18n.new_feature_stat) -%}
     {%- else -%}
-      {%- set_global stats = stats | concat(with="[**`" ~ features_count ~ "`**](#new-features) new features") -%}
+      {%- set_global stats = stats | concat(with=stat(count=features_count, anchor=anchors.features) ~ " " ~ i18n.new_features_stat) -%}
     {%- endif -%}
   {%- endif -%}
 {%- endif -%}
@@ -47,96 +138,215 @@ pub const DEFAULT_TEMPLATE: &str = r#"{%- macro commit_contributors(commit) -%}
   {%- set fixes_count = fixes | length -%}
   {%- if fixes_count > 0 -%}
     {%- if fixes_count == 1 -%}
-      {%- set_global stats = stats | concat(with="[**`" ~ fixes_count ~ "`**](#bug-fixes) bug fixed") -%}
+      {%- set_global stats = stats | concat(with=stat(count=fixes_count, anchor=anchors.fixes) ~ " " ~ i18n.bug_fix_stat) -%}
     {%- else -%}
-      {%- set_global stats = stats | concat(with="[**`" ~ fixes_count ~ "`**](#bug-fixes) bug fixes") -%}
+      {%- set_global stats = stats | concat(with=stat(count=fixes_count, anchor=anchors.fixes) ~ " " ~ i18n.bug_fixes_stat) -%}
     {%- endif -%}
   {%- endif -%}
 {%- endif -%}
+{%- if not no_stats %}
 {%- if stats | length > 0 %}
 
-{{ stats | join(sep=" • ") }}
+{{ stats | join(sep=stats_separator) }}
 {% endif %}
-{%- if contributors %}
-## Contributors
-{%- for contributor in contributors | filter(attribute="is_bot", value=false) %}
-- <img src="{{ contributor.avatar_url }}&size=20" align="center">&nbsp;&nbsp;@{{ contributor.username }} ({{ self::contributor_link(contributor=contributor) }})
+{%- endif %}
+{%- if contributors and not no_contributors_section %}
+## {{ i18n.contributors }}
+{%- set humans = contributors | filter(attribute="is_bot", value=false) %}
+{%- set shown = humans %}
+{%- if max_contributors %}{% set shown = humans | slice(end=max_contributors) %}{% endif %}
+{%- for contributor in shown %}
+- {% if not no_avatars %}<img src="{{ contributor.avatar_url }}&size=20" align="center">&nbsp;&nbsp;{% endif %}{{ contributor | mention }} {% if commit_badges %}{{ self::contributor_badge(contributor=contributor) }}{% else %}({{ self::contributor_link(contributor=contributor) }}){% endif %}
 {%- endfor %}
+{%- if humans | length > shown | length %}
+{%- set remaining = humans | length - shown | length %}
+- {{ i18n.more_contributors | replace(from="{n}", to=remaining ~ "") }}
+{%- endif %}
 {% endif %}
-{%- if breaking %}
-## Breaking Changes
-{%- for commit in breaking %}
-- {{ commit_url(sha = commit.hash) }} {{ commit.first_line | strip_conventional_prefix }}{{ self::commit_contributors(commit=commit) }}
-{%- if commit.body %}
+{%- if breaking or always_show_sections %}
+{{ self::section_heading(title=i18n.breaking_changes, anchor=anchors.breaking, count=breaking | length, collapsible=collapsible, lang=lang) }}
+{%- if not breaking %}
+
+{{ i18n.no_changes }}
+{%- endif %}
+{%- for commit in breaking | capped %}
+- {{ commit_url(sha = commit.hash, text = commit.hash | short_hash, repository = commit.repository) }} {{ self::commit_subject(commit=commit) }}{{ self::commit_contributors(commit=commit) }}
+{%- if commit.body and not commit.release_note %}
+
+{{ self::commit_body(text=commit.body, indent=body_indent, blank=indent_blank_lines, wrap=wrap_width) }}
+{%- endif %}
+{{- self::commit_trailers(commit=commit, indent=body_indent) }}
+{%- endfor %}
+{{- self::more_commits(commits=breaking, label=i18n.more_commits) }}
+{%- if collapsible %}
+
+</details>
+{%- endif %}
 
-{{ commit.body | unwrap | indent(prefix = "  ", first=true) }}
 {%- endif %}
+{%- if hotfixes or always_show_sections %}
+{{ self::section_heading(title=i18n.hot_fixes, anchor=anchors.hotfixes, count=hotfixes | length, collapsible=collapsible, lang=lang) }}
+{%- if not hotfixes %}
+
+{{ i18n.no_changes }}
+{%- endif %}
+{%- for commit in hotfixes | capped %}
+{{ self::commit_entry(commit=commit, indent=body_indent, blank=indent_blank_lines, wrap=wrap_width) }}
 {%- endfor %}
+{{- self::more_commits(commits=hotfixes, label=i18n.more_commits) }}
+{%- if collapsible %}
 
+</details>
 {%- endif %}
-{%- if features %}
-## New Features
-{%- for commit in features %}
-- {{ commit_url(sha = commit.hash) }} {{ commit.first_line | strip_conventional_prefix }}{{ self::commit_contributors(commit=commit) }}
-{%- if commit.body %}
 
-{{ commit.body | unwrap | indent(prefix = "  ", first=true) }}
 {%- endif %}
+{%- if features or always_show_sections %}
+{{ self::section_heading(title=i18n.new_features, anchor=anchors.features, count=features | length, collapsible=collapsible, lang=lang) }}
+{%- if not features %}
+
+{{ i18n.no_changes }}
+{%- endif %}
+{%- for commit in features | capped %}
+{{ self::commit_entry(commit=commit, indent=body_indent, blank=indent_blank_lines, wrap=wrap_width) }}
 {%- endfor %}
+{{- self::more_commits(commits=features, label=i18n.more_commits) }}
+{%- if collapsible %}
+
+</details>
+{%- endif %}
 
 {%- endif %}
-{%- if fixes %}
-## Bug Fixes
-{%- for commit in fixes %}
-- {{ commit_url(sha = commit.hash) }} {{ commit.first_line | strip_conventional_prefix }}{{ self::commit_contributors(commit=commit) }}
-{%- if commit.body %}
+{%- if deprecations or always_show_sections %}
+{{ self::section_heading(title=i18n.deprecations, anchor=anchors.deprecations, count=deprecations | length, collapsible=collapsible, lang=lang) }}
+{%- if not deprecations %}
 
-{{ commit.body | unwrap | indent(prefix = "  ", first=true) }}
+{{ i18n.no_changes }}
 {%- endif %}
+{%- for commit in deprecations | capped %}
+{{ self::commit_entry(commit=commit, indent=body_indent, blank=indent_blank_lines, wrap=wrap_width, description=commit.deprecation_description) }}
 {%- endfor %}
+{{- self::more_commits(commits=deprecations, label=i18n.more_commits) }}
+{%- if collapsible %}
+
+</details>
+{%- endif %}
 
 {%- endif %}
-{%- if perf %}
-## Performance Improvements
-{%- for commit in perf %}
-- {{ commit_url(sha = commit.hash) }} {{ commit.first_line | strip_conventional_prefix }}{{ self::commit_contributors(commit=commit) }}
-{%- if commit.body %}
+{%- if fixes or always_show_sections %}
+{{ self::section_heading(title=i18n.bug_fixes, anchor=anchors.fixes, count=fixes | length, collapsible=collapsible, lang=lang) }}
+{%- if not fixes %}
 
-{{ commit.body | unwrap | indent(prefix = "  ", first=true) }}
+{{ i18n.no_changes }}
 {%- endif %}
+{%- for commit in fixes | capped %}
+{{ self::commit_entry(commit=commit, indent=body_indent, blank=indent_blank_lines, wrap=wrap_width) }}
 {%- endfor %}
+{{- self::more_commits(commits=fixes, label=i18n.more_commits) }}
+{%- if collapsible %}
 
+</details>
 {%- endif %}
-{%- if dependencies %}
-## Dependency Updates
+
+{%- endif %}
+{%- if perf or always_show_sections %}
+{{ self::section_heading(title=i18n.performance_improvements, anchor="", count=perf | length, collapsible=collapsible, lang=lang) }}
+{%- if not perf %}
+
+{{ i18n.no_changes }}
+{%- endif %}
+{%- for commit in perf | capped %}
+{{ self::commit_entry(commit=commit, indent=body_indent, blank=indent_blank_lines, wrap=wrap_width) }}
+{%- endfor %}
+{{- self::more_commits(commits=perf, label=i18n.more_commits) }}
+{%- if collapsible %}
+
+</details>
+{%- endif %}
+
+{%- endif %}
+{%- if reverts or always_show_sections %}
+{{ self::section_heading(title=i18n.reverts, anchor="", count=reverts | length, collapsible=collapsible, lang=lang) }}
+{%- if not reverts %}
+
+{{ i18n.no_changes }}
+{%- endif %}
+{%- for commit in reverts | capped %}
+- {{ commit_url(sha = commit.hash, text = commit.hash | short_hash, repository = commit.repository) }} {{ self::breaking_marker(commit=commit) }}{{ self::commit_subject(commit=commit) }}{% if commit.reverted_pull_request %} (reverts {{ pull_request_link(number = commit.reverted_pull_request, repository = commit.repository) }}){% endif %}{{ self::commit_contributors(commit=commit) }}
+{%- endfor %}
+{{- self::more_commits(commits=reverts, label=i18n.more_commits) }}
+{%- if collapsible %}
+
+</details>
+{%- endif %}
+
+{%- endif %}
+{%- if dependencies or always_show_sections %}
+{{ self::section_heading(title=i18n.dependency_updates, anchor="", count=dependencies | length, collapsible=collapsible, lang=lang) }}
+{%- if not dependencies %}
+
+{{ i18n.no_changes }}
+{%- endif %}
+{%- for commit in dependencies | capped %}
+{%- if loop.first %}
 
 | Commit | Update | Contributors |
 |--------|--------|--------------|
-{%- for commit in dependencies %}
-| {{ commit_url(sha = commit.hash) }} | {{ commit.first_line | strip_conventional_prefix | table_escape }} |{% if commit.contributors %} {{ commit.contributors | mention | join(sep=", ") }}{% endif %} |
+{%- endif %}
+| {{ commit_url(sha = commit.hash, text = commit.hash | short_hash, repository = commit.repository) }} | {{ self::breaking_marker(commit=commit) }}{{ self::commit_subject(commit=commit) | table_escape }} |{% if commit.contributors %} {{ commit.contributors | mention | join(sep=", ") }}{% endif %} |
 {%- endfor %}
+{{- self::more_commits(commits=dependencies, label=i18n.more_commits, table=true) }}
+{%- if collapsible %}
+
+</details>
+{%- endif %}
+
+{%- endif %}
+{%- if compare_url %}
 
+**{{ i18n.full_changelog }}**: {{ compare_url }}
 {%- endif %}
+{%- if footer %}
 
-*Generated with [release-note](https://github.com/purpleclay/release-note)*"#;
+{{ footer }}
+{%- endif %}"#;
+
+pub const DEFAULT_FOOTER: &str =
+    "*Generated with [release-note](https://github.com/purpleclay/release-note)*";
 
 pub struct TemplateResolver {
     working_dir: PathBuf,
+    template_dir: Option<PathBuf>,
 }
 
 impl TemplateResolver {
     pub fn new(working_dir: PathBuf) -> Self {
-        Self { working_dir }
+        Self {
+            working_dir,
+            template_dir: None,
+        }
     }
 
-    pub fn resolve(&self) -> Result<String> {
-        let candidates = [
-            self.working_dir.join("release-note.tera"),
-            self.working_dir.join(".github/release-note.tera"),
-            self.working_dir.join(".gitlab/release-note.tera"),
-        ];
+    /// Searches this directory for a template before any within the working directory.
+    pub fn with_template_dir(mut self, template_dir: PathBuf) -> Self {
+        self.template_dir = Some(template_dir);
+        self
+    }
 
-        for path in candidates {
+    /// Candidate template paths in the order they are searched by `resolve`.
+    pub fn list_candidates(&self) -> Vec<PathBuf> {
+        self.template_dir
+            .iter()
+            .map(|dir| dir.join("release-note.tera"))
+            .chain([
+                self.working_dir.join("release-note.tera"),
+                self.working_dir.join(".github/release-note.tera"),
+                self.working_dir.join(".gitlab/release-note.tera"),
+            ])
+            .collect()
+    }
+
+    pub fn resolve(&self) -> Result<String> {
+        for path in self.list_candidates() {
             if path.is_file() {
                 let content = std::fs::read_to_string(&path)
                     .with_context(|| format!("failed to read template: {}", path.display()))?;
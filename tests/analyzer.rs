@@ -1,24 +1,32 @@
 mod commit;
 
 use commit::CommitBuilder;
-use release_note::analyzer::{CommitAnalyzer, CommitCategory};
+use release_note::analyzer::{
+    AnalyzerOptions, Bump, CategorizedCommits, CategoryPrecedence, CommitAnalyzer, CommitCategory,
+};
+use release_note::git::CommitOrder;
+use release_note::locale::Locale;
 
 #[test]
 fn categorizes_commits() {
     let test_cases = vec![
         ("feat: to be or not to be", CommitCategory::Feature),
         ("fix: all the world's a stage", CommitCategory::Fix),
+        (
+            "hotfix: once more unto the breach, dear friends",
+            CommitCategory::Hotfix,
+        ),
         (
             "docs: a horse! a horse! my kingdom for a horse!",
             CommitCategory::Documentation,
         ),
         (
             "build: if music be the food of love, play on",
-            CommitCategory::Other,
+            CommitCategory::Build,
         ),
         (
             "style: lord, what fools these mortals be!",
-            CommitCategory::Other,
+            CommitCategory::Style,
         ),
         (
             "refactor: cowards die many times before their deaths",
@@ -55,6 +63,90 @@ fn categorizes_commits() {
     }
 }
 
+#[test]
+fn categorizes_breaking_dependency_update_by_precedence() {
+    let test_cases = [
+        (CategoryPrecedence::BreakingFirst, CommitCategory::Breaking),
+        (
+            CategoryPrecedence::DependenciesFirst,
+            CommitCategory::Dependencies,
+        ),
+    ];
+
+    for (precedence, expected_category) in test_cases {
+        let commit =
+            CommitBuilder::new("feat(deps)!: the quality of mercy is not strained").build();
+        let result = CommitAnalyzer::analyze_with_options(
+            &[commit],
+            &AnalyzerOptions {
+                precedence,
+                ..Default::default()
+            },
+        );
+
+        let commits = result.by_category.get(&expected_category).unwrap();
+        assert_eq!(commits.len(), 1, "{:?}", precedence);
+        assert!(commits[0].breaking);
+        assert!(!result.by_category.contains_key(&CommitCategory::Feature));
+    }
+}
+
+#[test]
+fn requires_description_for_breaking_dependency_update_filed_under_dependencies() {
+    let commit = CommitBuilder::new("feat(deps)!: the quality of mercy is not strained").build();
+
+    let result = CommitAnalyzer::analyze_with_options(
+        &[commit],
+        &AnalyzerOptions {
+            precedence: CategoryPrecedence::DependenciesFirst,
+            ..Default::default()
+        },
+    );
+    let err = result.ensure_breaking_described().unwrap_err().to_string();
+
+    assert!(
+        result
+            .by_category
+            .contains_key(&CommitCategory::Dependencies)
+    );
+    assert!(err.contains("feat(deps)!: the quality of mercy is not strained"));
+}
+
+#[test]
+fn keeps_type_precedence_for_commits_outside_deps_scope() {
+    for precedence in [
+        CategoryPrecedence::BreakingFirst,
+        CategoryPrecedence::DependenciesFirst,
+    ] {
+        let commits = [
+            CommitBuilder::new("feat(stage)!: exit, pursued by a bear").build(),
+            CommitBuilder::new("fix(deps): the better part of valor is discretion").build(),
+        ];
+        let result = CommitAnalyzer::analyze_with_options(
+            &commits,
+            &AnalyzerOptions {
+                precedence,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(result.by_category[&CommitCategory::Breaking].len(), 1);
+        assert_eq!(result.by_category[&CommitCategory::Dependencies].len(), 1);
+    }
+}
+
+#[test]
+fn categorizes_conventional_prefix_without_description_as_other() {
+    for commit_msg in ["feat:", "fix(globe): ", "feat!:"] {
+        let commit = CommitBuilder::new(commit_msg).build();
+        let result = CommitAnalyzer::analyze(&[commit]);
+
+        let other = result.by_category.get(&CommitCategory::Other).unwrap();
+        assert_eq!(other.len(), 1);
+        assert_eq!(other[0].first_line, commit_msg);
+    }
+}
+
 #[test]
 fn categorizes_by_breaking_change_in_footer() {
     let commit = CommitBuilder::new("fix: the course of true love never did run smooth")
@@ -79,6 +171,22 @@ BREAKING CHANGE: but in battalions",
     );
 }
 
+#[test]
+fn categorizes_by_breaking_change_in_footer_without_body() {
+    let commit = CommitBuilder::new("feat: the play's the thing")
+        .with_footer("BREAKING CHANGE: wherein I'll catch the conscience of the king")
+        .build();
+
+    let result = CommitAnalyzer::analyze(&[commit]);
+    let breaking = result.by_category.get(&CommitCategory::Breaking).unwrap();
+
+    assert_eq!(breaking.len(), 1);
+    assert_eq!(
+        breaking[0].breaking_description.as_deref(),
+        Some("wherein I'll catch the conscience of the king")
+    );
+}
+
 #[test]
 fn categorizes_breaking_change_by_hash_bang() {
     let commit =
@@ -144,6 +252,39 @@ fn categorizes_by_dependency_scope() {
     assert_eq!(deps.len(), 5);
 }
 
+#[test]
+fn drops_commits_matching_an_ignore_pattern() {
+    let commits = [
+        CommitBuilder::new("feat: exit, pursued by a bear").build(),
+        CommitBuilder::new("chore(release): 1.0.0").build(),
+        CommitBuilder::new("fix: the better part of valor is discretion").build(),
+    ];
+    let options = AnalyzerOptions::default()
+        .with_ignore_patterns(&[r"^chore\(release\)".to_string(), "valor".to_string()])
+        .unwrap();
+
+    let result = CommitAnalyzer::analyze_with_options(&commits, &options);
+
+    assert_eq!(result.by_category.len(), 1);
+    assert_eq!(result.by_category[&CommitCategory::Feature].len(), 1);
+}
+
+#[test]
+fn reports_every_invalid_ignore_pattern() {
+    let err = AnalyzerOptions::default()
+        .with_ignore_patterns(&[
+            r"^chore\(release\)".to_string(),
+            "(unclosed".to_string(),
+            "[z-a]".to_string(),
+        ])
+        .unwrap_err()
+        .to_string();
+
+    assert!(err.contains("2 ignore pattern(s)"));
+    assert!(err.contains("(unclosed"));
+    assert!(err.contains("[z-a]"));
+}
+
 #[test]
 fn supports_mixed_case_commit_types() {
     let commits = vec![
@@ -251,6 +392,78 @@ fn detects_breaking_change_when_parsed_as_trailer() {
     assert_eq!(breaking.len(), 1);
 }
 
+#[test]
+fn categorizes_commit_using_changelog_trailer() {
+    let commit = CommitBuilder::new("mend the broken lute strings")
+        .with_trailer("Changelog", "fix")
+        .build();
+
+    let result = CommitAnalyzer::analyze(&[commit]);
+    let fixes = result.by_category.get(&CommitCategory::Fix).unwrap();
+    assert_eq!(fixes.len(), 1);
+    assert!(!result.by_category.contains_key(&CommitCategory::Other));
+}
+
+#[test]
+fn changelog_trailer_overrides_conventional_type() {
+    let commit = CommitBuilder::new("chore: a new prologue for the second act")
+        .with_trailer("changelog", "feature")
+        .build();
+
+    let result = CommitAnalyzer::analyze(&[commit]);
+    let features = result.by_category.get(&CommitCategory::Feature).unwrap();
+    assert_eq!(features.len(), 1);
+    assert_eq!(features[0].type_, "chore");
+}
+
+#[test]
+fn lists_deprecated_footer_under_deprecations_and_its_type() {
+    let commits = vec![
+        CommitBuilder::new("feat: a new soliloquy for the third act")
+            .with_trailer("Deprecated", "the old soliloquy will be cut next season")
+            .build(),
+        CommitBuilder::new("refactor: retire the prologue")
+            .with_trailer("DEPRECATED", "the prologue is no longer spoken")
+            .build(),
+    ];
+
+    let result = CommitAnalyzer::analyze(&commits);
+    let deprecations = result
+        .by_category
+        .get(&CommitCategory::Deprecation)
+        .unwrap();
+    let descriptions: Vec<_> = deprecations
+        .iter()
+        .map(|c| c.deprecation_description.as_deref())
+        .collect();
+    assert_eq!(
+        descriptions,
+        vec![
+            Some("the old soliloquy will be cut next season"),
+            Some("the prologue is no longer spoken")
+        ]
+    );
+    assert_eq!(
+        result.by_category[&CommitCategory::Feature][0].first_line,
+        "feat: a new soliloquy for the third act"
+    );
+    assert_eq!(result.by_category[&CommitCategory::Refactor].len(), 1);
+}
+
+#[test]
+fn breaking_change_takes_precedence_over_deprecation() {
+    let commit = CommitBuilder::new("feat!: off with his head")
+        .with_trailer("Deprecated", "the crown is no longer worn")
+        .build();
+
+    let result = CommitAnalyzer::analyze(&[commit]);
+    let breaking = result.by_category.get(&CommitCategory::Breaking).unwrap();
+    assert_eq!(
+        breaking[0].deprecation_description.as_deref(),
+        Some("the crown is no longer worn")
+    );
+}
+
 #[test]
 fn populates_type_from_conventional_commit() {
     let commits = vec![
@@ -372,3 +585,501 @@ fn detects_breaking_change_trailer_with_hyphen() {
     let breaking = result.by_category.get(&CommitCategory::Breaking).unwrap();
     assert_eq!(breaking.len(), 1);
 }
+
+#[test]
+fn supports_mixed_case_commit_types_with_scopes() {
+    let commits = vec![
+        CommitBuilder::new("FEAT(Stage): exit, pursued by a bear").build(),
+        CommitBuilder::new("Fix(UI): the play's the thing").build(),
+        CommitBuilder::new("DOCS(Readme): words, words, words").build(),
+    ];
+
+    let result = CommitAnalyzer::analyze(&commits);
+
+    let features = result.by_category.get(&CommitCategory::Feature).unwrap();
+    assert_eq!(features.len(), 1);
+    assert_eq!(features[0].type_, "feat");
+    assert_eq!(features[0].scope, "stage");
+
+    let fixes = result.by_category.get(&CommitCategory::Fix).unwrap();
+    assert_eq!(fixes.len(), 1);
+    assert_eq!(fixes[0].type_, "fix");
+    assert_eq!(fixes[0].scope, "ui");
+
+    let docs = result
+        .by_category
+        .get(&CommitCategory::Documentation)
+        .unwrap();
+    assert_eq!(docs.len(), 1);
+    assert_eq!(docs[0].type_, "docs");
+    assert_eq!(docs[0].scope, "readme");
+}
+
+#[test]
+fn parses_scopes_with_numbers_and_dots() {
+    let commits = vec![
+        CommitBuilder::new("feat(api2): once more unto the breach").build(),
+        CommitBuilder::new("fix(V2): cry havoc").build(),
+        CommitBuilder::new("feat(core.io): let slip the dogs of war").build(),
+        CommitBuilder::new("fix(ui_kit): the fault is in ourselves").build(),
+    ];
+
+    let result = CommitAnalyzer::analyze(&commits);
+
+    let features = result.by_category.get(&CommitCategory::Feature).unwrap();
+    assert_eq!(features[0].scope, "api2");
+    assert_eq!(features[1].scope, "core.io");
+
+    let fixes = result.by_category.get(&CommitCategory::Fix).unwrap();
+    assert_eq!(fixes[0].scope, "v2");
+    assert_eq!(fixes[1].scope, "ui_kit");
+    assert!(!result.by_category.contains_key(&CommitCategory::Other));
+}
+
+#[test]
+fn categorizes_by_uppercase_dependency_scope() {
+    let commits = vec![
+        CommitBuilder::new("chore(DEPS): bump serde to 1.0.200").build(),
+        CommitBuilder::new("Fix(Deps): bump tera to 1.20.1").build(),
+    ];
+
+    let result = CommitAnalyzer::analyze(&commits);
+
+    let deps = result
+        .by_category
+        .get(&CommitCategory::Dependencies)
+        .unwrap();
+    assert_eq!(deps.len(), 2);
+    assert!(deps.iter().all(|c| c.scope == "deps"));
+    assert!(!result.by_category.contains_key(&CommitCategory::Chore));
+    assert!(!result.by_category.contains_key(&CommitCategory::Fix));
+}
+
+#[test]
+fn categorizes_build_and_style_commits() {
+    let commits = vec![
+        CommitBuilder::new("build: if music be the food of love, play on").build(),
+        CommitBuilder::new("build(cargo): give every man thy ear, but few thy voice").build(),
+        CommitBuilder::new("style: lord, what fools these mortals be!").build(),
+        CommitBuilder::new("style!: brevity is the soul of wit").build(),
+    ];
+
+    let result = CommitAnalyzer::analyze(&commits);
+
+    let subjects = |category| -> Vec<&str> {
+        result
+            .by_category
+            .get(&category)
+            .map(|commits| commits.iter().map(|c| c.first_line.as_str()).collect())
+            .unwrap_or_default()
+    };
+    assert_eq!(
+        subjects(CommitCategory::Build),
+        vec![
+            "build: if music be the food of love, play on",
+            "build(cargo): give every man thy ear, but few thy voice"
+        ]
+    );
+    assert_eq!(
+        subjects(CommitCategory::Style),
+        vec!["style: lord, what fools these mortals be!"]
+    );
+    assert_eq!(
+        subjects(CommitCategory::Breaking),
+        vec!["style!: brevity is the soul of wit"]
+    );
+    assert!(!result.by_category.contains_key(&CommitCategory::Other));
+}
+
+#[test]
+fn identifies_categories_rendered_by_default() {
+    let rendered = [
+        CommitCategory::Breaking,
+        CommitCategory::Feature,
+        CommitCategory::Fix,
+        CommitCategory::Hotfix,
+        CommitCategory::Performance,
+        CommitCategory::Dependencies,
+        CommitCategory::Deprecation,
+    ];
+    let ignored = [
+        CommitCategory::Build,
+        CommitCategory::Chore,
+        CommitCategory::CI,
+        CommitCategory::Documentation,
+        CommitCategory::Other,
+        CommitCategory::Refactor,
+        CommitCategory::Style,
+        CommitCategory::Test,
+    ];
+
+    assert!(rendered.iter().all(|c| c.is_rendered_by_default()));
+    assert!(!ignored.iter().any(|c| c.is_rendered_by_default()));
+}
+
+struct CapturingLogger(std::sync::Mutex<Vec<String>>);
+
+impl log::Log for CapturingLogger {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        self.0.lock().unwrap().push(record.args().to_string());
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: CapturingLogger = CapturingLogger(std::sync::Mutex::new(Vec::new()));
+
+#[test]
+fn logs_rendered_and_ignored_commit_counts() {
+    let _ = log::set_logger(&LOGGER);
+    log::set_max_level(log::LevelFilter::Info);
+
+    let commits = vec![
+        CommitBuilder::new("feat: the lunatic, the lover, and the poet").build(),
+        CommitBuilder::new("fix: are of imagination all compact").build(),
+        CommitBuilder::new("perf: swift as a shadow, short as any dream").build(),
+        CommitBuilder::new("docs: lord, what fools these mortals be").build(),
+        CommitBuilder::new("chore: the course of true love never did run smooth").build(),
+        CommitBuilder::new("test: i'll put a girdle round about the earth").build(),
+        CommitBuilder::new("ci: in forty minutes").build(),
+    ];
+    CommitAnalyzer::analyze(&commits);
+
+    assert!(
+        LOGGER
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|line| line == "total commits: 7, rendered: 3, other/ignored: 4")
+    );
+}
+
+#[test]
+fn requires_description_for_bare_breaking_change() {
+    let commit = CommitBuilder::new("feat!: off with his head").build();
+
+    let result = CommitAnalyzer::analyze(&[commit]);
+    let err = result.ensure_breaking_described().unwrap_err().to_string();
+
+    assert!(err.contains("1 breaking change(s) have no description"));
+    assert!(err.contains("feat!: off with his head"));
+}
+
+#[test]
+fn keeps_inline_breaking_changes_of_unrendered_types_under_breaking() {
+    let commits = vec![
+        CommitBuilder::new("refactor!: off with his head").build(),
+        CommitBuilder::new("feat!: now is the winter of our discontent").build(),
+    ];
+
+    let result = CommitAnalyzer::analyze_with_options(
+        &commits,
+        &AnalyzerOptions {
+            breaking_inline: true,
+            ..Default::default()
+        },
+    );
+
+    let breaking = &result.by_category[&CommitCategory::Breaking];
+    assert_eq!(breaking.len(), 1);
+    assert_eq!(breaking[0].first_line, "refactor!: off with his head");
+    assert!(!result.by_category.contains_key(&CommitCategory::Refactor));
+    assert_eq!(result.by_category[&CommitCategory::Feature].len(), 1);
+
+    let err = result.ensure_breaking_described().unwrap_err().to_string();
+    assert!(err.contains("2 breaking change(s) have no description"));
+    assert!(err.contains("feat!: now is the winter of our discontent"));
+}
+
+#[test]
+fn accepts_breaking_changes_with_description() {
+    let commits = vec![
+        CommitBuilder::new("feat!: off with his head")
+            .with_body("So much for Buckingham.")
+            .build(),
+        CommitBuilder::new("refactor: now is the winter of our discontent")
+            .with_trailer(
+                "BREAKING CHANGE",
+                "made glorious summer by this sun of York",
+            )
+            .build(),
+    ];
+
+    let result = CommitAnalyzer::analyze(&commits);
+
+    assert!(result.ensure_breaking_described().is_ok());
+}
+
+#[test]
+fn recommends_bump_from_breaking_changes_and_features() {
+    let test_cases = [
+        (vec!["fix: the lady doth protest too much"], Bump::Patch),
+        (
+            vec!["feat: all the world's a stage", "fix: brevity is wit"],
+            Bump::Minor,
+        ),
+        (
+            vec!["feat!: off with his head", "feat: all the world's a stage"],
+            Bump::Major,
+        ),
+    ];
+
+    for (subjects, expected) in test_cases {
+        let commits: Vec<_> = subjects
+            .iter()
+            .map(|subject| CommitBuilder::new(subject).build())
+            .collect();
+
+        assert_eq!(
+            CommitAnalyzer::analyze(&commits).recommend_bump(),
+            expected,
+            "{:?}",
+            subjects
+        );
+    }
+}
+
+#[test]
+fn does_not_recommend_major_bump_for_reverted_breaking_change() {
+    let commits = vec![
+        CommitBuilder::new(r#"Revert "feat!: off with his head""#).build(),
+        CommitBuilder::new("feat: all the world's a stage").build(),
+        CommitBuilder::new("feat!: off with his head").build(),
+    ];
+    assert_eq!(
+        CommitAnalyzer::analyze(&commits).recommend_bump(),
+        Bump::Minor
+    );
+
+    let commits = vec![
+        CommitBuilder::new("Revert the removal of the third act")
+            .with_body("This reverts commit 5d4c3b2a1f0e9d8c7b6a5f4e3d2c1b0a9f8e7d6c.")
+            .build(),
+        CommitBuilder::new("refactor!: cut the third act")
+            .with_hash("5d4c3b2a1f0e9d8c7b6a5f4e3d2c1b0a9f8e7d6c")
+            .build(),
+    ];
+    assert_eq!(
+        CommitAnalyzer::analyze(&commits).recommend_bump(),
+        Bump::Patch
+    );
+}
+
+#[test]
+fn recommends_major_bump_when_the_revert_of_a_breaking_change_is_reverted() {
+    let commits = vec![
+        CommitBuilder::new(r#"Revert "Revert "feat!: off with his head"""#).build(),
+        CommitBuilder::new(r#"Revert "feat!: off with his head""#).build(),
+        CommitBuilder::new("feat!: off with his head").build(),
+    ];
+
+    assert_eq!(
+        CommitAnalyzer::analyze(&commits).recommend_bump(),
+        Bump::Major
+    );
+}
+
+#[test]
+fn lists_contributor_handles_excluding_bots() {
+    let commits = vec![
+        CommitBuilder::new("feat: all the world's a stage")
+            .with_contributor("shakespeare")
+            .build(),
+        CommitBuilder::new("fix(deps): the better part of valor is discretion")
+            .with_contributor_bot("renovate[bot]")
+            .build(),
+        CommitBuilder::new("fix: though she be but little, she is fierce")
+            .with_contributor("marlowe")
+            .build(),
+        CommitBuilder::new("feat: to be or not to be")
+            .with_contributor("shakespeare")
+            .build(),
+    ];
+
+    let result = CommitAnalyzer::analyze(&commits);
+
+    assert_eq!(result.contributor_handles(), vec!["shakespeare", "marlowe"]);
+}
+
+#[test]
+fn breaks_contributor_count_ties_alphabetically() {
+    let commits = vec![
+        CommitBuilder::new("feat: all the world's a stage")
+            .with_contributor("webster")
+            .build(),
+        CommitBuilder::new("fix: the lady doth protest too much")
+            .with_contributors(vec!["marlowe", "jonson"])
+            .build(),
+        CommitBuilder::new("docs: brevity is the soul of wit")
+            .with_contributor("webster")
+            .build(),
+    ];
+
+    let result = CommitAnalyzer::analyze(&commits);
+
+    assert_eq!(
+        result.contributor_handles(),
+        vec!["webster", "jonson", "marlowe"]
+    );
+}
+
+#[test]
+fn sorts_contributors_by_name_when_requested() {
+    let commits = vec![
+        CommitBuilder::new("feat: all the world's a stage")
+            .with_contributor("webster")
+            .build(),
+        CommitBuilder::new("fix: the lady doth protest too much")
+            .with_contributors(vec!["marlowe", "jonson"])
+            .build(),
+        CommitBuilder::new("docs: brevity is the soul of wit")
+            .with_contributor("webster")
+            .build(),
+    ];
+
+    let result = CommitAnalyzer::analyze_with_options(
+        &commits,
+        &AnalyzerOptions {
+            sort_contributors_by_name: true,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(
+        result.contributor_handles(),
+        vec!["jonson", "marlowe", "webster"]
+    );
+}
+
+#[test]
+fn merges_repositories_in_date_order_sorting_contributors_by_name() {
+    let stage = CommitAnalyzer::analyze(&[
+        CommitBuilder::new("feat: all the world's a stage")
+            .with_contributor("webster")
+            .with_timestamp(300)
+            .build(),
+        CommitBuilder::new("feat: brevity is the soul of wit")
+            .with_contributor("webster")
+            .with_timestamp(100)
+            .build(),
+    ]);
+    let props = CommitAnalyzer::analyze(&[CommitBuilder::new(
+        "feat: a horse! a horse! my kingdom for a horse!",
+    )
+    .with_contributor("marlowe")
+    .with_timestamp(200)
+    .build()]);
+    let options = AnalyzerOptions {
+        sort_contributors_by_name: true,
+        ..Default::default()
+    };
+
+    let merged = CategorizedCommits::merge(
+        vec![("stage".to_string(), stage), ("props".to_string(), props)],
+        CommitOrder::NewestFirst,
+        &options,
+    );
+
+    let features: Vec<_> = merged.by_category[&CommitCategory::Feature]
+        .iter()
+        .map(|c| c.first_line.as_str())
+        .collect();
+    assert_eq!(
+        features,
+        vec![
+            "feat: all the world's a stage",
+            "feat: a horse! a horse! my kingdom for a horse!",
+            "feat: brevity is the soul of wit",
+        ]
+    );
+    assert_eq!(merged.contributor_handles(), vec!["marlowe", "webster"]);
+}
+
+#[test]
+fn carries_display_names_into_contributor_summaries() {
+    let commits = vec![
+        CommitBuilder::new("feat: all the world's a stage")
+            .with_named_contributor("shakespeare", "William Shakespeare")
+            .build(),
+        CommitBuilder::new("fix: though she be but little, she is fierce")
+            .with_contributor("marlowe")
+            .build(),
+    ];
+
+    let result = CommitAnalyzer::analyze(&commits);
+
+    let names: Vec<_> = result
+        .contributors
+        .iter()
+        .map(|c| (c.username.as_str(), c.name.as_deref()))
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            ("marlowe", None),
+            ("shakespeare", Some("William Shakespeare"))
+        ]
+    );
+}
+
+#[test]
+fn summarizes_release_in_plain_text() {
+    let commits = vec![
+        CommitBuilder::new("feat!: off with his head").build(),
+        CommitBuilder::new("refactor!: so much for Buckingham").build(),
+        CommitBuilder::new("feat: all the world's a stage").build(),
+        CommitBuilder::new("fix: though she be but little, she is fierce").build(),
+        CommitBuilder::new("fix: the lady doth protest too much").build(),
+        CommitBuilder::new("docs: brevity is the soul of wit").build(),
+    ];
+
+    let result = CommitAnalyzer::analyze(&commits);
+
+    assert_eq!(
+        CommitAnalyzer::summarize(&result),
+        "2 breaking changes, 1 new feature, 2 bug fixes"
+    );
+    assert_eq!(
+        CommitAnalyzer::summarize_with_locale(&result, Locale::Es),
+        "2 cambios incompatibles, 1 nueva funcionalidad, 2 errores corregidos"
+    );
+}
+
+#[test]
+fn summarizes_release_without_rendered_commits_as_empty() {
+    let commits = vec![CommitBuilder::new("docs: brevity is the soul of wit").build()];
+
+    let result = CommitAnalyzer::analyze(&commits);
+
+    assert_eq!(CommitAnalyzer::summarize(&result), "");
+}
+
+#[test]
+fn round_trips_commit_category_slugs() {
+    for category in CommitCategory::ALL {
+        assert_eq!(category.to_string(), category.as_str());
+        assert_eq!(
+            category.as_str().parse::<CommitCategory>().unwrap(),
+            category
+        );
+        assert_eq!(serde_json::to_value(&category).unwrap(), category.as_str());
+    }
+
+    assert_eq!(CommitCategory::Breaking.as_str(), "breaking");
+    assert_eq!(CommitCategory::Feature.as_str(), "new-features");
+    assert!("features".parse::<CommitCategory>().is_err());
+}
+
+#[test]
+fn maps_commit_categories_to_template_anchors() {
+    assert_eq!(CommitCategory::Breaking.anchor(), Some("breaking-changes"));
+    assert_eq!(CommitCategory::Hotfix.anchor(), Some("hot-fixes"));
+    assert_eq!(CommitCategory::Feature.anchor(), Some("new-features"));
+    assert_eq!(CommitCategory::Fix.anchor(), Some("bug-fixes"));
+    assert_eq!(CommitCategory::Chore.anchor(), None);
+}
@@ -1,7 +1,7 @@
 #![allow(dead_code)]
 
 use release_note::contributor::Contributor;
-use release_note::git::{Commit, GitTrailer};
+use release_note::git::{Commit, GitTrailer, LinkedIssue};
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 
@@ -24,10 +24,12 @@ pub struct CommitBuilder {
     first_line: String,
     body: Option<String>,
     trailers: Vec<GitTrailer>,
+    linked_issues: Vec<LinkedIssue>,
     author: Option<String>,
     email: Option<String>,
     contributors: Vec<Contributor>,
     timestamp: Option<i64>,
+    reverted_pull_request: Option<u32>,
 }
 
 impl CommitBuilder {
@@ -37,10 +39,12 @@ impl CommitBuilder {
             first_line: first_line.to_string(),
             body: None,
             trailers: Vec::new(),
+            linked_issues: Vec::new(),
             author: None,
             email: None,
             contributors: Vec::new(),
             timestamp: None,
+            reverted_pull_request: None,
         }
     }
 
@@ -54,6 +58,16 @@ impl CommitBuilder {
         self
     }
 
+    /// Appends a footer to the body, separated by a blank line. Footers are kept in the
+    /// body as the builder bypasses trailer parsing.
+    pub fn with_footer(mut self, footer: &str) -> Self {
+        self.body = Some(match self.body {
+            Some(body) => format!("{}\n\n{}", body, footer),
+            None => footer.to_string(),
+        });
+        self
+    }
+
     pub fn with_trailer(mut self, key: &str, value: &str) -> Self {
         self.trailers.push(GitTrailer::from_key_value(
             key.to_string(),
@@ -62,6 +76,11 @@ impl CommitBuilder {
         self
     }
 
+    pub fn with_linked_issue(mut self, issue: LinkedIssue) -> Self {
+        self.linked_issues.push(issue);
+        self
+    }
+
     pub fn with_author(mut self, author: &str) -> Self {
         self.author = Some(author.to_string());
         self
@@ -78,6 +97,18 @@ impl CommitBuilder {
             avatar_url: format!("https://github.com/{}.png", username),
             is_bot: false,
             is_ai: false,
+            name: None,
+        });
+        self
+    }
+
+    pub fn with_named_contributor(mut self, username: &str, name: &str) -> Self {
+        self.contributors.push(Contributor {
+            username: username.to_string(),
+            avatar_url: format!("https://github.com/{}.png", username),
+            is_bot: false,
+            is_ai: false,
+            name: Some(name.to_string()),
         });
         self
     }
@@ -88,6 +119,7 @@ impl CommitBuilder {
             avatar_url: format!("https://github.com/{}.png", username),
             is_bot: true,
             is_ai: false,
+            name: None,
         });
         self
     }
@@ -100,6 +132,7 @@ impl CommitBuilder {
                 avatar_url: "https://avatars.githubusercontent.com/u/2651292?v=4".to_string(),
                 is_bot: false,
                 is_ai: false,
+                name: None,
             })
             .collect();
         self
@@ -110,6 +143,11 @@ impl CommitBuilder {
         self
     }
 
+    pub fn with_reverted_pull_request(mut self, number: u32) -> Self {
+        self.reverted_pull_request = Some(number);
+        self
+    }
+
     pub fn build(self) -> Commit {
         let hash = self.hash.unwrap_or_else(|| generate_hash(&self.first_line));
         Commit {
@@ -120,12 +158,25 @@ impl CommitBuilder {
             type_: String::new(),
             breaking: false,
             breaking_description: None,
+            deprecation_description: None,
+            release_note: None,
+            reverted_pull_request: self.reverted_pull_request,
             trailers: self.trailers,
-            linked_issues: Vec::new(),
+            linked_issues: self.linked_issues,
+            committer: self
+                .author
+                .clone()
+                .unwrap_or("William Shakespeare".to_string()),
+            committer_email: self
+                .email
+                .clone()
+                .unwrap_or("will@globe-theatre.com".to_string()),
             author: self.author.unwrap_or("William Shakespeare".to_string()),
             email: self.email.unwrap_or("will@globe-theatre.com".to_string()),
             contributors: self.contributors,
             timestamp: self.timestamp.unwrap_or(BASE_TIMESTAMP),
+            parent_hashes: Vec::new(),
+            repository: None,
         }
     }
 }
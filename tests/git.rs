@@ -1,7 +1,16 @@
 use anyhow::Result;
 use git2::{Oid, Repository, Signature, Time};
-use release_note::git::{GitRepo, GitTrailer};
-use std::path::Path;
+use release_note::analyzer::{AnalyzerOptions, CategorizedCommits, CommitAnalyzer, CommitCategory};
+use release_note::contributor::{Contributor, ContributorResolver, NoopResolver};
+use release_note::git::{
+    BlankLinePolicy, CommitOrder, DateSource, DroppedCommits, GitRepo, GitRepoError, GitTrailer,
+    proxy_for_remote,
+};
+use release_note::markdown;
+use release_note::platform::Platform;
+use release_note::template::DEFAULT_TEMPLATE;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use tempfile::TempDir;
 
 const TEST_USER_NAME: &str = "William Shakespeare";
@@ -67,7 +76,22 @@ impl TestRepo {
         let mut remaining = line;
 
         while let Some(start) = remaining.find("(tag:") {
-            if let Some(end) = remaining[start..].find(')') {
+            let mut depth = 0;
+            let closing = remaining[start..]
+                .char_indices()
+                .find_map(|(i, c)| match c {
+                    '(' => {
+                        depth += 1;
+                        None
+                    }
+                    ')' => {
+                        depth -= 1;
+                        (depth == 0).then_some(i)
+                    }
+                    _ => None,
+                });
+
+            if let Some(end) = closing {
                 let tag_section = remaining[start + 5..start + end].trim();
                 tags.extend(tag_section.split(',').map(|t| t.trim()));
                 remaining = &remaining[start + end + 1..];
@@ -89,14 +113,55 @@ impl TestRepo {
     }
 
     fn commit(&mut self, message: &str) -> Result<Oid> {
-        self.commit_internal(None, message)
+        self.commit_internal(None, message, None, None)
     }
 
     fn commit_in_path(&mut self, path: &str, message: &str) -> Result<Oid> {
-        self.commit_internal(Some(path), message)
+        self.commit_internal(Some(path), message, None, None)
     }
 
-    fn commit_internal(&mut self, path: Option<&str>, message: &str) -> Result<Oid> {
+    fn commit_files(&mut self, files: &[(String, Vec<u8>)], message: &str) -> Result<Oid> {
+        let mut index = self.repo.index()?;
+        for (path, content) in files {
+            let file_path = self._temp_dir.path().join(path);
+            if let Some(parent) = file_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(file_path, content)?;
+            index.add_path(Path::new(path))?;
+        }
+        index.write()?;
+
+        let tree = self.repo.find_tree(index.write_tree()?)?;
+        let sig = self.create_signature()?;
+        let parent = match self.commits.last() {
+            Some(oid) => Some(self.repo.find_commit(*oid)?),
+            None => None,
+        };
+        let parents: Vec<_> = parent.iter().collect();
+        let oid = self
+            .repo
+            .commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)?;
+
+        self.commits.push(oid);
+        Ok(oid)
+    }
+
+    fn commit_as(&mut self, name: &str, email: &str, message: &str) -> Result<Oid> {
+        self.commit_internal(None, message, Some((name, email)), None)
+    }
+
+    fn commit_applied_by(&mut self, name: &str, email: &str, message: &str) -> Result<Oid> {
+        self.commit_internal(None, message, None, Some((name, email)))
+    }
+
+    fn commit_internal(
+        &mut self,
+        path: Option<&str>,
+        message: &str,
+        author: Option<(&str, &str)>,
+        committer: Option<(&str, &str)>,
+    ) -> Result<Oid> {
         self.commit_counter += 1;
         let file_path = match path {
             Some(p) => format!("{}/file{}.txt", p, self.commit_counter),
@@ -119,7 +184,22 @@ impl TestRepo {
         let tree_id = index.write_tree()?;
         let tree = self.repo.find_tree(tree_id)?;
 
-        let sig = self.create_signature()?;
+        let sig = match author {
+            Some((name, email)) => Signature::new(
+                name,
+                email,
+                &Time::new(BASE_TIMESTAMP + self.commits.len() as i64, 0),
+            )?,
+            None => self.create_signature()?,
+        };
+        let committer_sig = match committer {
+            Some((name, email)) => Signature::new(
+                name,
+                email,
+                &Time::new(BASE_TIMESTAMP + self.commits.len() as i64, 0),
+            )?,
+            None => sig.clone(),
+        };
 
         let parent_commit = if self.commits.is_empty() {
             None
@@ -131,12 +211,39 @@ impl TestRepo {
         let parents: Vec<_> = parent_commit.iter().collect();
         let oid = self
             .repo
-            .commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)?;
+            .commit(Some("HEAD"), &sig, &committer_sig, message, &tree, &parents)?;
+
+        self.commits.push(oid);
+        Ok(oid)
+    }
+
+    fn merge(&mut self, message: &str, other: Oid) -> Result<Oid> {
+        let head = self.repo.find_commit(*self.commits.last().unwrap())?;
+        let other = self.repo.find_commit(other)?;
+        let sig = self.create_signature()?;
+
+        let oid = self.repo.commit(
+            Some("HEAD"),
+            &sig,
+            &sig,
+            message,
+            &head.tree()?,
+            &[&head, &other],
+        )?;
 
         self.commits.push(oid);
         Ok(oid)
     }
 
+    fn branch_commit(&mut self, message: &str, parent: Oid) -> Result<Oid> {
+        let parent = self.repo.find_commit(parent)?;
+        let sig = self.create_signature()?;
+
+        Ok(self
+            .repo
+            .commit(None, &sig, &sig, message, &parent.tree()?, &[&parent])?)
+    }
+
     fn create_tag(&self, name: &str, commit_oid: Oid) -> Result<()> {
         let commit = self.repo.find_commit(commit_oid)?;
         let sig = self.create_signature()?;
@@ -161,6 +268,7 @@ fn includes_entire_history_on_first_release() -> Result<()> {
     )?;
 
     let git_repo = GitRepo::open(test_repo.path())?;
+    assert_eq!(git_repo.path_in_repo(), None);
     let commits = git_repo.history(None, None)?;
 
     assert_eq!(commits.len(), 3);
@@ -178,276 +286,1711 @@ fn includes_entire_history_on_first_release() -> Result<()> {
 }
 
 #[test]
-fn fails_on_empty_repository() -> Result<()> {
-    let temp_dir = TempDir::new()?;
-    let _repo = Repository::init(temp_dir.path())?;
+fn caps_first_release_to_depth_only_without_previous_tag() -> Result<()> {
+    let test_repo = TestRepo::from_log(
+        "
+        The better part of valor is discretion
+        Lord, what fools these mortals be!
+        If music be the food of love, play on
+        (tag: v1.0.0) Brevity is the soul of wit
+        Frailty, thy name is woman
+        All that glisters is not gold
+    ",
+    )?;
 
-    let result = GitRepo::open(temp_dir.path());
-    assert!(result.is_err());
-    let err = result.err().unwrap();
-    assert!(err.to_string().contains("empty"));
+    let git_repo = GitRepo::open(test_repo.path())?.with_first_release_depth(Some(2));
+
+    let commits = git_repo.history(Some("v1.0.0".to_string()), None)?;
+    assert_eq!(commits.len(), 2);
+    assert_eq!(commits[0].first_line, "Brevity is the soul of wit");
+    assert_eq!(commits[1].first_line, "Frailty, thy name is woman");
+
+    let commits = git_repo.history(None, None)?;
+    assert_eq!(commits.len(), 3);
+
+    let git_repo = git_repo.with_commit_order(CommitOrder::OldestFirst);
+    let commits = git_repo.history(Some("v1.0.0".to_string()), None)?;
+    assert_eq!(commits.len(), 2);
+    assert_eq!(commits[0].first_line, "Frailty, thy name is woman");
+    assert_eq!(commits[1].first_line, "Brevity is the soul of wit");
 
     Ok(())
 }
 
 #[test]
-fn fails_on_shallow_clone() -> Result<()> {
+fn analyzes_history_directly_from_repository() -> Result<()> {
     let test_repo = TestRepo::from_log(
-        r#"
-        feat: we know what we are, but know not what we may be
-        fix: some are born great, some achieve greatness
-        "#,
+        "
+        feat: the quality of mercy is not strained
+        fix: it droppeth as the gentle rain from heaven (tag: 0.1.0)
+        docs: upon the place beneath
+    ",
     )?;
 
-    let shallow_file = test_repo.repo.path().join("shallow");
-    std::fs::write(&shallow_file, format!("{}\n", test_repo.commits[0]))?;
+    let git_repo = GitRepo::open(test_repo.path())?;
+    let categorized = CommitAnalyzer::analyze_from_repo(&git_repo, None, None, None)?;
+
+    assert_eq!(categorized.by_category.len(), 1);
+    let features = categorized
+        .by_category
+        .get(&CommitCategory::Feature)
+        .unwrap();
+    assert_eq!(features.len(), 1);
+    assert_eq!(
+        features[0].first_line,
+        "feat: the quality of mercy is not strained"
+    );
+    assert!(categorized.contributors.is_empty());
 
-    let result = GitRepo::open(test_repo.path());
-    assert!(result.is_err());
-    let err = result.err().unwrap();
-    assert!(err.to_string().contains("shallow"));
+    Ok(())
+}
+
+#[test]
+fn analyzes_repository_with_noop_contributor_resolver() -> Result<()> {
+    let test_repo = TestRepo::from_log(
+        "
+        feat: the quality of mercy is not strained
+        fix: it droppeth as the gentle rain from heaven
+        ",
+    )?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    let mut resolver = ContributorResolver::with_resolver(NoopResolver);
+    let categorized =
+        CommitAnalyzer::analyze_from_repo(&git_repo, None, None, Some(&mut resolver))?;
+
+    assert_eq!(categorized.by_category.len(), 2);
+    assert!(
+        categorized
+            .by_category
+            .values()
+            .flatten()
+            .all(|c| c.contributors.is_empty())
+    );
+    assert!(categorized.contributors.is_empty());
 
     Ok(())
 }
 
 #[test]
-fn extracts_and_strips_linked_issues() -> Result<()> {
-    let mut test_repo = TestRepo::new()?;
+fn chooses_proxy_for_remote_by_scheme() {
+    let env = HashMap::from([
+        ("HTTPS_PROXY", "http://proxy.globe-theatre.com:3128"),
+        ("HTTP_PROXY", "http://proxy.globe-theatre.com:8080"),
+    ]);
+    let env = |key: &str| env.get(key).map(|value| value.to_string());
 
-    let message = r#"feat: to thine own self be true
-closes #1
-CLOSE: #2
-closed #3
-fix #4
-fixes: #5
-FIXED #6
-resolve #7
-RESOLVED #8
-resolves: #9
-This above all: to thine own self be true, and it must follow, as the night the day.
-Resolves globe-theatre/hamlet#100
-Thou canst not then be false to any man."#;
-    test_repo.commit(message)?;
+    assert_eq!(
+        proxy_for_remote("https://github.com/globe/theatre.git", env),
+        Some("http://proxy.globe-theatre.com:3128".to_string())
+    );
+    assert_eq!(
+        proxy_for_remote("http://git.globe-theatre.com/globe/theatre.git", env),
+        Some("http://proxy.globe-theatre.com:8080".to_string())
+    );
+    assert_eq!(
+        proxy_for_remote("git@github.com:globe/theatre.git", env),
+        None
+    );
+}
 
-    let git_repo = GitRepo::open(test_repo.path())?;
-    let commits = git_repo.history(None, None)?;
+#[test]
+fn bypasses_proxy_for_hosts_in_no_proxy() {
+    let env = HashMap::from([
+        ("HTTPS_PROXY", "http://proxy.globe-theatre.com:3128"),
+        ("NO_PROXY", "localhost, .globe-theatre.com"),
+    ]);
+    let env = |key: &str| env.get(key).map(|value| value.to_string());
+
+    assert_eq!(
+        proxy_for_remote("https://git.globe-theatre.com:8443/globe/theatre.git", env),
+        None
+    );
+    assert_eq!(
+        proxy_for_remote("https://globe-theatre.com/globe/theatre.git", env),
+        None
+    );
+    assert_eq!(
+        proxy_for_remote("https://github.com/globe/theatre.git", env),
+        Some("http://proxy.globe-theatre.com:3128".to_string())
+    );
+}
+
+#[test]
+fn clones_and_opens_repository_from_url() -> Result<()> {
+    let test_repo = TestRepo::from_log(
+        "
+        feat: the fault, dear Brutus, is not in our stars
+        (tag: v1.0.0) fix: but in ourselves, that we are underlings
+    ",
+    )?;
 
+    let dest = TempDir::new()?;
+    let url = test_repo.path().to_string_lossy().to_string();
+    let git_repo = GitRepo::clone_and_open(&url, dest.path(), None)?;
+
+    assert_eq!(git_repo.origin_url(), Some(url.as_str()));
+    let commits = git_repo.history(None, None)?;
     assert_eq!(commits.len(), 1);
     assert_eq!(
-        commits[0].body.as_deref(),
-        Some(
-            "This above all: to thine own self be true, and it must follow, as the night the day.\nThou canst not then be false to any man."
-        )
+        commits[0].first_line,
+        "feat: the fault, dear Brutus, is not in our stars"
     );
 
-    assert_eq!(commits[0].linked_issues.len(), 10);
-    assert_eq!(commits[0].linked_issues[0].number, 1);
-    assert_eq!(commits[0].linked_issues[0].owner, None);
-    assert_eq!(commits[0].linked_issues[0].repo, None);
-    assert_eq!(commits[0].linked_issues[1].number, 2);
-    assert_eq!(commits[0].linked_issues[2].number, 3);
-    assert_eq!(commits[0].linked_issues[3].number, 4);
-    assert_eq!(commits[0].linked_issues[4].number, 5);
-    assert_eq!(commits[0].linked_issues[5].number, 6);
-    assert_eq!(commits[0].linked_issues[6].number, 7);
-    assert_eq!(commits[0].linked_issues[7].number, 8);
-    assert_eq!(commits[0].linked_issues[8].number, 9);
-    assert_eq!(commits[0].linked_issues[9].number, 100);
+    Ok(())
+}
+
+#[test]
+fn reads_history_from_bare_repository() -> Result<()> {
+    let test_repo = TestRepo::from_log(
+        "
+        feat: the fault, dear Brutus, is not in our stars
+        (tag: v1.0.0) fix: but in ourselves, that we are underlings
+    ",
+    )?;
+
+    let dest = TempDir::new()?;
+    let url = test_repo.path().to_string_lossy().to_string();
+    git2::build::RepoBuilder::new()
+        .bare(true)
+        .clone(&url, dest.path())?;
+
+    let git_repo = GitRepo::open(dest.path())?;
+    assert_eq!(git_repo.path_in_repo(), None);
+
+    let categorized = CommitAnalyzer::analyze_from_repo(&git_repo, None, None, None)?;
+    let features = &categorized.by_category[&CommitCategory::Feature];
+    assert_eq!(features.len(), 1);
     assert_eq!(
-        commits[0].linked_issues[9].owner.as_deref(),
-        Some("globe-theatre")
+        features[0].first_line,
+        "feat: the fault, dear Brutus, is not in our stars"
     );
-    assert_eq!(commits[0].linked_issues[9].repo.as_deref(), Some("hamlet"));
 
     Ok(())
 }
 
 #[test]
-fn includes_history_between_existing_releases() -> Result<()> {
+fn fetches_tags_missing_from_clone() -> Result<()> {
     let test_repo = TestRepo::from_log(
         "
-        (tag: v3.0.0) To be, or not to be, that is the question
-        (tag: v2.0.0) All the world's a stage
-        (tag: v1.0.0) What's in a name? That which we call a rose
+        feat: the fault, dear Brutus, is not in our stars
+        (tag: v1.0.0) fix: but in ourselves, that we are underlings
     ",
     )?;
 
-    let git_repo = GitRepo::open(test_repo.path())?;
-    let commits = git_repo.history(Some("v3.0.0".to_string()), None)?;
+    let dest = TempDir::new()?;
+    let url = test_repo.path().to_string_lossy().to_string();
+    GitRepo::clone_and_open(&url, dest.path(), None)?;
 
+    let clone = Repository::open(dest.path())?;
+    clone.tag_delete("v1.0.0")?;
+
+    let git_repo = GitRepo::open(dest.path())?;
+    assert_eq!(git_repo.history(None, None)?.len(), 2);
+
+    git_repo.fetch_tags(None)?;
+    let commits = git_repo.history(None, None)?;
     assert_eq!(commits.len(), 1);
     assert_eq!(
         commits[0].first_line,
-        "To be, or not to be, that is the question"
+        "feat: the fault, dear Brutus, is not in our stars"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn detects_default_branch_of_origin() -> Result<()> {
+    let test_repo = TestRepo::from_log(
+        "
+        feat: the fault, dear Brutus, is not in our stars
+        fix: but in ourselves, that we are underlings
+    ",
+    )?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    assert_eq!(git_repo.default_branch(), None);
+
+    let head = test_repo.repo.head()?.peel_to_commit()?.id();
+    test_repo
+        .repo
+        .reference("refs/remotes/origin/trunk", head, false, "fetch")?;
+    test_repo.repo.reference_symbolic(
+        "refs/remotes/origin/HEAD",
+        "refs/remotes/origin/trunk",
+        false,
+        "set-head",
+    )?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    assert_eq!(git_repo.default_branch(), Some("trunk".to_string()));
+    assert!(git_repo.is_branch("origin/trunk"));
+    assert!(!git_repo.is_branch("HEAD~1"));
+
+    Ok(())
+}
+
+#[test]
+fn parses_version_from_tag_at_reference() -> Result<()> {
+    let test_repo = TestRepo::from_log(
+        "
+        feat: the fault, dear Brutus, is not in our stars
+        (tag: v1.2.0) fix: but in ourselves, that we are underlings
+        (tag: 1.1.0) feat: beware the ides of March
+    ",
+    )?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    assert_eq!(git_repo.version(None)?, None);
+    assert_eq!(
+        git_repo.version(Some("v1.2.0"))?,
+        Some(semver::Version::new(1, 2, 0))
+    );
+    assert_eq!(
+        git_repo.version(Some("HEAD~2"))?,
+        Some(semver::Version::new(1, 1, 0))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn captures_parent_hashes_of_merge_commits() -> Result<()> {
+    let mut test_repo = TestRepo::from_log(
+        "
+        fix: there is nothing either good or bad
+        feat: but thinking makes it so
+    ",
+    )?;
+
+    let side = test_repo.branch_commit("feat: denmark's a prison", test_repo.commits[0])?;
+    let main = *test_repo.commits.last().unwrap();
+    let merge = test_repo.merge("Merge branch 'prison'", side)?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    let commits = git_repo.history(None, None)?;
+
+    let merge_commit = commits
+        .iter()
+        .find(|c| c.hash == merge.to_string())
+        .unwrap();
+    assert_eq!(
+        merge_commit.parent_hashes,
+        vec![main.to_string(), side.to_string()]
+    );
+
+    assert!(
+        commits
+            .iter()
+            .filter(|c| c.hash != merge.to_string())
+            .all(|c| c.parent_hashes.is_empty())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn uses_chosen_date_source_in_contributor_commit_links() -> Result<()> {
+    let mut test_repo = TestRepo::new()?;
+
+    let oid = test_repo.commit("feat: what's past is prologue")?;
+    let rebased = Signature::new(
+        TEST_USER_NAME,
+        TEST_USER_EMAIL,
+        &Time::new(BASE_TIMESTAMP + 10 * 86400, 0),
+    )?;
+    test_repo
+        .repo
+        .find_commit(oid)?
+        .amend(Some("HEAD"), None, Some(&rebased), None, None, None)?;
+
+    let platform = Platform::GitHub {
+        url: "https://github.com/shakespeare/globe-theatre".to_string(),
+        api_url: "https://api.github.com".to_string(),
+        owner: "shakespeare".to_string(),
+        repo: "globe-theatre".to_string(),
+        token: None,
+    };
+
+    for (date_source, date) in [
+        (DateSource::Committer, "2019-08-10"),
+        (DateSource::Author, "2019-07-31"),
+    ] {
+        let git_repo = GitRepo::open(test_repo.path())?.with_date_source(date_source);
+        let mut commits = git_repo.history(None, None)?;
+        commits[0].contributors = vec![Contributor {
+            username: "shakespeare".to_string(),
+            avatar_url: "https://avatars.githubusercontent.com/u/1?v=4".to_string(),
+            is_bot: false,
+            is_ai: false,
+            name: None,
+        }];
+
+        let note = markdown::render_history(
+            &CommitAnalyzer::analyze(&commits),
+            &platform,
+            "HEAD",
+            BASE_TIMESTAMP,
+            DEFAULT_TEMPLATE,
+        )?;
+        assert!(
+            note.contains(&format!("since={}&until={}", date, date)),
+            "{:?}: {}",
+            date_source,
+            note
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn reads_release_date_from_tagged_commit() -> Result<()> {
+    let test_repo = TestRepo::from_log(
+        "
+        feat: once more unto the breach, dear friends
+        fix: cry God for Harry, England, and Saint George!
+    ",
+    )?;
+
+    let tagged = test_repo.repo.find_commit(test_repo.commits[0])?;
+    test_repo
+        .repo
+        .tag_lightweight("v1.0.0", tagged.as_object(), false)?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+
+    assert_eq!(
+        git_repo.tag_time(Some("v1.0.0"))?,
+        Some(tagged.time().seconds())
+    );
+    assert_eq!(git_repo.tag_time(None)?, None);
+
+    Ok(())
+}
+
+#[test]
+fn reads_release_date_from_annotated_tag() -> Result<()> {
+    let test_repo = TestRepo::from_log(
+        "
+        (tag: v2.0.0) feat: we few, we happy few, we band of brothers
+        fix: cry God for Harry, England, and Saint George!
+    ",
+    )?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    let tag = test_repo
+        .repo
+        .find_reference("refs/tags/v2.0.0")?
+        .peel_to_tag()?;
+
+    assert_eq!(
+        git_repo.tag_time(None)?,
+        Some(tag.tagger().unwrap().when().seconds())
     );
+
+    Ok(())
+}
+
+#[test]
+fn strips_signature_from_signed_tag_message() -> Result<()> {
+    let test_repo = TestRepo::from_log(
+        "
+        feat: we few, we happy few, we band of brothers
+        fix: cry God for Harry, England, and Saint George!
+    ",
+    )?;
+
+    let head = test_repo.repo.head()?.peel_to_commit()?;
+    let sig = test_repo.create_signature()?;
+    test_repo.repo.tag(
+        "v2.0.0",
+        head.as_object(),
+        &sig,
+        "Once more unto the breach\n\n-----BEGIN PGP SIGNATURE-----\n\niQEzBAABCAAdFiEE\n=Xy9z\n-----END PGP SIGNATURE-----\n",
+        false,
+    )?;
+    test_repo
+        .repo
+        .tag("v1.0.0", head.as_object(), &sig, "", false)?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    assert_eq!(
+        git_repo.tag_message(Some("v2.0.0"))?,
+        Some("Once more unto the breach".to_string())
+    );
+    assert_eq!(git_repo.tag_message(Some("v1.0.0"))?, None);
+    assert_eq!(git_repo.tag_message(Some("HEAD~1"))?, None);
+
+    Ok(())
+}
+
+#[test]
+fn fails_to_read_message_of_missing_tag_object() -> Result<()> {
+    let test_repo = TestRepo::from_log(
+        "
+        feat: we few, we happy few, we band of brothers
+    ",
+    )?;
+
+    let head = test_repo.repo.head()?.peel_to_commit()?;
+    let sig = test_repo.create_signature()?;
+    test_repo
+        .repo
+        .tag_lightweight("v1.0.0", head.as_object(), false)?;
+    let tag = test_repo.repo.tag(
+        "v2.0.0",
+        head.as_object(),
+        &sig,
+        "Once more unto the breach",
+        false,
+    )?;
+    let hex = tag.to_string();
+    std::fs::remove_file(
+        test_repo
+            .repo
+            .path()
+            .join("objects")
+            .join(&hex[..2])
+            .join(&hex[2..]),
+    )?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    assert_eq!(git_repo.tag_message(Some("v1.0.0"))?, None);
+    assert!(git_repo.tag_message(Some("v2.0.0")).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn peels_nested_annotated_tags_to_underlying_commit() -> Result<()> {
+    let test_repo = TestRepo::from_log(
+        "
+        feat: o, that this too too solid flesh would melt
+        fix: frailty, thy name is woman
+        feat: the rest is silence
+    ",
+    )?;
+
+    let sig = test_repo.create_signature()?;
+    let tagged_commit = test_repo.repo.find_commit(test_repo.commits[1])?;
+    let inner_oid = test_repo.repo.tag(
+        "release-candidate",
+        tagged_commit.as_object(),
+        &sig,
+        "",
+        false,
+    )?;
+    let inner_tag = test_repo.repo.find_object(inner_oid, None)?;
+    test_repo.repo.tag("v1.0.0", &inner_tag, &sig, "", false)?;
+
+    let nested = test_repo
+        .repo
+        .find_reference("refs/tags/v1.0.0")?
+        .peel_to_tag()?;
+    assert_eq!(nested.target_type(), Some(git2::ObjectType::Tag));
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    let commits = git_repo.history(None, None)?;
+
+    assert_eq!(commits.len(), 1);
+    assert_eq!(
+        commits[0].first_line,
+        "feat: o, that this too too solid flesh would melt"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn lists_history_oldest_first() -> Result<()> {
+    let test_repo = TestRepo::from_log(
+        "
+        feat: the better part of valor is discretion
+        fix: lord, what fools these mortals be!
+        (tag: v1.0.0) feat: if music be the food of love, play on
+    ",
+    )?;
+
+    let git_repo = GitRepo::open(test_repo.path())?.with_commit_order(CommitOrder::OldestFirst);
+    let commits = git_repo.history(None, None)?;
+
+    assert_eq!(commits.len(), 2);
+    assert_eq!(
+        commits[0].first_line,
+        "fix: lord, what fools these mortals be!"
+    );
+    assert_eq!(
+        commits[1].first_line,
+        "feat: the better part of valor is discretion"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn fails_when_history_contains_uncategorized_commits() -> Result<()> {
+    let test_repo = TestRepo::from_log(
+        "
+        feat: the quality of mercy is not strained
+        It droppeth as the gentle rain from heaven
+        fix: upon the place beneath
+    ",
+    )?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    let categorized = CommitAnalyzer::analyze_from_repo(&git_repo, None, None, None)?;
+
+    let result = categorized.ensure_categorized();
+    assert!(result.is_err());
+    let err = result.err().unwrap().to_string();
+    assert!(err.contains("1 commit(s) could not be categorized"));
+    assert!(err.contains(&format!(
+        "{} It droppeth as the gentle rain from heaven",
+        &test_repo.commits[1].to_string()[..7]
+    )));
+
+    Ok(())
+}
+
+#[test]
+fn filters_path_relative_to_linked_worktree() -> Result<()> {
+    let mut test_repo = TestRepo::new()?;
+    test_repo.commit("The readiness is all")?;
+    test_repo.commit_in_path("src", "There is nothing either good or bad")?;
+
+    let worktree_dir = TempDir::new()?;
+    let worktree_path = worktree_dir.path().join("feature");
+    let worktree = test_repo.repo.worktree("feature", &worktree_path, None)?;
+
+    let worktree_repo = Repository::open_from_worktree(&worktree)?;
+    let head = worktree_repo.head()?.peel_to_commit()?;
+    std::fs::create_dir_all(worktree_path.join("src"))?;
+    std::fs::write(
+        worktree_path.join("src/feature.txt"),
+        "but thinking makes it so",
+    )?;
+    let mut index = worktree_repo.index()?;
+    index.add_path(Path::new("src/feature.txt"))?;
+    let tree = worktree_repo.find_tree(index.write_tree()?)?;
+    let sig = test_repo.create_signature()?;
+    worktree_repo.commit(
+        Some("HEAD"),
+        &sig,
+        &sig,
+        "But thinking makes it so",
+        &tree,
+        &[&head],
+    )?;
+
+    let git_repo = GitRepo::open(worktree_path.join("src"))?;
+    assert_eq!(git_repo.path_in_repo(), Some(Path::new("src")));
+
+    let commits = git_repo.history(None, None)?;
+    assert_eq!(commits.len(), 2);
+    assert_eq!(commits[0].first_line, "But thinking makes it so");
+    assert_eq!(commits[1].first_line, "There is nothing either good or bad");
+
+    Ok(())
+}
+
+#[test]
+fn generates_release_note_from_linked_worktree() -> Result<()> {
+    let test_repo = TestRepo::from_log(
+        "
+        feat: to be or not to be
+        (tag: 1.0.0) feat: all the world's a stage
+        chore: the readiness is all
+    ",
+    )?;
+
+    let worktree_dir = TempDir::new()?;
+    let worktree_path = worktree_dir.path().join("release");
+    let worktree = test_repo.repo.worktree("release", &worktree_path, None)?;
+
+    let worktree_repo = Repository::open_from_worktree(&worktree)?;
+    let head = worktree_repo.head()?.peel_to_commit()?;
+    let sig = test_repo.create_signature()?;
+    let fix = worktree_repo.commit(
+        Some("HEAD"),
+        &sig,
+        &sig,
+        "fix: the lady doth protest too much",
+        &head.tree()?,
+        &[&head],
+    )?;
+
+    // Tags live in the main repository, yet still bound the range from a worktree
+    let git_repo = GitRepo::open(&worktree_path)?;
+    let commits = git_repo.history(None, None)?;
+    let subjects: Vec<&str> = commits.iter().map(|c| c.first_line.as_str()).collect();
+    assert_eq!(
+        subjects,
+        vec![
+            "fix: the lady doth protest too much",
+            "feat: to be or not to be"
+        ]
+    );
+    assert_eq!(git_repo.release_tag("1.0.0")?, "1.0.0");
+
+    let categorized = CommitAnalyzer::analyze(&commits);
+    let rendered = markdown::render_history(
+        &categorized,
+        &Platform::Unknown,
+        &git_repo.current_ref()?,
+        BASE_TIMESTAMP,
+        DEFAULT_TEMPLATE,
+    )?;
+    assert!(
+        rendered.starts_with(&format!("## {} - ", &fix.to_string()[..7])),
+        "{}",
+        rendered
+    );
+    assert!(rendered.contains("the lady doth protest too much"));
+    assert!(rendered.contains("to be or not to be"));
+
+    Ok(())
+}
+
+#[test]
+fn excludes_commits_by_author_email() -> Result<()> {
+    let mut test_repo = TestRepo::new()?;
+    test_repo.commit("feat: all the world's a stage")?;
+    test_repo.commit_as(
+        "release-bot",
+        "Release-Bot@globe-theatre.com",
+        "Release 1.0.0",
+    )?;
+    test_repo.commit("fix: and all the men and women merely players")?;
+
+    let git_repo = GitRepo::open(test_repo.path())?
+        .with_excluded_authors(vec!["release-bot@globe-theatre.com".to_string()]);
+    let commits = git_repo.history(None, None)?;
+
+    assert_eq!(commits.len(), 2);
+    assert!(commits.iter().all(|c| c.first_line != "Release 1.0.0"));
+
+    Ok(())
+}
+
+#[test]
+fn excludes_commits_by_committer_email_when_attributing_to_committer() -> Result<()> {
+    let mut test_repo = TestRepo::new()?;
+    test_repo.commit("feat: all the world's a stage")?;
+    test_repo.commit_applied_by(
+        "release-bot",
+        "release-bot@globe-theatre.com",
+        "fix: and all the men and women merely players",
+    )?;
+
+    let excluded = vec!["release-bot@globe-theatre.com".to_string()];
+    let commits = GitRepo::open(test_repo.path())?
+        .with_excluded_authors(excluded.clone())
+        .history(None, None)?;
+    assert_eq!(commits.len(), 2);
+
+    let commits = GitRepo::open(test_repo.path())?
+        .with_committer_attribution(true)
+        .with_excluded_authors(excluded)
+        .history(None, None)?;
+    assert_eq!(commits.len(), 1);
+    assert_eq!(commits[0].first_line, "feat: all the world's a stage");
+
+    Ok(())
+}
+
+#[test]
+fn fails_on_empty_repository() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let _repo = Repository::init(temp_dir.path())?;
+
+    let result = GitRepo::open(temp_dir.path());
+    assert!(result.is_err());
+    let err = result.err().unwrap();
+    assert!(err.to_string().contains("empty"));
+
+    Ok(())
+}
+
+#[test]
+fn detects_shallow_clone() -> Result<()> {
+    let test_repo = TestRepo::from_log(
+        r#"
+        feat: we know what we are, but know not what we may be
+        fix: some are born great, some achieve greatness
+        "#,
+    )?;
+
+    let shallow_file = test_repo.repo.path().join("shallow");
+    std::fs::write(&shallow_file, format!("{}\n", test_repo.commits[0]))?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    assert!(git_repo.is_shallow());
+
+    Ok(())
+}
+
+#[test]
+fn reports_full_clone_as_not_shallow() -> Result<()> {
+    let test_repo = TestRepo::from_log("feat: we know what we are, but know not what we may be")?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    assert!(!git_repo.is_shallow());
+
+    Ok(())
+}
+
+#[test]
+fn fails_with_hint_when_object_database_is_corrupted() -> Result<()> {
+    let test_repo = TestRepo::from_log(
+        r#"
+        feat: the fault, dear Brutus, is not in our stars
+        fix: but in ourselves, that we are underlings
+        docs: men at some time are masters of their fates
+        "#,
+    )?;
+
+    let missing = test_repo.commits[1].to_string();
+    let object_file = test_repo
+        .repo
+        .path()
+        .join("objects")
+        .join(&missing[..2])
+        .join(&missing[2..]);
+    std::fs::remove_file(object_file)?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    let result = git_repo.history(None, None);
+    assert!(result.is_err());
+    let err = result.err().unwrap();
+    assert!(err.to_string().contains("failed to find commit"));
+    assert!(err.to_string().contains("git fsck"));
+    assert!(matches!(
+        err.downcast_ref::<GitRepoError>(),
+        Some(GitRepoError::CommitNotFound(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn extracts_and_strips_linked_issues() -> Result<()> {
+    let mut test_repo = TestRepo::new()?;
+
+    let message = r#"feat: to thine own self be true
+closes #1
+CLOSE: #2
+closed #3
+fix #4
+fixes: #5
+FIXED #6
+resolve #7
+RESOLVED #8
+resolves: #9
+This above all: to thine own self be true, and it must follow, as the night the day.
+Resolves globe-theatre/hamlet#100
+Thou canst not then be false to any man."#;
+    test_repo.commit(message)?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    let commits = git_repo.history(None, None)?;
+
+    assert_eq!(commits.len(), 1);
+    assert_eq!(
+        commits[0].body.as_deref(),
+        Some(
+            "This above all: to thine own self be true, and it must follow, as the night the day.\nThou canst not then be false to any man."
+        )
+    );
+
+    assert_eq!(commits[0].linked_issues.len(), 10);
+    assert_eq!(commits[0].linked_issues[0].number, 1);
+    assert_eq!(commits[0].linked_issues[0].owner, None);
+    assert_eq!(commits[0].linked_issues[0].repo, None);
+    assert_eq!(commits[0].linked_issues[1].number, 2);
+    assert_eq!(commits[0].linked_issues[2].number, 3);
+    assert_eq!(commits[0].linked_issues[3].number, 4);
+    assert_eq!(commits[0].linked_issues[4].number, 5);
+    assert_eq!(commits[0].linked_issues[5].number, 6);
+    assert_eq!(commits[0].linked_issues[6].number, 7);
+    assert_eq!(commits[0].linked_issues[7].number, 8);
+    assert_eq!(commits[0].linked_issues[8].number, 9);
+    assert_eq!(commits[0].linked_issues[9].number, 100);
+    assert_eq!(
+        commits[0].linked_issues[9].owner.as_deref(),
+        Some("globe-theatre")
+    );
+    assert_eq!(commits[0].linked_issues[9].repo.as_deref(), Some("hamlet"));
+
+    Ok(())
+}
+
+#[test]
+fn keeps_linked_issue_lines_in_body_when_stripping_disabled() -> Result<()> {
+    let mut test_repo = TestRepo::new()?;
+
+    let message = r#"fix: something is rotten in the state of denmark
+
+The ghost walks the battlements at midnight.
+
+Closes #35
+
+Signed-off-by: William Shakespeare <will@globe-theatre.com>"#;
+    test_repo.commit(message)?;
+
+    let git_repo = GitRepo::open(test_repo.path())?.with_issue_lines_kept(true);
+    let commits = git_repo.history(None, None)?;
+
+    assert_eq!(commits.len(), 1);
+    assert_eq!(
+        commits[0].body.as_deref(),
+        Some("The ghost walks the battlements at midnight.\n\nCloses #35")
+    );
+    assert_eq!(commits[0].linked_issues.len(), 1);
+    assert_eq!(commits[0].linked_issues[0].number, 35);
+    assert_eq!(commits[0].trailers.len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn yields_no_body_when_message_only_contains_issues_and_trailers() -> Result<()> {
+    let mut test_repo = TestRepo::new()?;
+
+    test_repo.commit(
+        "fix: what's done cannot be undone\n\nCloses #1\nSigned-off-by: Lady Macbeth <lady@macbeth.com>",
+    )?;
+    test_repo.commit(
+        "fix: out, damned spot! out, I say!\n\nSigned-off-by: Lady Macbeth <lady@macbeth.com>\nCloses #2",
+    )?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    let commits = git_repo.history(None, None)?;
+
+    assert_eq!(commits.len(), 2);
+    for (commit, issue) in commits.iter().zip([2, 1]) {
+        assert_eq!(commit.body, None);
+        assert_eq!(commit.linked_issues.len(), 1);
+        assert_eq!(commit.linked_issues[0].number, issue);
+        assert_eq!(commit.trailers.len(), 1);
+        assert_eq!(
+            commit.trailers[0].to_string(),
+            "Signed-off-by: Lady Macbeth <lady@macbeth.com>"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn detects_jira_keys_as_linked_issues_when_enabled() -> Result<()> {
+    let mut test_repo = TestRepo::new()?;
+
+    test_repo.commit(
+        "feat: GLOBE-42 all the world's a stage\n\nAnd all the men and women merely players, see ROSE-7.\n\nCloses #3",
+    )?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    let commits = git_repo.history(None, None)?;
+    assert_eq!(commits[0].linked_issues.len(), 1);
+
+    let git_repo = GitRepo::open(test_repo.path())?
+        .with_jira_projects(vec!["GLOBE".to_string(), "ROSE".to_string()]);
+    let commits = git_repo.history(None, None)?;
+    let issues = &commits[0].linked_issues;
+
+    assert_eq!(issues.len(), 3);
+    assert_eq!(issues[0].number, 3);
+    assert_eq!(issues[0].project, None);
+    assert_eq!(issues[1].number, 42);
+    assert_eq!(issues[1].project.as_deref(), Some("GLOBE"));
+    assert_eq!(issues[2].number, 7);
+    assert_eq!(issues[2].project.as_deref(), Some("ROSE"));
+
+    Ok(())
+}
+
+#[test]
+fn ignores_jira_lookalikes_outside_listed_projects() -> Result<()> {
+    let mut test_repo = TestRepo::new()?;
+
+    test_repo.commit(
+        "fix: decode UTF-8 names in AB1-123\n\nVerify SHA-256 digests, parse ISO-8601 dates and patch CVE-2024-3094.",
+    )?;
+
+    let git_repo = GitRepo::open(test_repo.path())?.with_jira_projects(vec!["AB1".to_string()]);
+    let commits = git_repo.history(None, None)?;
+    let issues = &commits[0].linked_issues;
+
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].number, 123);
+    assert_eq!(issues[0].project.as_deref(), Some("AB1"));
+
+    Ok(())
+}
+
+#[test]
+fn collapses_consecutive_duplicate_subjects_when_enabled() -> Result<()> {
+    let test_repo = TestRepo::from_log(
+        "
+        fix: the lady doth protest too much, methinks
+        fix: the lady doth protest too much, methinks
+        feat: to sleep, perchance to dream
+    ",
+    )?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    assert_eq!(git_repo.history(None, None)?.len(), 3);
+
+    let git_repo = GitRepo::open(test_repo.path())?.with_dedupe_subjects(true);
+    let commits = git_repo.history(None, None)?;
+
+    assert_eq!(commits.len(), 2);
+    assert_eq!(
+        commits[0].first_line,
+        "fix: the lady doth protest too much, methinks"
+    );
+    assert_eq!(commits[1].first_line, "feat: to sleep, perchance to dream");
+
+    Ok(())
+}
+
+#[test]
+fn includes_history_between_existing_releases() -> Result<()> {
+    let test_repo = TestRepo::from_log(
+        "
+        (tag: v3.0.0) To be, or not to be, that is the question
+        (tag: v2.0.0) All the world's a stage
+        (tag: v1.0.0) What's in a name? That which we call a rose
+    ",
+    )?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    let commits = git_repo.history(Some("v3.0.0".to_string()), None)?;
+
+    assert_eq!(commits.len(), 1);
+    assert_eq!(
+        commits[0].first_line,
+        "To be, or not to be, that is the question"
+    );
+    Ok(())
+}
+
+#[test]
+fn generates_history_for_existing_release_tag() -> Result<()> {
+    let test_repo = TestRepo::from_log(
+        "
+        (tag: v0.3.0) feat: the fault, dear Brutus, is not in our stars
+        fix: but in ourselves, that we are underlings
+        (tag: v0.2.0) feat: beware the ides of March
+        fix: et tu, Brute?
+        (tag: v0.1.0) feat: friends, Romans, countrymen
+        (tag: release-candidate) feat: lend me your ears
+    ",
+    )?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    let tag = git_repo.release_tag("refs/tags/v0.2.0")?;
+    assert_eq!(tag, "v0.2.0");
+
+    let commits = git_repo.history(Some(tag), None)?;
+    assert_eq!(commits.len(), 2);
+    assert_eq!(commits[0].first_line, "feat: beware the ides of March");
+    assert_eq!(commits[1].first_line, "fix: et tu, Brute?");
+
+    let err = git_repo.release_tag("v9.9.9").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "tag v9.9.9 does not exist; run 'git fetch --tags' if it was created remotely"
+    );
+    let err = git_repo.release_tag("release-candidate").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "tag release-candidate is not a semantic version"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn includes_history_from_head_until_first_release() -> Result<()> {
+    let test_repo = TestRepo::from_log(
+        "
+        The course of true love never did run smooth
+        Brevity is the soul of wit
+        (tag: 1.0.0) Cowards die many times before their deaths
+    ",
+    )?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    let commits = git_repo.history(None, None)?;
+
+    assert_eq!(commits.len(), 2);
+    assert_eq!(
+        commits[0].first_line,
+        "The course of true love never did run smooth"
+    );
+    assert_eq!(commits[1].first_line, "Brevity is the soul of wit");
+    Ok(())
+}
+
+#[test]
+fn includes_history_from_commit_until_latest_release() -> Result<()> {
+    let test_repo = TestRepo::from_log(
+        "
+        Some are born great, some achieve greatness
+        And some have greatness thrust upon them
+        (tag: v2.0.0) The lady doth protest too much, methinks
+        Though this be madness, yet there is method in't
+        (tag: v1.0.0) A horse! A horse! My kingdom for a horse!
+    ",
+    )?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    let c2_hash = test_repo.commits[1].to_string();
+    let commits = git_repo.history(Some(c2_hash), None)?;
+
+    assert_eq!(commits.len(), 1);
+    assert_eq!(
+        commits[0].first_line,
+        "Though this be madness, yet there is method in't"
+    );
+    Ok(())
+}
+
+#[test]
+fn auto_detection_ignores_non_semver_tags() -> Result<()> {
+    let test_repo = TestRepo::from_log(
+        "
+        The quality of mercy is not strained
+        It droppeth as the gentle rain from heaven
+        (tag: random-tag) It is twice blessed
+        (tag: v1.0.0) Upon the place beneath
+        Shall I compare thee to a summer's day?
+    ",
+    )?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    let commits = git_repo.history(None, None)?;
+
+    assert_eq!(commits.len(), 3);
+    assert_eq!(
+        commits[0].first_line,
+        "The quality of mercy is not strained"
+    );
+    assert_eq!(
+        commits[1].first_line,
+        "It droppeth as the gentle rain from heaven"
+    );
+    assert_eq!(commits[2].first_line, "It is twice blessed");
+
+    Ok(())
+}
+
+#[test]
+fn auto_detection_supports_semver_tags_with_build_metadata() -> Result<()> {
+    let test_repo = TestRepo::from_log(
+        "
+        Now is the winter of our discontent
+        (tag: v1.1.0+build.2) Made glorious summer by this sun of York
+        (tag: v1.0.0+build.1) And all the clouds that loured upon our house
+        In the deep bosom of the ocean buried
+    ",
+    )?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    let commits = git_repo.history(None, None)?;
+
+    assert_eq!(commits.len(), 1);
+    assert_eq!(commits[0].first_line, "Now is the winter of our discontent");
+
+    let commits = git_repo.history(Some("v1.1.0+build.2".to_string()), None)?;
+    assert_eq!(commits.len(), 1);
+    assert_eq!(
+        commits[0].first_line,
+        "Made glorious summer by this sun of York"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn parses_tag_names_containing_parentheses() -> Result<()> {
+    let test_repo = TestRepo::from_log(
+        "
+        Cry havoc and let slip the dogs of war
+        (tag: v1.0.0, release(final)) Et tu, Brute?
+    ",
+    )?;
+
+    assert!(
+        test_repo
+            .repo
+            .find_reference("refs/tags/release(final)")
+            .is_ok()
+    );
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    let commits = git_repo.history(None, None)?;
+
+    assert_eq!(commits.len(), 1);
+    assert_eq!(
+        commits[0].first_line,
+        "Cry havoc and let slip the dogs of war"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn auto_detection_supports_v_prefixed_semver_tags() -> Result<()> {
+    let test_repo = TestRepo::from_log(
+        "
+        (tag: v2.0.0) When sorrows come, they come not single spies, but in battalions
+        (tag: v1.5.0) The rest is silence
+        (tag: v1.0.0) We are such stuff as dreams are made on
+    ",
+    )?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    let commits = git_repo.history(Some("v2.0.0".to_string()), None)?;
+
+    assert_eq!(commits.len(), 1);
+    assert_eq!(
+        commits[0].first_line,
+        "When sorrows come, they come not single spies, but in battalions"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn auto_detection_supports_path_prefixed_semver_tags() -> Result<()> {
+    let test_repo = TestRepo::from_log(
+        "
+        (tag: search/v0.3.0) Now is the winter of our discontent
+        (tag: component/sub/v0.2.0) What is past is prologue
+        (tag: search/0.2.0) Be not afraid of greatness
+    ",
+    )?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+
+    let commits = git_repo.history(Some("component/sub/v0.2.0".to_string()), None)?;
+    assert_eq!(commits.len(), 1);
+    assert_eq!(commits[0].first_line, "What is past is prologue");
+
+    Ok(())
+}
+
+#[test]
+fn auto_detection_only_considers_tags_at_path_within_repository() -> Result<()> {
+    let mut test_repo = TestRepo::new()?;
+
+    test_repo.commit("The fault, dear Brutus, is not in our stars")?;
+    test_repo.commit_in_path("search", "But in ourselves, that we are underlings")?;
+    let tag1_oid = test_repo.commit("Uneasy lies the head that wears a crown")?;
+    test_repo.commit_in_path("search", "Friends, Romans, countrymen, lend me your ears")?;
+    let tag2_oid =
+        test_repo.commit_in_path("search", "I come to bury Caesar, not to praise him")?;
+    test_repo.commit("The evil that men do lives after them")?;
+
+    test_repo.create_tag("v1.0.0", tag1_oid)?;
+    test_repo.create_tag("v2.0.0", tag2_oid)?;
+
+    let search_dir = test_repo.path().join("search");
+    let git_repo = GitRepo::open(&search_dir)?;
+
+    let commits = git_repo.history(Some("v2.0.0".to_string()), None)?;
+    assert_eq!(commits.len(), 2);
+    assert_eq!(
+        commits[0].first_line,
+        "I come to bury Caesar, not to praise him"
+    );
+    assert_eq!(
+        commits[1].first_line,
+        "Friends, Romans, countrymen, lend me your ears"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn only_includes_history_at_path_within_repository() -> Result<()> {
+    let mut test_repo = TestRepo::new()?;
+
+    test_repo.commit("The readiness is all")?;
+    test_repo.commit_in_path("src", "There is nothing either good or bad")?;
+    test_repo.commit_in_path("src/components", "But thinking makes it so")?;
+    test_repo.commit_in_path("src/components", "To be or not to be")?;
+    test_repo.commit_in_path("src/utils", "That is the question")?;
+
+    let components_dir = test_repo.path().join("src/components");
+    let git_repo = GitRepo::open(&components_dir)?;
+    assert_eq!(git_repo.path_in_repo(), Some(Path::new("src/components")));
+
+    let commits = git_repo.history(None, None)?;
+    assert_eq!(commits.len(), 2);
+    assert_eq!(commits[0].first_line, "To be or not to be");
+    assert_eq!(commits[1].first_line, "But thinking makes it so");
+
+    Ok(())
+}
+
+#[test]
+fn filters_path_relative_to_repository_rather_than_cwd() -> Result<()> {
+    let mut test_repo = TestRepo::new()?;
+
+    test_repo.commit("The readiness is all")?;
+    test_repo.commit_in_path("src", "There is nothing either good or bad")?;
+    test_repo.commit_in_path("docs", "But thinking makes it so")?;
+
+    // The process cwd is this crate, which also has a src directory
+    assert_ne!(std::env::current_dir()?, test_repo.path());
+
+    let git_repo = GitRepo::open(test_repo.path())?.with_path_filter("./src")?;
+    assert_eq!(git_repo.path_in_repo(), Some(Path::new("src")));
+
+    let commits = git_repo.history(None, None)?;
+    assert_eq!(commits.len(), 1);
+    assert_eq!(commits[0].first_line, "There is nothing either good or bad");
+
+    Ok(())
+}
+
+#[test]
+fn normalizes_absolute_and_parent_path_filters() -> Result<()> {
+    let mut test_repo = TestRepo::new()?;
+    test_repo.commit_in_path("src/components", "But thinking makes it so")?;
+
+    let git_repo =
+        GitRepo::open(test_repo.path())?.with_path_filter(test_repo.path().join("src"))?;
+    assert_eq!(git_repo.path_in_repo(), Some(Path::new("src")));
+
+    let git_repo =
+        GitRepo::open(test_repo.path().join("src/components"))?.with_path_filter("../../docs")?;
+    assert_eq!(git_repo.path_in_repo(), Some(Path::new("docs")));
+
+    let err = GitRepo::open(test_repo.path())?
+        .with_path_filter("src/../..")
+        .map(|_| ())
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<GitRepoError>(),
+        Some(GitRepoError::PathOutsideRepository(_))
+    ));
+
+    let outside = TempDir::new()?;
+    let err = GitRepo::open(test_repo.path())?
+        .with_path_filter(outside.path())
+        .map(|_| ())
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<GitRepoError>(),
+        Some(GitRepoError::PathOutsideRepository(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn drops_commits_only_touching_excluded_paths() -> Result<()> {
+    let mut test_repo = TestRepo::new()?;
+
+    test_repo.commit_in_path("docs", "The play's the thing")?;
+    test_repo.commit_in_path("src", "There is nothing either good or bad")?;
+    test_repo.commit_in_path("docs", "But thinking makes it so")?;
+    test_repo.commit_files(
+        &[
+            ("src/globe.rs".to_string(), b"to be".to_vec()),
+            ("docs/globe.md".to_string(), b"or not to be".to_vec()),
+        ],
+        "All the world's a stage",
+    )?;
+
+    let git_repo =
+        GitRepo::open(test_repo.path())?.with_excluded_paths(vec![PathBuf::from("docs/")]);
+
+    let commits = git_repo.history(None, None)?;
+    let subjects: Vec<&str> = commits.iter().map(|c| c.first_line.as_str()).collect();
+    assert_eq!(
+        subjects,
+        vec![
+            "All the world's a stage",
+            "There is nothing either good or bad"
+        ]
+    );
+    assert_eq!(git_repo.dropped_commits().outside_path, 2);
+
+    Ok(())
+}
+
+#[test]
+fn describes_github_merge_commits_by_pull_request() -> Result<()> {
+    let mut test_repo = TestRepo::new()?;
+
+    test_repo.commit("feat: to be or not to be")?;
+    test_repo.commit("feat: all the world's a stage (#42)")?;
+    test_repo.commit("Merge pull request #7 from shakespeare/globe\n\nThe play's the thing")?;
+
+    let platform = Platform::GitHub {
+        url: "https://github.com/owner/repo".to_string(),
+        api_url: "https://api.github.com".to_string(),
+        owner: "owner".to_string(),
+        repo: "repo".to_string(),
+        token: None,
+    };
+    let git_repo = GitRepo::open(test_repo.path())?;
+    let commits = git_repo.history(None, None)?;
+
+    assert_eq!(commits[0].pull_request_number(), Some(7));
+    assert_eq!(
+        commits[0].merge_description(&platform),
+        "PR #7: The play's the thing"
+    );
+    assert_eq!(commits[1].pull_request_number(), Some(42));
+    assert_eq!(
+        commits[1].merge_description(&platform),
+        "PR #42: feat: all the world's a stage"
+    );
+    assert_eq!(commits[2].pull_request_number(), None);
+    assert_eq!(
+        commits[2].merge_description(&platform),
+        "feat: to be or not to be"
+    );
+    assert_eq!(
+        commits[0].merge_description(&Platform::Unknown),
+        "Merge pull request #7 from shakespeare/globe"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn parses_every_pull_request_from_squash_subject() -> Result<()> {
+    let mut test_repo = TestRepo::new()?;
+
+    test_repo.commit("feat: to be or not to be")?;
+    test_repo.commit("feat: all the world's a stage (#1, #2,#3)")?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    let commits = git_repo.history(None, None)?;
+
+    assert_eq!(commits[0].authored_pull_requests(), vec![1, 2, 3]);
+    assert_eq!(commits[0].pull_request_number(), Some(1));
+    assert!(commits[1].authored_pull_requests().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn parses_github_revert_of_pull_request() -> Result<()> {
+    let mut test_repo = TestRepo::new()?;
+
+    test_repo.commit("feat: to be or not to be")?;
+    test_repo
+        .commit("Revert \"feat: all the world's a stage\" (#15)\n\nReverts shakespeare/globe#12")?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    let commits = git_repo.history(None, None)?;
+
+    assert!(commits[0].is_revert());
+    assert_eq!(commits[0].pull_request_number(), Some(15));
+    assert_eq!(commits[0].reverted_pull_request, Some(12));
+    assert!(!commits[1].is_revert());
+    assert_eq!(commits[1].reverted_pull_request, None);
+
+    let categorized = CommitAnalyzer::analyze(&commits);
+    assert_eq!(categorized.by_category[&CommitCategory::Revert].len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn reports_commits_lacking_sign_off() -> Result<()> {
+    let mut test_repo = TestRepo::new()?;
+
+    test_repo.commit(
+        "feat: all the world's a stage\n\nSigned-off-by: William Shakespeare <will@globe-theatre.com>",
+    )?;
+    test_repo.commit("fix: to be or not to be")?;
+    test_repo.commit(
+        "feat: brevity is the soul of wit\n\nCo-authored-by: Christopher Marlowe <kit@rose-theatre.com>",
+    )?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    let unsigned: Vec<String> = git_repo
+        .history(None, None)?
+        .into_iter()
+        .filter(|c| !c.is_signed_off())
+        .map(|c| c.first_line)
+        .collect();
+
+    assert_eq!(
+        unsigned,
+        vec![
+            "feat: brevity is the soul of wit",
+            "fix: to be or not to be"
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn captures_committer_distinct_from_author() -> Result<()> {
+    let mut test_repo = TestRepo::new()?;
+
+    test_repo.commit_applied_by(
+        "Christopher Marlowe",
+        "kit@rose-theatre.com",
+        "feat: all the world's a stage",
+    )?;
+
+    let commits = GitRepo::open(test_repo.path())?.history(None, None)?;
+    assert_eq!(commits[0].author, TEST_USER_NAME);
+    assert_eq!(commits[0].email, TEST_USER_EMAIL);
+    assert_eq!(commits[0].committer, "Christopher Marlowe");
+    assert_eq!(commits[0].committer_email, "kit@rose-theatre.com");
+
+    let commits = GitRepo::open(test_repo.path())?
+        .with_committer_attribution(true)
+        .history(None, None)?;
+    assert_eq!(commits[0].author, "Christopher Marlowe");
+    assert_eq!(commits[0].email, "kit@rose-theatre.com");
+
     Ok(())
 }
 
 #[test]
-fn includes_history_from_head_until_first_release() -> Result<()> {
-    let test_repo = TestRepo::from_log(
-        "
-        The course of true love never did run smooth
-        Brevity is the soul of wit
-        (tag: 1.0.0) Cowards die many times before their deaths
-    ",
-    )?;
+fn excludes_commits_reachable_from_multiple_refs() -> Result<()> {
+    let mut test_repo = TestRepo::new()?;
 
-    let git_repo = GitRepo::open(test_repo.path())?;
+    let root = test_repo.commit("feat: the readiness is all")?;
+    let release_1 = test_repo.branch_commit("fix: denmark's a prison", root)?;
+    let release_2 = test_repo.branch_commit("fix: something is rotten", root)?;
+    test_repo.commit("feat: to be or not to be")?;
+    test_repo.merge("Merge branch 'release/1'", release_1)?;
+    test_repo.merge("Merge branch 'release/2'", release_2)?;
+
+    for (name, oid) in [("release/1", release_1), ("release/2", release_2)] {
+        let commit = test_repo.repo.find_commit(oid)?;
+        test_repo.repo.branch(name, &commit, false)?;
+    }
+
+    let git_repo = GitRepo::open(test_repo.path())?
+        .with_hidden_refs(vec!["release/1".to_string(), "release/2".to_string()]);
     let commits = git_repo.history(None, None)?;
 
-    assert_eq!(commits.len(), 2);
+    let subjects: Vec<&str> = commits.iter().map(|c| c.first_line.as_str()).collect();
     assert_eq!(
-        commits[0].first_line,
-        "The course of true love never did run smooth"
+        subjects,
+        vec![
+            "Merge branch 'release/2'",
+            "Merge branch 'release/1'",
+            "feat: to be or not to be",
+        ]
     );
-    assert_eq!(commits[1].first_line, "Brevity is the soul of wit");
+
     Ok(())
 }
 
 #[test]
-fn includes_history_from_commit_until_latest_release() -> Result<()> {
-    let test_repo = TestRepo::from_log(
-        "
-        Some are born great, some achieve greatness
-        And some have greatness thrust upon them
-        (tag: v2.0.0) The lady doth protest too much, methinks
-        Though this be madness, yet there is method in't
-        (tag: v1.0.0) A horse! A horse! My kingdom for a horse!
-    ",
+fn counts_dropped_commits_by_reason() -> Result<()> {
+    let mut test_repo = TestRepo::new()?;
+
+    test_repo.commit_in_path("src", "feat: all the world's a stage")?;
+    test_repo.commit_in_path("src", "feat: all the world's a stage")?;
+    test_repo.commit_in_path("docs", "docs: words, words, words")?;
+    test_repo.commit_internal(
+        Some("src"),
+        "fix: was this the face that launched a thousand ships",
+        Some(("Christopher Marlowe", "kit@rose-theatre.com")),
+        None,
     )?;
+    test_repo.commit_in_path("src", "fix: to be or not to be")?;
 
-    let git_repo = GitRepo::open(test_repo.path())?;
-    let c2_hash = test_repo.commits[1].to_string();
-    let commits = git_repo.history(Some(c2_hash), None)?;
+    let git_repo = GitRepo::open(test_repo.path())?
+        .with_path_filter("src")?
+        .with_excluded_authors(vec!["kit@rose-theatre.com".to_string()])
+        .with_dedupe_subjects(true);
+    let commits = git_repo.history(None, None)?;
 
-    assert_eq!(commits.len(), 1);
+    assert_eq!(commits.len(), 2);
     assert_eq!(
-        commits[0].first_line,
-        "Though this be madness, yet there is method in't"
+        git_repo.dropped_commits(),
+        DroppedCommits {
+            outside_path: 1,
+            excluded_author: 1,
+            duplicate_subject: 1,
+            skipped: 0,
+        }
     );
+
     Ok(())
 }
 
 #[test]
-fn auto_detection_ignores_non_semver_tags() -> Result<()> {
+fn splits_history_into_sections_per_tag() -> Result<()> {
     let test_repo = TestRepo::from_log(
         "
-        The quality of mercy is not strained
-        It droppeth as the gentle rain from heaven
-        (tag: random-tag) It is twice blessed
-        (tag: v1.0.0) Upon the place beneath
-        Shall I compare thee to a summer's day?
+        (tag: 1.2.0) fix: the lady doth protest too much
+        (tag: 1.1.0) feat: brevity is the soul of wit
+        feat: to be or not to be
+        (tag: 1.0.0) feat: all the world's a stage
+        chore: the readiness is all
     ",
     )?;
 
     let git_repo = GitRepo::open(test_repo.path())?;
-    let commits = git_repo.history(None, None)?;
+    let segments = git_repo.history_by_tag(
+        Some("1.2.0".to_string()),
+        Some(test_repo.commits[0].to_string()),
+    )?;
 
-    assert_eq!(commits.len(), 3);
-    assert_eq!(
-        commits[0].first_line,
-        "The quality of mercy is not strained"
-    );
+    let sections: Vec<(&str, Vec<&str>)> = segments
+        .iter()
+        .map(|(tag, commits)| {
+            (
+                tag.as_str(),
+                commits.iter().map(|c| c.first_line.as_str()).collect(),
+            )
+        })
+        .collect();
     assert_eq!(
-        commits[1].first_line,
-        "It droppeth as the gentle rain from heaven"
+        sections,
+        vec![
+            ("1.2.0", vec!["fix: the lady doth protest too much"]),
+            (
+                "1.1.0",
+                vec![
+                    "feat: brevity is the soul of wit",
+                    "feat: to be or not to be"
+                ]
+            ),
+            ("1.0.0", vec!["feat: all the world's a stage"]),
+        ]
     );
-    assert_eq!(commits[2].first_line, "It is twice blessed");
 
     Ok(())
 }
 
 #[test]
-fn auto_detection_supports_v_prefixed_semver_tags() -> Result<()> {
-    let test_repo = TestRepo::from_log(
-        "
-        (tag: v2.0.0) When sorrows come, they come not single spies, but in battalions
-        (tag: v1.5.0) The rest is silence
-        (tag: v1.0.0) We are such stuff as dreams are made on
-    ",
-    )?;
+fn excludes_commits_marked_to_skip_the_release_note() -> Result<()> {
+    let mut test_repo = TestRepo::new()?;
+
+    test_repo.commit("feat: all the world's a stage")?;
+    test_repo.commit("chore: bump copyright year\n\nRelease-Note: skip")?;
+    test_repo.commit("docs: fix typo [Skip Changelog]")?;
+    test_repo.commit("fix: to be or not to be\n\nRelease-Note: include")?;
 
     let git_repo = GitRepo::open(test_repo.path())?;
-    let commits = git_repo.history(Some("v2.0.0".to_string()), None)?;
+    let commits = git_repo.history(None, None)?;
 
-    assert_eq!(commits.len(), 1);
+    let subjects: Vec<&str> = commits.iter().map(|c| c.first_line.as_str()).collect();
     assert_eq!(
-        commits[0].first_line,
-        "When sorrows come, they come not single spies, but in battalions"
+        subjects,
+        vec!["fix: to be or not to be", "feat: all the world's a stage"]
     );
+    assert_eq!(git_repo.dropped_commits().skipped, 2);
 
     Ok(())
 }
 
 #[test]
-fn auto_detection_supports_path_prefixed_semver_tags() -> Result<()> {
-    let test_repo = TestRepo::from_log(
-        "
-        (tag: search/v0.3.0) Now is the winter of our discontent
-        (tag: component/sub/v0.2.0) What is past is prologue
-        (tag: search/0.2.0) Be not afraid of greatness
-    ",
+fn overrides_rendered_line_with_release_note_trailer() -> Result<()> {
+    let mut test_repo = TestRepo::new()?;
+
+    test_repo.commit("feat: all the world's a stage")?;
+    test_repo.commit(
+        "fix: handle nil pointer in parser\n\nAdds a guard.\n\nRelease-Note: Parsing no longer crashes on empty acts",
     )?;
 
     let git_repo = GitRepo::open(test_repo.path())?;
+    let commits = git_repo.history(None, None)?;
 
-    let commits = git_repo.history(Some("component/sub/v0.2.0".to_string()), None)?;
-    assert_eq!(commits.len(), 1);
-    assert_eq!(commits[0].first_line, "What is past is prologue");
+    assert_eq!(
+        commits[0].release_note.as_deref(),
+        Some("Parsing no longer crashes on empty acts")
+    );
+    assert_eq!(commits[1].release_note, None);
+
+    let categorized = CommitAnalyzer::analyze(&commits);
+    let result = release_note::markdown::render_history(
+        &categorized,
+        &Platform::Unknown,
+        "v1.0.0",
+        BASE_TIMESTAMP,
+        release_note::template::DEFAULT_TEMPLATE,
+    )?;
+    assert!(result.contains("** Parsing no longer crashes on empty acts\n"));
+    assert!(!result.contains("handle nil pointer"));
+    assert!(!result.contains("Adds a guard."));
 
     Ok(())
 }
 
 #[test]
-fn auto_detection_only_considers_tags_at_path_within_repository() -> Result<()> {
+fn filters_path_for_commits_touching_many_binary_files() -> Result<()> {
     let mut test_repo = TestRepo::new()?;
 
-    test_repo.commit("The fault, dear Brutus, is not in our stars")?;
-    test_repo.commit_in_path("search", "But in ourselves, that we are underlings")?;
-    let tag1_oid = test_repo.commit("Uneasy lies the head that wears a crown")?;
-    test_repo.commit_in_path("search", "Friends, Romans, countrymen, lend me your ears")?;
-    let tag2_oid =
-        test_repo.commit_in_path("search", "I come to bury Caesar, not to praise him")?;
-    test_repo.commit("The evil that men do lives after them")?;
-
-    test_repo.create_tag("v1.0.0", tag1_oid)?;
-    test_repo.create_tag("v2.0.0", tag2_oid)?;
-
-    let search_dir = test_repo.path().join("search");
-    let git_repo = GitRepo::open(&search_dir)?;
+    test_repo.commit("feat: the readiness is all")?;
+    let assets: Vec<(String, Vec<u8>)> = (0..500)
+        .map(|i| {
+            (
+                format!("assets/image{}.bin", i),
+                vec![0u8, 159, 146, 150, i as u8],
+            )
+        })
+        .collect();
+    test_repo.commit_files(&assets, "chore: add all the world's images")?;
+
+    let mut mixed = assets[..250]
+        .iter()
+        .map(|(path, _)| (path.clone(), vec![0u8, 1, 2, 3]))
+        .collect::<Vec<_>>();
+    mixed.push(("src/stage.rs".to_string(), b"fn main() {}".to_vec()));
+    test_repo.commit_files(&mixed, "feat: exit, pursued by a bear")?;
+
+    let commits = GitRepo::open(test_repo.path())?
+        .with_path_filter("src")?
+        .history(None, None)?;
+    assert_eq!(commits.len(), 1);
+    assert_eq!(commits[0].first_line, "feat: exit, pursued by a bear");
 
-    let commits = git_repo.history(Some("v2.0.0".to_string()), None)?;
+    let commits = GitRepo::open(test_repo.path())?
+        .with_path_filter("assets")?
+        .history(None, None)?;
     assert_eq!(commits.len(), 2);
-    assert_eq!(
-        commits[0].first_line,
-        "I come to bury Caesar, not to praise him"
-    );
-    assert_eq!(
-        commits[1].first_line,
-        "Friends, Romans, countrymen, lend me your ears"
-    );
 
     Ok(())
 }
 
 #[test]
-fn only_includes_history_at_path_within_repository() -> Result<()> {
+fn filters_history_by_single_file() -> Result<()> {
     let mut test_repo = TestRepo::new()?;
 
-    test_repo.commit("The readiness is all")?;
-    test_repo.commit_in_path("src", "There is nothing either good or bad")?;
-    test_repo.commit_in_path("src/components", "But thinking makes it so")?;
-    test_repo.commit_in_path("src/components", "To be or not to be")?;
-    test_repo.commit_in_path("src/utils", "That is the question")?;
-
-    let components_dir = test_repo.path().join("src/components");
-    let git_repo = GitRepo::open(&components_dir)?;
+    let file = |path: &str, content: &str| (path.to_string(), content.as_bytes().to_vec());
+    test_repo.commit_files(&[file("README.md", "Act I")], "docs: the play's the thing")?;
+    test_repo.commit_files(
+        &[file("src/stage.rs", "fn main() {}")],
+        "feat: exit, pursued by a bear",
+    )?;
+    test_repo.commit_files(
+        &[file("README.md.orig", "Act I")],
+        "chore: what's done is done",
+    )?;
+    test_repo.commit_files(
+        &[file("README.md", "Act II")],
+        "docs: to sleep, perchance to dream",
+    )?;
 
-    let commits = git_repo.history(None, None)?;
+    let commits = GitRepo::open(test_repo.path())?
+        .with_path_filter("README.md")?
+        .history(None, None)?;
     assert_eq!(commits.len(), 2);
-    assert_eq!(commits[0].first_line, "To be or not to be");
-    assert_eq!(commits[1].first_line, "But thinking makes it so");
+    assert_eq!(commits[0].first_line, "docs: to sleep, perchance to dream");
+    assert_eq!(commits[1].first_line, "docs: the play's the thing");
 
     Ok(())
 }
@@ -495,6 +2038,24 @@ Co-authored-by: Christopher Marlowe <kit@rose-theatre.com>
     Ok(())
 }
 
+#[test]
+fn accesses_name_and_email_of_trailers() {
+    let signed_off = GitTrailer::from_key_value(
+        "Signed-off-by".to_string(),
+        "William Shakespeare <will@globe-theatre.com>".to_string(),
+    );
+    assert_eq!(signed_off.name(), Some("William Shakespeare"));
+    assert_eq!(signed_off.email(), Some("will@globe-theatre.com"));
+
+    let reviewed = GitTrailer::from_key_value("Reviewed-by".to_string(), "Ben Jonson".to_string());
+    assert_eq!(reviewed.name(), Some("Ben Jonson"));
+    assert_eq!(reviewed.email(), None);
+
+    let other = GitTrailer::from_key_value("Refs".to_string(), "#42".to_string());
+    assert_eq!(other.name(), None);
+    assert_eq!(other.email(), None);
+}
+
 #[test]
 fn preserves_blank_lines_in_body() -> Result<()> {
     let mut test_repo = TestRepo::new()?;
@@ -596,3 +2157,279 @@ performance, mirroring reality back to the audience."#
 
     Ok(())
 }
+
+const SPACED_BODY_MESSAGE: &str = "feat: there are more things in heaven and earth, Horatio
+
+Than are dreamt of in your philosophy.
+
+
+
+But come; here, as before, never, so help you mercy.
+
+How strange or odd soe'er I bear myself.";
+
+fn history_with_blank_line_policy(policy: BlankLinePolicy) -> Result<Option<String>> {
+    let mut test_repo = TestRepo::new()?;
+    test_repo.commit(SPACED_BODY_MESSAGE)?;
+
+    let git_repo = GitRepo::open(test_repo.path())?.with_blank_line_policy(policy);
+    let commits = git_repo.history(None, None)?;
+    assert_eq!(commits.len(), 1);
+
+    Ok(commits[0].body.clone())
+}
+
+#[test]
+fn collapses_blank_line_runs_to_a_single_blank_line_by_default() -> Result<()> {
+    assert_eq!(
+        history_with_blank_line_policy(BlankLinePolicy::default())?.as_deref(),
+        Some(
+            "Than are dreamt of in your philosophy.\n\nBut come; here, as before, never, so help you mercy.\n\nHow strange or odd soe'er I bear myself."
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn removes_blank_and_whitespace_lines_when_collapsing_to_one() -> Result<()> {
+    let mut test_repo = TestRepo::new()?;
+    test_repo.commit(
+        "feat: what's in a name?\n\nThat which we call a rose\n  \n\t\n\nBy any other word would smell as sweet.",
+    )?;
+
+    let git_repo =
+        GitRepo::open(test_repo.path())?.with_blank_line_policy(BlankLinePolicy::CollapseTo1);
+    let commits = git_repo.history(None, None)?;
+    assert_eq!(
+        commits[0].body.as_deref(),
+        Some("That which we call a rose\nBy any other word would smell as sweet.")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn removes_paragraph_breaks_when_collapsing_to_one() -> Result<()> {
+    assert_eq!(
+        history_with_blank_line_policy(BlankLinePolicy::CollapseTo1)?.as_deref(),
+        Some(
+            "Than are dreamt of in your philosophy.\nBut come; here, as before, never, so help you mercy.\nHow strange or odd soe'er I bear myself."
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn preserves_blank_line_runs_when_requested() -> Result<()> {
+    assert_eq!(
+        history_with_blank_line_policy(BlankLinePolicy::Preserve)?.as_deref(),
+        Some(
+            "Than are dreamt of in your philosophy.\n\n\n\nBut come; here, as before, never, so help you mercy.\n\nHow strange or odd soe'er I bear myself."
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn ignores_stale_packed_tag_shadowed_by_annotated_tag() -> Result<()> {
+    let test_repo = TestRepo::from_log(
+        "
+        feat: to be or not to be
+        feat: all the world's a stage
+    ",
+    )?;
+    test_repo.create_tag("v1.0.0", test_repo.commits[1])?;
+    std::fs::write(
+        test_repo.path().join(".git/packed-refs"),
+        format!(
+            "# pack-refs with: peeled fully-peeled sorted \n{} refs/tags/v1.0.0\n",
+            test_repo.commits[0]
+        ),
+    )?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    let segments = git_repo.history_by_tag(Some("v1.0.0".to_string()), None)?;
+
+    let sections: Vec<(&str, usize)> = segments
+        .iter()
+        .map(|(tag, commits)| (tag.as_str(), commits.len()))
+        .collect();
+    assert_eq!(sections, vec![("v1.0.0", 2)]);
+
+    Ok(())
+}
+
+#[test]
+fn labels_history_with_typed_tag_when_commit_has_two_tags() -> Result<()> {
+    let test_repo = TestRepo::from_log(
+        "
+        (tag: 1.1.0) feat: brevity is the soul of wit
+        feat: to be or not to be
+        (tag: 1.0.0) feat: all the world's a stage
+    ",
+    )?;
+    test_repo.create_tag("1.1.0-rc.1", test_repo.commits[2])?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    for typed in ["1.1.0", "1.1.0-rc.1"] {
+        let segments = git_repo.history_by_tag(Some(typed.to_string()), None)?;
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].0, typed);
+        assert_eq!(segments[0].1.len(), 2);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn labels_intermediate_segments_with_highest_version_of_shared_tags() -> Result<()> {
+    let test_repo = TestRepo::from_log(
+        "
+        fix: though this be madness, yet there is method in it
+        (tag: 1.1.0-rc.1) feat: brevity is the soul of wit
+        feat: to be or not to be
+        (tag: 1.0.0) feat: all the world's a stage
+    ",
+    )?;
+    test_repo.create_tag("1.1.0", test_repo.commits[2])?;
+    test_repo.create_tag("2.0.0", test_repo.commits[2])?;
+
+    let git_repo = GitRepo::open(test_repo.path())?;
+    let segments = git_repo.history_by_tag(None, Some("1.0.0".to_string()))?;
+    let names: Vec<&str> = segments.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["HEAD", "2.0.0"]);
+
+    Ok(())
+}
+
+#[test]
+fn merges_categorized_commits_from_two_repositories() -> Result<()> {
+    let stage = TestRepo::from_log(
+        "
+        fix: the lady doth protest too much
+        feat: all the world's a stage
+    ",
+    )?;
+    let props = TestRepo::from_log(
+        "
+        feat: a horse! a horse! my kingdom for a horse!
+    ",
+    )?;
+
+    let merged = CategorizedCommits::merge(
+        vec![
+            (
+                "stage".to_string(),
+                CommitAnalyzer::analyze_from_repo(&GitRepo::open(stage.path())?, None, None, None)?,
+            ),
+            (
+                "props".to_string(),
+                CommitAnalyzer::analyze_from_repo(&GitRepo::open(props.path())?, None, None, None)?,
+            ),
+        ],
+        CommitOrder::NewestFirst,
+        &AnalyzerOptions::default(),
+    );
+
+    let features: Vec<(Option<&str>, &str)> = merged.by_category[&CommitCategory::Feature]
+        .iter()
+        .map(|c| (c.repository.as_deref(), c.first_line.as_str()))
+        .collect();
+    assert_eq!(
+        features,
+        vec![
+            (Some("stage"), "feat: all the world's a stage"),
+            (
+                Some("props"),
+                "feat: a horse! a horse! my kingdom for a horse!"
+            ),
+        ]
+    );
+    assert_eq!(merged.by_category[&CommitCategory::Fix].len(), 1);
+
+    let rendered = markdown::render_history(
+        &merged,
+        &Platform::Unknown,
+        "v1.0.0",
+        BASE_TIMESTAMP,
+        DEFAULT_TEMPLATE,
+    )?;
+    assert!(rendered.contains("**props**: a horse! a horse! my kingdom for a horse!"));
+    assert!(rendered.contains("**stage**: the lady doth protest too much"));
+
+    Ok(())
+}
+
+#[test]
+fn links_merged_commits_to_the_origin_of_their_repository() -> Result<()> {
+    let stage = TestRepo::from_log(
+        "
+        feat: all the world's a stage (#12)
+    ",
+    )?;
+    stage
+        .repo
+        .remote("origin", "https://github.com/globe/stage.git")?;
+    let props = TestRepo::from_log(
+        "
+        feat: a horse! a horse! my kingdom for a horse! (#7)
+    ",
+    )?;
+    props
+        .repo
+        .remote("origin", "git@gitlab.com:globe/props.git")?;
+
+    let stage_repo = GitRepo::open(stage.path())?;
+    let props_repo = GitRepo::open(props.path())?;
+    let stage_platform = Platform::detect_from_remote_url(stage_repo.origin_url().unwrap());
+    let props_platform = Platform::detect_from_remote_url(props_repo.origin_url().unwrap());
+    let merged = CategorizedCommits::merge(
+        vec![
+            (
+                "stage".to_string(),
+                CommitAnalyzer::analyze_from_repo(&stage_repo, None, None, None)?,
+            ),
+            (
+                "props".to_string(),
+                CommitAnalyzer::analyze_from_repo(&props_repo, None, None, None)?,
+            ),
+        ],
+        CommitOrder::NewestFirst,
+        &AnalyzerOptions::default(),
+    );
+
+    let options = markdown::RenderOptions {
+        repository_platforms: [
+            ("stage".to_string(), stage_platform.clone()),
+            ("props".to_string(), props_platform),
+        ]
+        .into(),
+        ..Default::default()
+    };
+    let rendered = markdown::render_history_with_options(
+        &merged,
+        &stage_platform,
+        "v1.0.0",
+        BASE_TIMESTAMP,
+        DEFAULT_TEMPLATE,
+        &options,
+    )?;
+
+    let stage_sha = stage.commits[0].to_string();
+    let props_sha = props.commits[0].to_string();
+    assert!(rendered.contains(&format!(
+        "(https://github.com/globe/stage/commit/{})",
+        stage_sha
+    )));
+    assert!(rendered.contains("([#12](https://github.com/globe/stage/pull/12))"));
+    assert!(rendered.contains(&format!(
+        "(https://gitlab.com/globe/props/-/commit/{})",
+        props_sha
+    )));
+    assert!(rendered.contains("([#7](https://gitlab.com/globe/props/-/merge_requests/7))"));
+
+    Ok(())
+}
@@ -1,8 +1,12 @@
 mod commit;
 
 use commit::CommitBuilder;
-use release_note::analyzer::{CategorizedCommits, CommitCategory, ContributorSummary};
-use release_note::markdown;
+use release_note::analyzer::{
+    AnalyzerOptions, CategorizedCommits, CommitAnalyzer, CommitCategory, ContributorSummary,
+};
+use release_note::git::LinkedIssue;
+use release_note::locale::Locale;
+use release_note::markdown::{self, RenderOptions, StatsStyle};
 use release_note::platform::Platform;
 use release_note::template::DEFAULT_TEMPLATE;
 use std::collections::HashMap;
@@ -84,6 +88,62 @@ fn generates_release_note_from_multiple_categories() {
     insta::assert_snapshot!(result);
 }
 
+#[test]
+fn streams_identical_output_to_writer() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![
+            CommitBuilder::new("feat: all the world's a stage")
+                .with_body("And all the men and women merely players.")
+                .build(),
+            CommitBuilder::new("feat: to be or not to be").build(),
+        ],
+    );
+    by_category.insert(
+        CommitCategory::Fix,
+        vec![CommitBuilder::new("fix: though she be but little, she is fierce").build()],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+
+    for options in [
+        RenderOptions::default(),
+        RenderOptions {
+            front_matter: true,
+            ..Default::default()
+        },
+    ] {
+        let buffered = markdown::render_history_with_options(
+            &categorized,
+            &Platform::Unknown,
+            "HEAD",
+            TEST_RELEASE_DATE,
+            DEFAULT_TEMPLATE,
+            &options,
+        )
+        .unwrap();
+
+        let mut streamed = Vec::new();
+        markdown::render_history_to(
+            &mut streamed,
+            &categorized,
+            &Platform::Unknown,
+            "HEAD",
+            TEST_RELEASE_DATE,
+            DEFAULT_TEMPLATE,
+            &options,
+        )
+        .unwrap();
+
+        assert_eq!(String::from_utf8(streamed).unwrap(), buffered);
+    }
+}
+
 #[test]
 fn includes_chore_deps_commits_in_dependency_table() {
     let mut by_category = HashMap::new();
@@ -141,6 +201,7 @@ fn displays_contributors_with_github_commit_links() {
             is_ai: false,
             first_commit_timestamp: 1748390400,
             last_commit_timestamp: 1748476800,
+            name: None,
         },
         ContributorSummary {
             username: "marlowe".to_string(),
@@ -150,6 +211,7 @@ fn displays_contributors_with_github_commit_links() {
             is_ai: false,
             first_commit_timestamp: 1748390400,
             last_commit_timestamp: 1748390400,
+            name: None,
         },
     ];
 
@@ -196,6 +258,7 @@ fn displays_contributors_without_links_for_gitlab() {
             is_ai: false,
             first_commit_timestamp: 1748390400,
             last_commit_timestamp: 1748476800,
+            name: None,
         },
         ContributorSummary {
             username: "ophelia".to_string(),
@@ -206,6 +269,7 @@ fn displays_contributors_without_links_for_gitlab() {
             is_ai: false,
             first_commit_timestamp: 1748390400,
             last_commit_timestamp: 1748390400,
+            name: None,
         },
     ];
 
@@ -645,6 +709,7 @@ fn displays_multiple_contributors() {
             is_ai: false,
             first_commit_timestamp: 1564567890,
             last_commit_timestamp: 1564567891,
+            name: None,
         },
         ContributorSummary {
             username: "jonson".to_string(),
@@ -654,6 +719,7 @@ fn displays_multiple_contributors() {
             is_ai: false,
             first_commit_timestamp: 1564567890,
             last_commit_timestamp: 1564567890,
+            name: None,
         },
         ContributorSummary {
             username: "marlowe".to_string(),
@@ -663,6 +729,7 @@ fn displays_multiple_contributors() {
             is_ai: false,
             first_commit_timestamp: 1564567890,
             last_commit_timestamp: 1564567890,
+            name: None,
         },
     ];
 
@@ -707,6 +774,7 @@ fn filters_bot_contributors() {
             is_ai: false,
             first_commit_timestamp: 1564567890,
             last_commit_timestamp: 1564567890,
+            name: None,
         },
         ContributorSummary {
             username: "iago[bot]".to_string(),
@@ -716,6 +784,7 @@ fn filters_bot_contributors() {
             is_ai: false,
             first_commit_timestamp: 1564567890,
             last_commit_timestamp: 1564567890,
+            name: None,
         },
     ];
 
@@ -762,6 +831,7 @@ fn ai_contributors_have_no_commit_links() {
             is_ai: false,
             first_commit_timestamp: 1748390400,
             last_commit_timestamp: 1748390400,
+            name: None,
         },
         ContributorSummary {
             username: "claude".to_string(),
@@ -771,6 +841,7 @@ fn ai_contributors_have_no_commit_links() {
             is_ai: true,
             first_commit_timestamp: 1748476800,
             last_commit_timestamp: 1748476800,
+            name: None,
         },
     ];
 
@@ -870,3 +941,1911 @@ fn escapes_table_metacharacters_in_dependency_update_cell() {
 
     insta::assert_snapshot!(result);
 }
+
+#[test]
+fn renders_section_titles_in_configured_language() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Breaking,
+        vec![CommitBuilder::new("feat!: la vida es sueño").build()],
+    );
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![
+            CommitBuilder::new("feat: all the world's a stage").build(),
+            CommitBuilder::new("feat: to be or not to be").build(),
+        ],
+    );
+    by_category.insert(
+        CommitCategory::Fix,
+        vec![CommitBuilder::new("fix: though she be but little, she is fierce").build()],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &Platform::Unknown,
+        "HEAD",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+        &RenderOptions {
+            lang: Locale::Es,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn omits_stats_when_disabled() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![CommitBuilder::new("feat: all the world's a stage").build()],
+    );
+    by_category.insert(
+        CommitCategory::Fix,
+        vec![CommitBuilder::new("fix: though she be but little, she is fierce").build()],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &Platform::Unknown,
+        "HEAD",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+        &RenderOptions {
+            no_stats: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn joins_stats_with_bullet_by_default() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![CommitBuilder::new("feat: all the world's a stage").build()],
+    );
+    by_category.insert(
+        CommitCategory::Fix,
+        vec![CommitBuilder::new("fix: though she be but little, she is fierce").build()],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let result = markdown::render_history(
+        &categorized,
+        &Platform::Unknown,
+        "HEAD",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+    )
+    .unwrap();
+
+    let stats = result.lines().nth(2).unwrap();
+    assert_eq!(
+        stats,
+        "[**`1`**](#new-features) new feature \u{2022} [**`1`**](#bug-fixes) bug fix"
+    );
+    assert!(!result.contains("\u{e2}\u{20ac}\u{a2}"));
+}
+
+#[test]
+fn pluralizes_bug_fixes_in_stats() {
+    let mut rendered = Vec::new();
+
+    for fixes in [
+        vec!["fix: though she be but little, she is fierce"],
+        vec![
+            "fix: though she be but little, she is fierce",
+            "fix: the course of true love never did run smooth",
+        ],
+    ] {
+        let mut by_category = HashMap::new();
+        by_category.insert(
+            CommitCategory::Fix,
+            fixes
+                .into_iter()
+                .map(|subject| CommitBuilder::new(subject).build())
+                .collect(),
+        );
+
+        let categorized = CategorizedCommits {
+            by_category,
+            contributors: Vec::new(),
+        };
+        let result = markdown::render_history_with_options(
+            &categorized,
+            &Platform::Unknown,
+            "HEAD",
+            TEST_RELEASE_DATE,
+            DEFAULT_TEMPLATE,
+            &RenderOptions {
+                stats_style: StatsStyle::Plain,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        rendered.push(result.lines().nth(2).unwrap().to_string());
+    }
+
+    insta::assert_snapshot!(rendered.join("\n"));
+}
+
+#[test]
+fn joins_stats_with_custom_separator_and_style() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![
+            CommitBuilder::new("feat: all the world's a stage").build(),
+            CommitBuilder::new("feat: to be or not to be").build(),
+        ],
+    );
+    by_category.insert(
+        CommitCategory::Fix,
+        vec![CommitBuilder::new("fix: though she be but little, she is fierce").build()],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &Platform::Unknown,
+        "HEAD",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+        &RenderOptions {
+            stats_separator: Some(" | ".to_string()),
+            stats_style: StatsStyle::Bold,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn indents_bodies_as_blockquotes() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![
+            CommitBuilder::new("feat: all the world's a stage")
+                .with_body("And all the men and women merely players.\n\nThey have their exits and their entrances.")
+                .build(),
+            CommitBuilder::new("feat: to be or not to be").build(),
+        ],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &Platform::Unknown,
+        "HEAD",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+        &RenderOptions {
+            body_indent: Some("  > ".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn rewraps_commit_body_prose_at_configured_width() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![
+            CommitBuilder::new("feat: add the quality of mercy soliloquy")
+                .with_body(
+                    "The quality of mercy is not strained. It droppeth as the gentle rain from heaven upon the place beneath. It is twice blessed: it blesseth him that gives and him that takes.
+
+- 'Tis mightiest in the mightiest; it becomes the throned monarch better than his crown.
+
+```
+His sceptre shows the force of temporal power, the attribute to awe and majesty
+```
+
+| Speaker | Play                     |
+|---------|--------------------------|
+| Portia  | The Merchant of Venice   |",
+                )
+                .build(),
+        ],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &Platform::Unknown,
+        "HEAD",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+        &RenderOptions {
+            wrap_width: Some(80),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn preserves_original_line_breaks_when_unwrap_disabled() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![
+            CommitBuilder::new("feat: add the quality of mercy soliloquy")
+                .with_body(
+                    "The quality of mercy is not strained.
+It droppeth as the gentle rain from heaven
+upon the place beneath. It is twice blessed:
+it blesseth him that gives and him that takes.
+
+- 'Tis mightiest in the mightiest; it becomes
+  the throned monarch better than his crown.",
+                )
+                .build(),
+        ],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &Platform::Unknown,
+        "HEAD",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+        &RenderOptions {
+            no_wrap: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn titles_release_note_as_unreleased() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![CommitBuilder::new("feat: what's past is prologue").build()],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &Platform::Unknown,
+        "a1b2c3d",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+        &RenderOptions {
+            unreleased: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn renders_trailers_not_listed_for_stripping() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Fix,
+        vec![
+            CommitBuilder::new("fix: something is rotten in the state of Denmark")
+                .with_body("The rest is silence.")
+                .with_trailer("Reviewed-by", "Horatio <horatio@elsinore.dk>")
+                .with_trailer("Signed-off-by", "Hamlet <hamlet@elsinore.dk>")
+                .build(),
+        ],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &Platform::Unknown,
+        "HEAD",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+        &RenderOptions {
+            strip_trailers: Some(vec!["signed-off-by".to_string()]),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn renders_only_trailers_listed_for_keeping() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![
+            CommitBuilder::new("feat: all the world's a stage")
+                .with_body("And all the men and women merely players.")
+                .with_trailer(
+                    "Co-authored-by",
+                    "Christopher Marlowe <kit@rose-theatre.com>",
+                )
+                .with_trailer("Reviewed-by", "Horatio <horatio@elsinore.dk>")
+                .with_trailer(
+                    "Signed-off-by",
+                    "William Shakespeare <will@globe-theatre.com>",
+                )
+                .build(),
+        ],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &Platform::Unknown,
+        "HEAD",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+        &RenderOptions {
+            keep_trailers: Some(vec!["Co-authored-by".to_string()]),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn renders_hotfixes_between_breaking_changes_and_features() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Breaking,
+        vec![CommitBuilder::new("feat!: the course of true love never did run smooth").build()],
+    );
+    by_category.insert(
+        CommitCategory::Hotfix,
+        vec![
+            CommitBuilder::new("hotfix: once more unto the breach, dear friends")
+                .with_body("Or close the wall up with our English dead.")
+                .build(),
+        ],
+    );
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![CommitBuilder::new("feat: all the world's a stage").build()],
+    );
+    by_category.insert(
+        CommitCategory::Fix,
+        vec![CommitBuilder::new("fix: though she be but little, she is fierce").build()],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let result = markdown::render_history(
+        &categorized,
+        &Platform::Unknown,
+        "HEAD",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+    )
+    .unwrap();
+
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn prepends_front_matter_when_enabled() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![CommitBuilder::new("feat: all the world's a stage").build()],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &Platform::Unknown,
+        "v1.0.0",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+        &RenderOptions {
+            front_matter: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn renders_with_custom_filters_registered_on_tera() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![CommitBuilder::new("feat: all the world's a stage").build()],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+
+    let mut tera = tera::Tera::default();
+    tera.register_filter(
+        "shout",
+        |value: &tera::Value, _: &HashMap<String, tera::Value>| {
+            Ok(tera::Value::String(
+                value.as_str().unwrap_or_default().to_uppercase(),
+            ))
+        },
+    );
+
+    let result = markdown::render_history_with_tera(
+        &categorized,
+        &Platform::Unknown,
+        "HEAD",
+        TEST_RELEASE_DATE,
+        "{% for commit in features %}{{ commit.first_line | strip_conventional_prefix | shout }}{% endfor %}",
+        &RenderOptions::default(),
+        &mut tera,
+    )
+    .unwrap();
+
+    assert_eq!(result, "ALL THE WORLD'S A STAGE");
+}
+
+#[test]
+fn renders_scope_as_badge_in_subject() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![
+            CommitBuilder::new("feat(ui): all the world's a stage").build(),
+            CommitBuilder::new("feat: to be or not to be").build(),
+        ],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let result = markdown::render_history(
+        &categorized,
+        &Platform::Unknown,
+        "HEAD",
+        TEST_RELEASE_DATE,
+        r#"{%- for commit in features %}
+- {{ commit.first_line | scope_badge }}
+{%- endfor %}"#,
+    )
+    .unwrap();
+
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn summarizes_contributors_beyond_maximum() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![
+            CommitBuilder::new("feat: we are such stuff as dreams are made on")
+                .with_contributors(vec!["shakespeare", "marlowe", "jonson", "kyd"])
+                .build(),
+        ],
+    );
+
+    let contributors = [
+        ("shakespeare", 4),
+        ("marlowe", 3),
+        ("jonson", 2),
+        ("kyd", 1),
+    ]
+    .into_iter()
+    .map(|(username, count)| ContributorSummary {
+        username: username.to_string(),
+        avatar_url: "https://avatars.githubusercontent.com/u/2651292?v=4".to_string(),
+        count,
+        is_bot: false,
+        is_ai: false,
+        first_commit_timestamp: 1564567890,
+        last_commit_timestamp: 1564567890,
+        name: None,
+    })
+    .collect();
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors,
+    };
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &Platform::Unknown,
+        "HEAD",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+        &RenderOptions {
+            max_contributors: Some(2),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn renders_links_to_jira_and_platform_issues() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![
+            CommitBuilder::new("feat: GLOBE-42 all the world's a stage")
+                .with_linked_issue(LinkedIssue {
+                    number: 42,
+                    owner: None,
+                    repo: None,
+                    project: Some("GLOBE".to_string()),
+                })
+                .with_linked_issue(LinkedIssue {
+                    number: 7,
+                    owner: None,
+                    repo: None,
+                    project: None,
+                })
+                .build(),
+        ],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &Platform::detect_from_remote_url("https://github.com/globe-theatre/hamlet.git"),
+        "HEAD",
+        TEST_RELEASE_DATE,
+        r#"{%- for commit in features %}
+- {{ commit.first_line | strip_conventional_prefix }}{% for issue in commit.linked_issues %} {{ issue_link(issue=issue) }}{% endfor %}
+{%- endfor %}"#,
+        &RenderOptions {
+            jira_url: Some("https://globe-theatre.atlassian.net/".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn collects_every_undefined_variable_in_template() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![CommitBuilder::new("feat: all the world's a stage").build()],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let undefined = markdown::validate_template_context(
+        "{{ git_ref }} {{ version }}{% if fixes %}{{ fixes }}{% endif %} {{ project.name }}",
+        &categorized,
+        &Platform::Unknown,
+        &RenderOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(undefined.len(), 2);
+    assert!(undefined[0].contains("`version`"));
+    assert!(undefined[1].contains("`project.name`"));
+}
+
+#[test]
+fn renders_tag_message_in_custom_template() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![CommitBuilder::new("feat: all the world's a stage").build()],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let template = "{{ git_ref }}{% if tag_message %}: {{ tag_message }}{% endif %}";
+
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &Platform::Unknown,
+        "v1.0.0",
+        0,
+        template,
+        &RenderOptions {
+            tag_message: Some("The first folio".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(result, "v1.0.0: The first folio");
+
+    let result =
+        markdown::render_history(&categorized, &Platform::Unknown, "v1.0.0", 0, template).unwrap();
+    assert_eq!(result, "v1.0.0");
+}
+
+#[test]
+fn renders_compare_link_before_footer() {
+    let commits = vec![CommitBuilder::new("feat: all the world's a stage").build()];
+    let categorized = CommitAnalyzer::analyze(&commits);
+
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &Platform::Unknown,
+        "v1.1.0",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+        &RenderOptions {
+            compare_url: Some("https://github.com/owner/repo/compare/main...v1.1.0".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn finds_no_undefined_variables_in_default_template() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![CommitBuilder::new("feat: all the world's a stage").build()],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+
+    let platform = Platform::GitHub {
+        url: "https://github.com/shakespeare/globe-theatre".to_string(),
+        api_url: "https://api.github.com".to_string(),
+        owner: "shakespeare".to_string(),
+        repo: "globe-theatre".to_string(),
+        token: None,
+    };
+
+    for options in [
+        RenderOptions::default(),
+        RenderOptions {
+            collapsible: true,
+            compare_url: Some(
+                "https://github.com/shakespeare/globe-theatre/compare/v0.9.0...v1.0.0".to_string(),
+            ),
+            ..Default::default()
+        },
+    ] {
+        assert!(
+            markdown::validate_template_context(
+                DEFAULT_TEMPLATE,
+                &categorized,
+                &platform,
+                &options
+            )
+            .unwrap()
+            .is_empty()
+        );
+    }
+}
+
+#[test]
+fn wraps_sections_in_collapsible_details_when_enabled() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![
+            CommitBuilder::new("feat: all the world's a stage").build(),
+            CommitBuilder::new("feat: to be or not to be").build(),
+        ],
+    );
+    by_category.insert(
+        CommitCategory::Fix,
+        vec![CommitBuilder::new("fix: though she be but little, she is fierce").build()],
+    );
+    by_category.insert(
+        CommitCategory::Dependencies,
+        vec![CommitBuilder::new("chore(deps): all that glisters is not gold").build()],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &Platform::Unknown,
+        "v1.0.0",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+        &RenderOptions {
+            collapsible: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn caps_commits_rendered_per_category() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![
+            CommitBuilder::new("feat: all the world's a stage").build(),
+            CommitBuilder::new("feat: to be or not to be").build(),
+            CommitBuilder::new("feat: now is the winter of our discontent").build(),
+            CommitBuilder::new("feat: brevity is the soul of wit").build(),
+        ],
+    );
+    by_category.insert(
+        CommitCategory::Fix,
+        vec![CommitBuilder::new("fix: though she be but little, she is fierce").build()],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &Platform::Unknown,
+        "v1.0.0",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+        &RenderOptions {
+            max_per_category: Some(2),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn suppresses_footer_when_disabled() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![CommitBuilder::new("feat: all the world's a stage").build()],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &Platform::Unknown,
+        "v1.0.0",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+        &RenderOptions {
+            no_footer: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert!(!result.contains("Generated with"));
+    assert!(result.ends_with("all the world's a stage"));
+}
+
+#[test]
+fn replaces_footer_with_custom_attribution() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![CommitBuilder::new("feat: all the world's a stage").build()],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &Platform::Unknown,
+        "v1.0.0",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+        &RenderOptions {
+            footer: Some("Released by the Globe Theatre".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert!(result.ends_with("all the world's a stage\n\nReleased by the Globe Theatre"));
+}
+
+#[test]
+fn exposes_template_context_as_json() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![CommitBuilder::new("feat: all the world's a stage").build()],
+    );
+    by_category.insert(
+        CommitCategory::Fix,
+        vec![CommitBuilder::new("fix: though she be but little, she is fierce").build()],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let context = markdown::template_context(
+        &categorized,
+        &Platform::Unknown,
+        "v1.0.0",
+        TEST_RELEASE_DATE,
+        &RenderOptions::default(),
+    );
+
+    for key in [
+        "contributors",
+        "features",
+        "fixes",
+        "git_ref",
+        "release_date",
+        "i18n",
+    ] {
+        assert!(context.get(key).is_some(), "missing {}", key);
+    }
+    assert_eq!(context["git_ref"], "v1.0.0");
+    assert_eq!(
+        context["schema_version"],
+        markdown::TEMPLATE_CONTEXT_SCHEMA_VERSION
+    );
+    assert_eq!(
+        context["features"][0]["first_line"],
+        "feat: all the world's a stage"
+    );
+}
+
+#[test]
+fn exposes_resolved_links_in_template_context() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![
+            CommitBuilder::new("feat: all the world's a stage (#15)")
+                .with_hash("8c8a505ad3b0c3d3d9c5e1f0a7b6c4d2e1f0a9b8")
+                .with_linked_issue(LinkedIssue {
+                    number: 7,
+                    owner: None,
+                    repo: None,
+                    project: None,
+                })
+                .with_linked_issue(LinkedIssue {
+                    number: 12,
+                    owner: Some("marlowe".to_string()),
+                    repo: Some("rose-theatre".to_string()),
+                    project: None,
+                })
+                .with_linked_issue(LinkedIssue {
+                    number: 42,
+                    owner: None,
+                    repo: None,
+                    project: Some("GLOBE".to_string()),
+                })
+                .build(),
+        ],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let platform = Platform::GitHub {
+        url: "https://github.com/shakespeare/globe-theatre".to_string(),
+        api_url: "https://api.github.com".to_string(),
+        owner: "shakespeare".to_string(),
+        repo: "globe-theatre".to_string(),
+        token: None,
+    };
+    let context = markdown::template_context(
+        &categorized,
+        &platform,
+        "v1.0.0",
+        TEST_RELEASE_DATE,
+        &RenderOptions {
+            jira_url: Some("https://globe.atlassian.net".to_string()),
+            ..Default::default()
+        },
+    );
+
+    insta::assert_snapshot!(serde_json::to_string_pretty(&context["features"]).unwrap());
+}
+
+#[test]
+fn checks_rendered_note_against_existing_file() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![CommitBuilder::new("feat: all the world's a stage").build()],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let rendered = markdown::render_history(
+        &categorized,
+        &Platform::Unknown,
+        "v1.0.0",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+    )
+    .unwrap();
+
+    let matching = format!("{}\n", rendered);
+    assert_eq!(
+        markdown::diff_release_note(&matching, &rendered, "CHANGELOG.md"),
+        None
+    );
+
+    let stale = rendered.replace("all the world's a stage", "to be or not to be");
+    insta::assert_snapshot!(
+        markdown::diff_release_note(&stale, &rendered, "CHANGELOG.md").unwrap()
+    );
+}
+
+#[test]
+fn links_each_pull_request_referenced_by_subject() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![CommitBuilder::new("feat: all the world's a stage (#1, #2)").build()],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let platform = Platform::GitHub {
+        url: "https://github.com/shakespeare/globe-theatre".to_string(),
+        api_url: "https://api.github.com".to_string(),
+        owner: "shakespeare".to_string(),
+        repo: "globe-theatre".to_string(),
+        token: None,
+    };
+    let result = markdown::render_history(
+        &categorized,
+        &platform,
+        "HEAD",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+    )
+    .unwrap();
+
+    assert!(result.contains(
+        "all the world's a stage ([#1](https://github.com/shakespeare/globe-theatre/pull/1), [#2](https://github.com/shakespeare/globe-theatre/pull/2))"
+    ));
+}
+
+#[test]
+fn links_the_pull_request_a_revert_reverts() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Revert,
+        vec![
+            CommitBuilder::new(r#"Revert "feat: all the world's a stage" (#15)"#)
+                .with_reverted_pull_request(12)
+                .build(),
+        ],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let platform = Platform::GitHub {
+        url: "https://github.com/shakespeare/globe-theatre".to_string(),
+        api_url: "https://api.github.com".to_string(),
+        owner: "shakespeare".to_string(),
+        repo: "globe-theatre".to_string(),
+        token: None,
+    };
+    let result = markdown::render_history(
+        &categorized,
+        &platform,
+        "HEAD",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+    )
+    .unwrap();
+
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn renders_placeholder_for_empty_sections_when_always_shown() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![CommitBuilder::new("feat: all the world's a stage").build()],
+    );
+    by_category.insert(
+        CommitCategory::Dependencies,
+        vec![CommitBuilder::new("chore(deps): all that glisters is not gold").build()],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &Platform::Unknown,
+        "v1.0.0",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+        &RenderOptions {
+            always_show_sections: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn merges_dependency_updates_from_the_same_pull_request() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Dependencies,
+        vec![
+            CommitBuilder::new("chore(deps): bump serde to 1.0.200 (#12)").build(),
+            CommitBuilder::new("chore(deps): bump tera to 1.20.1 (#12)").build(),
+            CommitBuilder::new("chore(deps): bump regex to 1.11.0 (#12)").build(),
+            CommitBuilder::new("chore(deps): bump git2 to 0.21.0 (#15)").build(),
+        ],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &Platform::Unknown,
+        "v1.0.0",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+        &RenderOptions {
+            merge_dependency_prs: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn links_merged_dependency_updates_like_other_pull_requests() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Dependencies,
+        vec![
+            CommitBuilder::new("chore(deps): bump serde to 1.0.200 (#12)").build(),
+            CommitBuilder::new("chore(deps): bump tera to 1.20.1 (#12)").build(),
+            CommitBuilder::new("chore(deps): bump git2 to 0.21.0 (#15)").build(),
+        ],
+    );
+
+    let platform = Platform::GitHub {
+        url: "https://github.com/shakespeare/globe-theatre".to_string(),
+        api_url: "https://api.github.com".to_string(),
+        owner: "shakespeare".to_string(),
+        repo: "globe-theatre".to_string(),
+        token: None,
+    };
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &platform,
+        "v1.0.0",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+        &RenderOptions {
+            merge_dependency_prs: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert!(result.contains(
+        "bump serde to 1.0.200, bump tera to 1.20.1 ([#12](https://github.com/shakespeare/globe-theatre/pull/12))"
+    ));
+    assert!(result.contains(
+        "bump git2 to 0.21.0 ([#15](https://github.com/shakespeare/globe-theatre/pull/15))"
+    ));
+}
+
+#[test]
+fn renders_contributor_commit_counts_as_badges() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![
+            CommitBuilder::new("feat: the course of true love never did run smooth")
+                .with_contributor("shakespeare")
+                .with_timestamp(1748390400)
+                .build(),
+            CommitBuilder::new("feat: some Cupid kills with arrows, some with traps")
+                .with_contributor("shakespeare")
+                .with_timestamp(1748476800)
+                .build(),
+        ],
+    );
+
+    let contributors = vec![ContributorSummary {
+        username: "shakespeare".to_string(),
+        avatar_url: "https://avatars.githubusercontent.com/u/2651292?v=4".to_string(),
+        count: 2,
+        is_bot: false,
+        is_ai: false,
+        first_commit_timestamp: 1748390400,
+        last_commit_timestamp: 1748476800,
+        name: None,
+    }];
+
+    let platform = Platform::GitHub {
+        url: "https://github.com/shakespeare/globe-theatre".to_string(),
+        api_url: "https://api.github.com".to_string(),
+        owner: "shakespeare".to_string(),
+        repo: "globe-theatre".to_string(),
+        token: None,
+    };
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors,
+    };
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &platform,
+        "v1.0.0",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+        &RenderOptions {
+            commit_badges: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn renders_singular_badge_for_a_single_commit() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![
+            CommitBuilder::new("feat: the course of true love never did run smooth")
+                .with_contributor("shakespeare")
+                .build(),
+        ],
+    );
+
+    let contributors = vec![ContributorSummary {
+        username: "shakespeare".to_string(),
+        avatar_url: "https://avatars.githubusercontent.com/u/2651292?v=4".to_string(),
+        count: 1,
+        is_bot: false,
+        is_ai: false,
+        first_commit_timestamp: 0,
+        last_commit_timestamp: 0,
+        name: None,
+    }];
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors,
+    };
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &Platform::Unknown,
+        "v1.0.0",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+        &RenderOptions {
+            commit_badges: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert!(result.contains("![1 commit](https://img.shields.io/badge/commit-1-blue)"));
+}
+
+#[test]
+fn renders_text_only_contributors_without_avatars() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![
+            CommitBuilder::new("feat: the course of true love never did run smooth")
+                .with_contributor("shakespeare")
+                .with_timestamp(1748390400)
+                .build(),
+            CommitBuilder::new("feat: some Cupid kills with arrows, some with traps")
+                .with_contributor("shakespeare")
+                .with_timestamp(1748476800)
+                .build(),
+        ],
+    );
+
+    let contributors = vec![ContributorSummary {
+        username: "shakespeare".to_string(),
+        avatar_url: "https://avatars.githubusercontent.com/u/2651292?v=4".to_string(),
+        count: 2,
+        is_bot: false,
+        is_ai: false,
+        first_commit_timestamp: 1748390400,
+        last_commit_timestamp: 1748476800,
+        name: None,
+    }];
+
+    let platform = Platform::GitHub {
+        url: "https://github.com/shakespeare/globe-theatre".to_string(),
+        api_url: "https://api.github.com".to_string(),
+        owner: "shakespeare".to_string(),
+        repo: "globe-theatre".to_string(),
+        token: None,
+    };
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors,
+    };
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &platform,
+        "v1.0.0",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+        &RenderOptions {
+            no_avatars: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn shortens_displayed_hashes_but_links_full_hash() {
+    let platform = Platform::GitHub {
+        url: "https://github.com/shakespeare/globe-theatre".to_string(),
+        api_url: "https://api.github.com".to_string(),
+        owner: "shakespeare".to_string(),
+        repo: "globe-theatre".to_string(),
+        token: None,
+    };
+
+    let mut rendered = Vec::new();
+    for hash_length in [None, Some(12)] {
+        let mut by_category = HashMap::new();
+        by_category.insert(
+            CommitCategory::Feature,
+            vec![
+                CommitBuilder::new("feat: all the world's a stage")
+                    .with_hash("8c8a505ad3b0c3d3d9c5e1f0a7b6c4d2e1f0a9b8")
+                    .build(),
+            ],
+        );
+
+        let categorized = CategorizedCommits {
+            by_category,
+            contributors: Vec::new(),
+        };
+        let result = markdown::render_history_with_options(
+            &categorized,
+            &platform,
+            "v1.0.0",
+            TEST_RELEASE_DATE,
+            DEFAULT_TEMPLATE,
+            &RenderOptions {
+                hash_length,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        rendered.extend(
+            result
+                .lines()
+                .filter(|line| line.starts_with("- "))
+                .map(str::to_string),
+        );
+    }
+
+    insta::assert_snapshot!(rendered.join("\n"));
+}
+
+#[test]
+fn keeps_inline_mentions_without_contributors_section() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![
+            CommitBuilder::new("feat: the course of true love never did run smooth")
+                .with_contributor("shakespeare")
+                .with_timestamp(1748390400)
+                .build(),
+            CommitBuilder::new("feat: some Cupid kills with arrows, some with traps")
+                .with_contributor("shakespeare")
+                .with_timestamp(1748476800)
+                .build(),
+        ],
+    );
+
+    let contributors = vec![ContributorSummary {
+        username: "shakespeare".to_string(),
+        avatar_url: "https://avatars.githubusercontent.com/u/2651292?v=4".to_string(),
+        count: 2,
+        is_bot: false,
+        is_ai: false,
+        first_commit_timestamp: 1748390400,
+        last_commit_timestamp: 1748476800,
+        name: None,
+    }];
+
+    let platform = Platform::GitHub {
+        url: "https://github.com/shakespeare/globe-theatre".to_string(),
+        api_url: "https://api.github.com".to_string(),
+        owner: "shakespeare".to_string(),
+        repo: "globe-theatre".to_string(),
+        token: None,
+    };
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors,
+    };
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &platform,
+        "v1.0.0",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+        &RenderOptions {
+            no_contributors_section: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn mentions_usernames_when_display_names_are_unresolved() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![
+            CommitBuilder::new("feat: the course of true love never did run smooth")
+                .with_contributor("shakespeare")
+                .build(),
+        ],
+    );
+
+    let platform = Platform::GitHub {
+        url: "https://github.com/shakespeare/globe-theatre".to_string(),
+        api_url: "https://api.github.com".to_string(),
+        owner: "shakespeare".to_string(),
+        repo: "globe-theatre".to_string(),
+        token: None,
+    };
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &platform,
+        "v1.0.0",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+        &RenderOptions {
+            display_names: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert!(
+        result.contains("the course of true love never did run smooth (@shakespeare)"),
+        "{}",
+        result
+    );
+}
+
+#[test]
+fn escapes_markdown_within_display_names() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![
+            CommitBuilder::new("feat: the course of true love never did run smooth")
+                .with_named_contributor("iago", "](https://evil.example) <img src=x> *Iago*")
+                .build(),
+        ],
+    );
+
+    let platform = Platform::GitHub {
+        url: "https://github.com/shakespeare/globe-theatre".to_string(),
+        api_url: "https://api.github.com".to_string(),
+        owner: "shakespeare".to_string(),
+        repo: "globe-theatre".to_string(),
+        token: None,
+    };
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &platform,
+        "v1.0.0",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+        &RenderOptions {
+            display_names: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert!(
+        result.contains(
+            r"([\]\(https://evil.example\) \<img src=x\> \*Iago\*](https://github.com/iago))"
+        ),
+        "{}",
+        result
+    );
+}
+
+#[test]
+fn renders_contributor_display_names_linked_to_profiles() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![
+            CommitBuilder::new("feat: the course of true love never did run smooth")
+                .with_named_contributor("shakespeare", "William Shakespeare")
+                .with_timestamp(1748390400)
+                .build(),
+            CommitBuilder::new("feat: some Cupid kills with arrows, some with traps")
+                .with_named_contributor("shakespeare", "William Shakespeare")
+                .with_timestamp(1748476800)
+                .build(),
+        ],
+    );
+
+    let contributors = vec![ContributorSummary {
+        username: "shakespeare".to_string(),
+        avatar_url: "https://avatars.githubusercontent.com/u/2651292?v=4".to_string(),
+        count: 2,
+        is_bot: false,
+        is_ai: false,
+        first_commit_timestamp: 1748390400,
+        last_commit_timestamp: 1748476800,
+        name: Some("William Shakespeare".to_string()),
+    }];
+
+    let platform = Platform::GitHub {
+        url: "https://github.com/shakespeare/globe-theatre".to_string(),
+        api_url: "https://api.github.com".to_string(),
+        owner: "shakespeare".to_string(),
+        repo: "globe-theatre".to_string(),
+        token: None,
+    };
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors,
+    };
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &platform,
+        "v1.0.0",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+        &RenderOptions {
+            display_names: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn renders_without_dates_from_zero_timestamps() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![
+            CommitBuilder::new("feat: the rest is silence")
+                .with_contributor("shakespeare")
+                .with_timestamp(0)
+                .build(),
+        ],
+    );
+
+    let contributors = vec![ContributorSummary {
+        username: "shakespeare".to_string(),
+        avatar_url: "https://avatars.githubusercontent.com/u/2651292?v=4".to_string(),
+        count: 1,
+        is_bot: false,
+        is_ai: false,
+        first_commit_timestamp: 0,
+        last_commit_timestamp: 0,
+        name: None,
+    }];
+
+    let platform = Platform::GitHub {
+        url: "https://github.com/shakespeare/globe-theatre".to_string(),
+        api_url: "https://api.github.com".to_string(),
+        owner: "shakespeare".to_string(),
+        repo: "globe-theatre".to_string(),
+        token: None,
+    };
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors,
+    };
+    let result =
+        markdown::render_history(&categorized, &platform, "v1.0.0", 0, DEFAULT_TEMPLATE).unwrap();
+
+    assert!(result.starts_with("## v1.0.0 - "));
+    assert!(!result.contains("1970"));
+    assert!(result.contains("@shakespeare (**`1`** commit)"));
+}
+
+#[test]
+fn dates_release_from_newest_commit_when_release_date_is_missing() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![
+            CommitBuilder::new("feat: the rest is silence")
+                .with_timestamp(1_717_200_000)
+                .build(),
+            CommitBuilder::new("feat: good night, sweet prince")
+                .with_timestamp(1_717_372_800)
+                .build(),
+        ],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: vec![],
+    };
+    let result = markdown::render_history(
+        &categorized,
+        &Platform::Unknown,
+        "v1.0.0",
+        0,
+        DEFAULT_TEMPLATE,
+    )
+    .unwrap();
+
+    assert!(result.starts_with("## v1.0.0 - June 03, 2024"));
+}
+
+#[test]
+fn links_stats_to_anchors_of_renamed_sections() {
+    let mut by_category = HashMap::new();
+
+    by_category.insert(
+        CommitCategory::Breaking,
+        vec![CommitBuilder::new("feat!: off with his head").build()],
+    );
+    by_category.insert(
+        CommitCategory::Feature,
+        vec![CommitBuilder::new("feat: all the world's a stage").build()],
+    );
+    by_category.insert(
+        CommitCategory::Fix,
+        vec![CommitBuilder::new("fix: though she be but little, she is fierce").build()],
+    );
+
+    let categorized = CategorizedCommits {
+        by_category,
+        contributors: Vec::new(),
+    };
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &Platform::Unknown,
+        "v1.0.0",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+        &RenderOptions {
+            section_titles: HashMap::from([
+                (CommitCategory::Feature, "What's New".to_string()),
+                (CommitCategory::Fix, "Squashed Bugs".to_string()),
+            ]),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn groups_other_changes_by_conventional_type() {
+    let commits = vec![
+        CommitBuilder::new("wip: the readiness is all").build(),
+        CommitBuilder::new("release: exit, pursued by a bear").build(),
+        CommitBuilder::new("there is a tide in the affairs of men").build(),
+        CommitBuilder::new("wip: the play's the thing").build(),
+    ];
+    let categorized = CommitAnalyzer::analyze(&commits);
+
+    let result = markdown::render_history(
+        &categorized,
+        &Platform::Unknown,
+        "HEAD",
+        TEST_RELEASE_DATE,
+        r#"## Other Changes
+{%- for group in other_by_type %}
+
+### {% if group.type %}{{ group.type }}{% else %}uncategorized{% endif %}
+{%- for commit in group.commits %}
+- {{ commit.first_line | strip_conventional_prefix }}
+{%- endfor %}
+{%- endfor %}"#,
+    )
+    .unwrap();
+
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn marks_breaking_changes_inline_within_their_type_section() {
+    let commits = vec![
+        CommitBuilder::new("feat!: off with his head").build(),
+        CommitBuilder::new("feat: all the world's a stage").build(),
+        CommitBuilder::new("fix: the lady doth protest too much")
+            .with_trailer("BREAKING CHANGE", "the quality of mercy is not strained")
+            .build(),
+    ];
+    let categorized = CommitAnalyzer::analyze_with_options(
+        &commits,
+        &AnalyzerOptions {
+            breaking_inline: true,
+            ..Default::default()
+        },
+    );
+
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &Platform::Unknown,
+        "v1.0.0",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+        &RenderOptions::default(),
+    )
+    .unwrap();
+
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn renders_deprecations_section_with_description() {
+    let commits = vec![
+        CommitBuilder::new("feat: a new soliloquy for the third act")
+            .with_body("Now is the winter of our discontent.")
+            .with_trailer("Deprecated", "the old soliloquy will be cut next season")
+            .build(),
+        CommitBuilder::new("fix: the lady doth protest too much").build(),
+    ];
+    let categorized = CommitAnalyzer::analyze(&commits);
+
+    let result = markdown::render_history(
+        &categorized,
+        &Platform::Unknown,
+        "v1.0.0",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+    )
+    .unwrap();
+
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn renders_heading_date_with_custom_format() {
+    let commits = vec![CommitBuilder::new("feat: all the world's a stage").build()];
+    let categorized = CommitAnalyzer::analyze(&commits);
+
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &Platform::Unknown,
+        "v1.0.0",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+        &RenderOptions {
+            date_format: Some("%Y-%m-%d".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn fails_on_invalid_date_format() {
+    let commits = vec![CommitBuilder::new("feat: all the world's a stage").build()];
+    let categorized = CommitAnalyzer::analyze(&commits);
+
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &Platform::Unknown,
+        "v1.0.0",
+        TEST_RELEASE_DATE,
+        DEFAULT_TEMPLATE,
+        &RenderOptions {
+            date_format: Some("%Y-%Q".to_string()),
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "'%Y-%Q' is not a valid date format"
+    );
+}
+
+#[test]
+fn names_the_unwrap_filter_when_unwrapping_a_non_string_without_wrapping() {
+    let commits = vec![CommitBuilder::new("feat: all the world's a stage").build()];
+    let categorized = CommitAnalyzer::analyze(&commits);
+
+    let result = markdown::render_history_with_options(
+        &categorized,
+        &Platform::Unknown,
+        "v1.0.0",
+        TEST_RELEASE_DATE,
+        "{{ 42 | unwrap }}",
+        &RenderOptions {
+            no_wrap: true,
+            ..Default::default()
+        },
+    );
+
+    assert!(
+        format!("{:#}", result.unwrap_err()).contains("the unwrap filter requires a string value")
+    );
+}
@@ -1,4 +1,4 @@
-use release_note::platform::Platform;
+use release_note::platform::{Platform, PlatformKind};
 use std::env;
 
 struct EnvVars {
@@ -15,12 +15,17 @@ impl EnvVars {
             "GITHUB_API_URL",
             "GITHUB_REPOSITORY",
             "GITHUB_TOKEN",
+            "GH_TOKEN",
             "GITLAB_CI",
             "CI_PROJECT_URL",
             "CI_API_V4_URL",
             "CI_API_GRAPHQL_URL",
             "CI_PROJECT_PATH",
             "GITLAB_TOKEN",
+            "GITEA_ACTIONS",
+            "FORGEJO_ACTIONS",
+            "GITEA_TOKEN",
+            "FORGEJO_TOKEN",
             "RELEASE_NOTE_TRUSTED_HOST",
         ];
 
@@ -359,6 +364,27 @@ fn detects_github_token_from_env() {
     );
 }
 
+#[test]
+fn detects_github_cli_token_from_env() {
+    let _env = EnvVars::set(&[
+        ("GITHUB_ACTIONS", "true"),
+        ("GITHUB_SERVER_URL", "https://github.com"),
+        ("GITHUB_REPOSITORY", "owner/repo"),
+        ("GH_TOKEN", "gho_cli_token_456"),
+    ]);
+
+    assert_eq!(
+        Platform::detect(None, &[]),
+        Platform::GitHub {
+            url: "https://github.com/owner/repo".to_string(),
+            api_url: "https://api.github.com".to_string(),
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+            token: Some("gho_cli_token_456".to_string()),
+        }
+    );
+}
+
 #[test]
 fn detects_gitlab_token_from_env() {
     let _env = EnvVars::set(&[
@@ -510,3 +536,398 @@ fn attaches_token_for_trusted_self_hosted_gitlab() {
         }
     );
 }
+
+#[test]
+fn trusts_only_public_or_listed_remote_hosts() {
+    assert!(Platform::is_trusted_remote(
+        "https://github.com/owner/repo.git",
+        &[]
+    ));
+    assert!(Platform::is_trusted_remote(
+        "git@gitlab.com:owner/repo.git",
+        &[]
+    ));
+    assert!(!Platform::is_trusted_remote(
+        "https://evil.example.com/owner/repo.git",
+        &[]
+    ));
+    assert!(!Platform::is_trusted_remote(
+        "https://github.com.evil.example/owner/repo.git",
+        &[]
+    ));
+    assert!(Platform::is_trusted_remote(
+        "https://git.mycorp.io/owner/repo.git",
+        &["git.mycorp.io".to_string()],
+    ));
+    assert!(!Platform::is_trusted_remote("file:///tmp/repo", &[]));
+}
+
+#[test]
+fn withholds_ci_token_from_untrusted_remote_url() {
+    let _env = EnvVars::set(&[
+        ("GITHUB_ACTIONS", "true"),
+        ("GITHUB_SERVER_URL", "https://github.com"),
+        ("GITHUB_REPOSITORY", "owner/repo"),
+        ("GITHUB_TOKEN", "ghp_secret"),
+    ]);
+
+    let platform = Platform::detect_from_remote_url("https://github.evil.example/owner/repo.git")
+        .with_trusted_token(&[]);
+    assert_eq!(platform.token(), None);
+}
+
+#[test]
+fn constructs_github_from_config_without_detection() {
+    let _clean_env = EnvVars::clear_ci_env();
+
+    assert_eq!(
+        Platform::from_config(
+            PlatformKind::GitHub,
+            "owner",
+            "repo",
+            "https://api.github.com",
+            Some("ghp_explicit".to_string()),
+        ),
+        Platform::GitHub {
+            url: "https://github.com/owner/repo".to_string(),
+            api_url: "https://api.github.com".to_string(),
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+            token: Some("ghp_explicit".to_string()),
+        }
+    );
+}
+
+#[test]
+fn constructs_self_hosted_gitlab_from_config_without_detection() {
+    let _clean_env = EnvVars::clear_ci_env();
+
+    assert_eq!(
+        Platform::from_config(
+            PlatformKind::GitLab,
+            "owner/group",
+            "repo",
+            "https://gitlab.example.com/api/v4",
+            None,
+        ),
+        Platform::GitLab {
+            url: "https://gitlab.example.com/owner/group/repo".to_string(),
+            api_url: "https://gitlab.example.com/api/v4".to_string(),
+            graphql_url: "https://gitlab.example.com/api/graphql".to_string(),
+            project_path: "owner/group/repo".to_string(),
+            token: None,
+        }
+    );
+}
+
+#[test]
+fn constructs_self_hosted_gitea_from_config_without_detection() {
+    let _clean_env = EnvVars::clear_ci_env();
+
+    assert_eq!(
+        Platform::from_config(
+            PlatformKind::Gitea,
+            "owner",
+            "repo",
+            "https://gitea.example.com/api/v1/",
+            Some("gitea_explicit".to_string()),
+        ),
+        Platform::Gitea {
+            url: "https://gitea.example.com/owner/repo".to_string(),
+            api_url: "https://gitea.example.com/api/v1".to_string(),
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+            token: Some("gitea_explicit".to_string()),
+        }
+    );
+}
+
+#[test]
+fn detects_from_remote_url_ignoring_ci_env() {
+    let _env = EnvVars::set(&[
+        ("GITLAB_CI", "true"),
+        ("CI_PROJECT_URL", "https://gitlab.com/owner/repo"),
+        ("CI_PROJECT_PATH", "owner/repo"),
+        ("GITHUB_TOKEN", "ghp_ignored"),
+    ]);
+
+    assert_eq!(
+        Platform::detect_from_remote_url("git@github.com:owner/repo.git"),
+        Platform::GitHub {
+            url: "https://github.com/owner/repo".to_string(),
+            api_url: "https://api.github.com".to_string(),
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+            token: None,
+        }
+    );
+}
+
+#[test]
+fn detects_self_hosted_github_from_base_url_over_ci_env() {
+    let _env = EnvVars::set(&[
+        ("GITHUB_ACTIONS", "true"),
+        ("GITHUB_REPOSITORY", "owner/repo"),
+        ("GITHUB_TOKEN", "ghp_enterprise"),
+    ]);
+
+    assert_eq!(
+        Platform::detect_from_base_url(
+            "https://github.example.com/owner/repo",
+            &["github.example.com".to_string()]
+        ),
+        Platform::GitHub {
+            url: "https://github.example.com/owner/repo".to_string(),
+            api_url: "https://github.example.com/api/v3".to_string(),
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+            token: Some("ghp_enterprise".to_string()),
+        }
+    );
+}
+
+#[test]
+fn builds_gitlab_urls_for_nested_subgroup_project() {
+    let _clean_env = EnvVars::clear_ci_env();
+
+    let platform = Platform::detect(Some("git@gitlab.com:owner/group/subgroup/repo.git"), &[]);
+
+    assert_eq!(
+        platform.commit_url("a1b2c3d"),
+        Some("https://gitlab.com/owner/group/subgroup/repo/-/commit/a1b2c3d".to_string())
+    );
+    assert_eq!(
+        platform.issue_url(42),
+        Some("https://gitlab.com/owner/group/subgroup/repo/-/issues/42".to_string())
+    );
+}
+
+#[test]
+fn builds_github_issue_url() {
+    let _clean_env = EnvVars::clear_ci_env();
+
+    let platform = Platform::detect(Some("https://github.com/owner/repo.git"), &[]);
+
+    assert_eq!(
+        platform.issue_url(7),
+        Some("https://github.com/owner/repo/issues/7".to_string())
+    );
+}
+
+#[test]
+fn builds_compare_urls() {
+    let _clean_env = EnvVars::clear_ci_env();
+
+    let github = Platform::detect(Some("https://github.com/owner/repo.git"), &[]);
+    let gitlab = Platform::detect(Some("https://gitlab.com/owner/repo.git"), &[]);
+
+    assert_eq!(
+        github.compare_url("main", "feature/sonnets"),
+        Some("https://github.com/owner/repo/compare/main...feature/sonnets".to_string())
+    );
+    assert_eq!(
+        gitlab.compare_url("v1.0.0", "v1.1.0"),
+        Some("https://gitlab.com/owner/repo/-/compare/v1.0.0...v1.1.0".to_string())
+    );
+    assert_eq!(Platform::Unknown.compare_url("main", "v1.1.0"), None);
+}
+
+#[test]
+fn builds_links_from_web_url_override_keeping_detected_api_host() {
+    let _clean_env = EnvVars::clear_ci_env();
+
+    let platform = Platform::detect(Some("git@github.mirror.internal:owner/repo.git"), &[])
+        .with_web_url("https://github.com/");
+
+    assert_eq!(platform.url(), "https://github.com/owner/repo");
+    assert_eq!(platform.api_url(), "https://github.mirror.internal/api/v3");
+    assert_eq!(
+        platform.commit_url("a1b2c3d"),
+        Some("https://github.com/owner/repo/commit/a1b2c3d".to_string())
+    );
+    assert_eq!(
+        platform.issue_url(7),
+        Some("https://github.com/owner/repo/issues/7".to_string())
+    );
+}
+
+#[test]
+fn derives_web_url_from_ssh_origin_of_unknown_host() {
+    let _clean_env = EnvVars::clear_ci_env();
+
+    let origin_url = "git@git.globe-theatre.com:company/hamlet.git";
+
+    assert_eq!(Platform::detect(Some(origin_url), &[]), Platform::Unknown);
+    assert_eq!(
+        Platform::derive_web_url(origin_url),
+        Some("https://git.globe-theatre.com/company/hamlet".to_string())
+    );
+}
+
+#[test]
+fn detects_gitea_from_https_url() {
+    let _clean_env = EnvVars::clear_ci_env();
+
+    assert_eq!(
+        Platform::detect(Some("https://gitea.com/owner/repo.git"), &[]),
+        Platform::Gitea {
+            url: "https://gitea.com/owner/repo".to_string(),
+            api_url: "https://gitea.com/api/v1".to_string(),
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+            token: None,
+        }
+    );
+}
+
+#[test]
+fn detects_codeberg_from_ssh_url() {
+    let _clean_env = EnvVars::clear_ci_env();
+
+    assert_eq!(
+        Platform::detect(Some("git@codeberg.org:owner/repo.git"), &[]),
+        Platform::Gitea {
+            url: "https://codeberg.org/owner/repo".to_string(),
+            api_url: "https://codeberg.org/api/v1".to_string(),
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+            token: None,
+        }
+    );
+}
+
+#[test]
+fn detects_gitea_from_actions_env() {
+    let _env = EnvVars::set(&[
+        ("GITEA_ACTIONS", "true"),
+        ("GITHUB_ACTIONS", "true"),
+        ("GITHUB_SERVER_URL", "https://gitea.company.com"),
+        ("GITHUB_REPOSITORY", "owner/repo"),
+        ("GITEA_TOKEN", "gitea_test_token_123"),
+    ]);
+
+    assert_eq!(
+        Platform::detect(None, &[]),
+        Platform::Gitea {
+            url: "https://gitea.company.com/owner/repo".to_string(),
+            api_url: "https://gitea.company.com/api/v1".to_string(),
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+            token: Some("gitea_test_token_123".to_string()),
+        }
+    );
+}
+
+#[test]
+fn attaches_forgejo_token_for_trusted_self_hosted_forgejo() {
+    let _env = EnvVars::set(&[("FORGEJO_TOKEN", "forgejo_test_token_123")]);
+
+    let platform = Platform::detect(
+        Some("https://forgejo.company.com/owner/repo.git"),
+        &["forgejo.company.com".to_string()],
+    );
+
+    assert_eq!(platform.token(), Some("forgejo_test_token_123"));
+}
+
+#[test]
+fn builds_gitea_commit_and_issue_urls() {
+    let _clean_env = EnvVars::clear_ci_env();
+
+    let platform = Platform::detect(Some("https://gitea.com/owner/repo.git"), &[]);
+
+    assert_eq!(
+        platform.commit_url("a1b2c3d"),
+        Some("https://gitea.com/owner/repo/commit/a1b2c3d".to_string())
+    );
+    assert_eq!(
+        platform.issue_url(7),
+        Some("https://gitea.com/owner/repo/issues/7".to_string())
+    );
+}
+
+#[test]
+fn builds_gitea_contributor_commits_url() {
+    let _clean_env = EnvVars::clear_ci_env();
+
+    let platform = Platform::detect(Some("https://codeberg.org/owner/repo.git"), &[]);
+
+    assert_eq!(
+        platform.commits_url("main", "hamlet", "2025-01-01", "2025-02-01"),
+        Some(
+            "https://codeberg.org/owner/repo/commits/branch/main/search?q=author:hamlet+after:2025-01-01+before:2025-02-01"
+                .to_string()
+        )
+    );
+}
+
+#[test]
+fn overrides_token_from_env_with_explicit_token() {
+    let _env = EnvVars::set(&[("GITHUB_TOKEN", "ghp_env_token")]);
+
+    let platform = Platform::detect(Some("https://github.com/owner/repo.git"), &[])
+        .with_token("ghp_cli_token".to_string());
+
+    assert_eq!(platform.token(), Some("ghp_cli_token"));
+    assert_eq!(platform.api_url(), "https://api.github.com");
+}
+
+#[test]
+fn detects_github_enterprise_from_http_url() {
+    let _clean_env = EnvVars::clear_ci_env();
+
+    assert_eq!(
+        Platform::detect_from_remote_url("http://github.company.com/owner/repo.git"),
+        Platform::GitHub {
+            url: "http://github.company.com/owner/repo".to_string(),
+            api_url: "http://github.company.com/api/v3".to_string(),
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+            token: None,
+        }
+    );
+}
+
+#[test]
+fn detects_self_hosted_gitlab_from_http_url() {
+    let _clean_env = EnvVars::clear_ci_env();
+
+    assert_eq!(
+        Platform::detect_from_remote_url("http://gitlab.company.com/group/project.git"),
+        Platform::GitLab {
+            url: "http://gitlab.company.com/group/project".to_string(),
+            api_url: "http://gitlab.company.com/api/v4".to_string(),
+            graphql_url: "http://gitlab.company.com/api/graphql".to_string(),
+            project_path: "group/project".to_string(),
+            token: None,
+        }
+    );
+}
+
+#[test]
+fn detects_from_env_only_when_running_in_ci() {
+    let _env = EnvVars::set(&[
+        ("GITHUB_ACTIONS", "true"),
+        ("GITHUB_SERVER_URL", "https://github.com"),
+        ("GITHUB_API_URL", "https://api.github.com"),
+        ("GITHUB_REPOSITORY", "ci-owner/ci-repo"),
+    ]);
+
+    assert_eq!(
+        Platform::detect_from_env_only(),
+        Some(Platform::GitHub {
+            url: "https://github.com/ci-owner/ci-repo".to_string(),
+            api_url: "https://api.github.com".to_string(),
+            owner: "ci-owner".to_string(),
+            repo: "ci-repo".to_string(),
+            token: None,
+        })
+    );
+}
+
+#[test]
+fn detects_nothing_from_env_only_outside_ci() {
+    let _clean_env = EnvVars::clear_ci_env();
+
+    assert_eq!(Platform::detect_from_env_only(), None);
+}
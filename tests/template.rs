@@ -1,5 +1,6 @@
 use release_note::template::{DEFAULT_TEMPLATE, TemplateResolver};
 use std::fs;
+use std::path::PathBuf;
 use tempfile::TempDir;
 
 #[test]
@@ -22,6 +23,27 @@ fn uses_template_from_root_as_highest_priority() {
     assert_eq!(template, root_template);
 }
 
+#[test]
+fn uses_template_from_custom_directory_over_root() {
+    let temp_dir = TempDir::new().unwrap();
+    let template_dir = TempDir::new().unwrap();
+    let root_template = "# Root template";
+    let custom_template = "# Custom template";
+
+    fs::write(temp_dir.path().join("release-note.tera"), root_template).unwrap();
+    fs::write(
+        template_dir.path().join("release-note.tera"),
+        custom_template,
+    )
+    .unwrap();
+
+    let resolver = TemplateResolver::new(temp_dir.path().to_path_buf())
+        .with_template_dir(template_dir.path().to_path_buf());
+    let template = resolver.resolve().unwrap();
+
+    assert_eq!(template, custom_template);
+}
+
 #[test]
 fn uses_template_from_github_directory() {
     let temp_dir = TempDir::new().unwrap();
@@ -82,3 +104,19 @@ fn fails_on_template_with_syntax_errors() {
     let error = result.unwrap_err().to_string();
     assert!(error.contains("invalid template syntax"));
 }
+
+#[test]
+fn lists_template_candidates_in_search_order() {
+    let working_dir = PathBuf::from("/globe-theatre");
+
+    let resolver = TemplateResolver::new(working_dir.clone());
+
+    assert_eq!(
+        resolver.list_candidates(),
+        vec![
+            working_dir.join("release-note.tera"),
+            working_dir.join(".github/release-note.tera"),
+            working_dir.join(".gitlab/release-note.tera"),
+        ]
+    );
+}